@@ -1,12 +1,18 @@
+use crate::commands::clubs::{set_current_club, sync_athlete_club_name};
+use crate::commands::goals::evaluation::evaluate_goals;
+use crate::commands::photos::{profile_thumbnail_path, remove_photo_files, remove_profile_photos, remove_unused_photo_files};
+use crate::commands::results::records::recalculate_records;
 use crate::database::get_pool;
-use crate::types::{Athlete, AthleteStats, AthleteWithStats, CreateAthlete, UpdateAthlete};
+use crate::types::{Athlete, AthleteStats, AthleteWithStats, CreateAthlete, MergeAthletesSummary, UpdateAthlete};
+use chrono::{Datelike, NaiveDate};
 use sqlx::Row;
 use tauri::AppHandle;
 
 /// SQL query for fetching athlete with stats using subqueries (avoids N+1)
 const ATHLETE_WITH_STATS_QUERY: &str = r#"
     SELECT
-        a.id, a.first_name, a.last_name, a.birth_year, a.gender, a.club_name, a.photo_path, a.created_at, a.updated_at,
+        a.id, a.first_name, a.last_name, a.birth_year, a.birth_date, a.gender, a.club_name, a.photo_path, a.archived_at, a.family_id,
+        a.photo_consent, a.photo_consent_date, a.photo_consent_note, a.created_at, a.updated_at,
         COALESCE((SELECT COUNT(DISTINCT discipline_id) FROM results WHERE athlete_id = a.id), 0) as discipline_count,
         COALESCE((SELECT COUNT(*) FROM results WHERE athlete_id = a.id), 0) as result_count,
         COALESCE((SELECT COUNT(*) FROM results WHERE athlete_id = a.id AND is_personal_best = 1), 0) as pb_count,
@@ -35,12 +41,18 @@ fn athlete_with_stats_from_row(row: &sqlx::sqlite::SqliteRow) -> AthleteWithStat
     }
 }
 
+/// List athletes. Archived athletes are left out unless `include_archived` is set
+/// (club top lists still show them).
 #[tauri::command]
-pub async fn get_all_athletes(app: AppHandle) -> Result<Vec<AthleteWithStats>, String> {
+pub async fn get_all_athletes(app: AppHandle, include_archived: Option<bool>) -> Result<Vec<AthleteWithStats>, String> {
     let pool = get_pool(&app).await?;
 
-    let query = format!("{} ORDER BY a.last_name, a.first_name", ATHLETE_WITH_STATS_QUERY);
+    let query = format!(
+        "{} WHERE (? = 1 OR a.archived_at IS NULL) ORDER BY a.last_name, a.first_name",
+        ATHLETE_WITH_STATS_QUERY
+    );
     let rows = sqlx::query(&query)
+        .bind(include_archived.unwrap_or(false))
         .fetch_all(&pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -63,18 +75,32 @@ pub async fn get_athlete(app: AppHandle, id: i64) -> Result<Option<AthleteWithSt
     Ok(row.as_ref().map(athlete_with_stats_from_row))
 }
 
-const ATHLETE_SELECT: &str = "SELECT id, first_name, last_name, birth_year, gender, club_name, photo_path, created_at, updated_at FROM athletes";
+const ATHLETE_SELECT: &str = "SELECT id, first_name, last_name, birth_year, birth_date, gender, club_name, photo_path, archived_at, family_id, photo_consent, photo_consent_date, photo_consent_note, created_at, updated_at FROM athletes";
+
+/// Validate a YYYY-MM-DD birth date and return its year
+pub(crate) fn birth_year_from_date(birth_date: &str) -> Result<i32, String> {
+    NaiveDate::parse_from_str(birth_date, "%Y-%m-%d")
+        .map(|d| d.year())
+        .map_err(|_| format!("Invalid birth date: {}", birth_date))
+}
 
 #[tauri::command]
 pub async fn create_athlete(app: AppHandle, athlete: CreateAthlete) -> Result<Athlete, String> {
     let pool = get_pool(&app).await?;
 
+    // birth_year follows the birth date when one is given
+    let birth_year = match &athlete.birth_date {
+        Some(date) => birth_year_from_date(date)?,
+        None => athlete.birth_year,
+    };
+
     let result = sqlx::query(
-        "INSERT INTO athletes (first_name, last_name, birth_year, gender, club_name, photo_path) VALUES (?, ?, ?, ?, ?, ?)"
+        "INSERT INTO athletes (first_name, last_name, birth_year, birth_date, gender, club_name, photo_path) VALUES (?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(&athlete.first_name)
     .bind(&athlete.last_name)
-    .bind(athlete.birth_year)
+    .bind(birth_year)
+    .bind(&athlete.birth_date)
     .bind(&athlete.gender)
     .bind(&athlete.club_name)
     .bind(&athlete.photo_path)
@@ -83,6 +109,7 @@ pub async fn create_athlete(app: AppHandle, athlete: CreateAthlete) -> Result<At
     .map_err(|e| e.to_string())?;
 
     let id = result.last_insert_rowid();
+    set_current_club(&pool, id, athlete.club_name.as_deref()).await?;
 
     let query = format!("{} WHERE id = ?", ATHLETE_SELECT);
     let row = sqlx::query(&query)
@@ -94,24 +121,92 @@ pub async fn create_athlete(app: AppHandle, athlete: CreateAthlete) -> Result<At
     Ok(athlete_from_row!(row))
 }
 
-#[tauri::command]
-pub async fn update_athlete(app: AppHandle, id: i64, athlete: UpdateAthlete) -> Result<Athlete, String> {
-    let pool = get_pool(&app).await?;
+/// Apply an athlete update. The birth date wins over a separately given birth year,
+/// and a stored birth date that no longer matches a changed birth year is cleared.
+async fn update_athlete_record(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64, athlete: &UpdateAthlete) -> Result<(), String> {
+    // An empty birth date clears the stored one
+    let birth_year = match athlete.birth_date.as_deref() {
+        Some(date) if !date.is_empty() => Some(birth_year_from_date(date)?),
+        _ => athlete.birth_year,
+    };
 
     sqlx::query(
-        "UPDATE athletes SET first_name = COALESCE(?, first_name), last_name = COALESCE(?, last_name), birth_year = COALESCE(?, birth_year), gender = COALESCE(?, gender), club_name = ?, photo_path = ? WHERE id = ?"
+        r#"UPDATE athletes SET first_name = COALESCE(?, first_name), last_name = COALESCE(?, last_name), birth_year = COALESCE(?, birth_year),
+           birth_date = CASE
+               WHEN ? IS NOT NULL THEN NULLIF(?, '')
+               WHEN CAST(strftime('%Y', birth_date) AS INTEGER) = COALESCE(?, birth_year) THEN birth_date
+           END,
+           gender = COALESCE(?, gender), club_name = ?, photo_path = ? WHERE id = ?"#
     )
     .bind(&athlete.first_name)
     .bind(&athlete.last_name)
-    .bind(athlete.birth_year)
+    .bind(birth_year)
+    .bind(&athlete.birth_date)
+    .bind(&athlete.birth_date)
+    .bind(birth_year)
     .bind(&athlete.gender)
     .bind(&athlete.club_name)
     .bind(&athlete.photo_path)
     .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    set_current_club(pool, id, athlete.club_name.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn update_athlete(app: AppHandle, id: i64, athlete: UpdateAthlete) -> Result<Athlete, String> {
+    let pool = get_pool(&app).await?;
+
+    update_athlete_record(&pool, id, &athlete).await?;
+
+    let query = format!("{} WHERE id = ?", ATHLETE_SELECT);
+    let row = sqlx::query(&query)
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(athlete_from_row!(row))
+}
+
+/// Record the photo publishing consent given for an athlete: "none",
+/// "internal" (club use only) or "public". The date defaults to today.
+#[tauri::command]
+pub async fn set_athlete_photo_consent(
+    app: AppHandle,
+    id: i64,
+    consent: String,
+    consent_date: Option<String>,
+    note: Option<String>,
+) -> Result<Athlete, String> {
+    let pool = get_pool(&app).await?;
+
+    if !["none", "internal", "public"].contains(&consent.as_str()) {
+        return Err("Invalid photo consent".to_string());
+    }
+    if let Some(date) = &consent_date {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("Invalid consent date: {}", date))?;
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE athletes SET photo_consent = ?, photo_consent_date = COALESCE(?, date('now')), photo_consent_note = ?,
+               updated_at = datetime('now')
+           WHERE id = ?"#
+    )
+    .bind(&consent)
+    .bind(&consent_date)
+    .bind(note.filter(|n| !n.trim().is_empty()))
+    .bind(id)
     .execute(&pool)
     .await
     .map_err(|e| e.to_string())?;
 
+    if result.rows_affected() == 0 {
+        return Err(format!("Athlete {} not found", id));
+    }
+
     let query = format!("{} WHERE id = ?", ATHLETE_SELECT);
     let row = sqlx::query(&query)
         .bind(id)
@@ -122,15 +217,674 @@ pub async fn update_athlete(app: AppHandle, id: i64, athlete: UpdateAthlete) ->
     Ok(athlete_from_row!(row))
 }
 
+/// Archive an athlete. Results, goals, medals and photos are kept and the
+/// athlete can be restored; use `purge_athlete` to delete permanently.
 #[tauri::command]
 pub async fn delete_athlete(app: AppHandle, id: i64) -> Result<bool, String> {
     let pool = get_pool(&app).await?;
 
-    let result = sqlx::query("DELETE FROM athletes WHERE id = ?")
+    let result = sqlx::query(
+        "UPDATE athletes SET archived_at = datetime('now'), updated_at = datetime('now') WHERE id = ? AND archived_at IS NULL"
+    )
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn restore_athlete(app: AppHandle, id: i64) -> Result<Athlete, String> {
+    let pool = get_pool(&app).await?;
+
+    sqlx::query("UPDATE athletes SET archived_at = NULL, updated_at = datetime('now') WHERE id = ?")
         .bind(id)
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(result.rows_affected() > 0)
+    let query = format!("{} WHERE id = ?", ATHLETE_SELECT);
+    let row = sqlx::query(&query)
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(athlete_from_row!(row))
+}
+
+/// Permanently delete an archived athlete with all history and photo files.
+/// The athlete must be archived first so a purge is always a deliberate second step.
+/// Photos also tagged with other athletes or competitions are kept.
+#[tauri::command]
+pub async fn purge_athlete(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let row = sqlx::query("SELECT photo_path, archived_at FROM athletes WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(row) = row else {
+        return Ok(false);
+    };
+
+    let archived_at: Option<String> = row.get("archived_at");
+    if archived_at.is_none() {
+        return Err("Only archived athletes can be deleted permanently".to_string());
+    }
+    let profile_photo: Option<String> = row.get("photo_path");
+
+    // Gallery photos imported for the athlete and for the athlete's results
+    let photos = sqlx::query(
+        r#"SELECT id, file_path, thumbnail_path, preview_path, original_path FROM photos
+        WHERE (entity_type = 'athletes' AND entity_id = ?)
+           OR (entity_type = 'results' AND entity_id IN (SELECT id FROM results WHERE athlete_id = ?))"#
+    )
+    .bind(id)
+    .bind(id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        r#"DELETE FROM photo_tags
+        WHERE (entity_type = 'athletes' AND entity_id = ?)
+           OR (entity_type = 'results' AND entity_id IN (SELECT id FROM results WHERE athlete_id = ?))"#
+    )
+    .bind(id)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Photos still tagged with someone else move to their first remaining tag
+    let mut removed_photos = Vec::new();
+    for photo in photos {
+        let photo_id: i64 = photo.get("id");
+        let moved = sqlx::query(
+            r#"UPDATE photos SET (entity_type, entity_id) =
+                   (SELECT entity_type, entity_id FROM photo_tags WHERE photo_id = photos.id ORDER BY id LIMIT 1)
+               WHERE id = ? AND EXISTS (SELECT 1 FROM photo_tags WHERE photo_id = photos.id)"#
+        )
+        .bind(photo_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if moved.rows_affected() == 0 {
+            sqlx::query("DELETE FROM photos WHERE id = ?")
+                .bind(photo_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            removed_photos.push(photo);
+        }
+    }
+
+    // Results, goals, medals and participants are removed by ON DELETE CASCADE
+    sqlx::query("DELETE FROM athletes WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    // Files go last so a failed delete never leaves rows pointing to missing files.
+    // Files still linked to other entities are kept.
+    for photo in &removed_photos {
+        remove_unused_photo_files(&pool, photo).await?;
+    }
+
+    if let Some(photo_path) = profile_photo {
+        remove_photo_files(&photo_path, profile_thumbnail_path(&photo_path).as_deref());
+    }
+    if let Err(e) = remove_profile_photos(&app, id) {
+        eprintln!("Warning: Failed to remove profile photos of athlete {}: {}", id, e);
+    }
+
+    Ok(true)
+}
+
+/// Planned disciplines of two participant rows combined, in first-seen order
+fn combine_planned_disciplines(keep: Option<&str>, merge: Option<&str>) -> Option<String> {
+    let parse = |json: Option<&str>| -> Vec<i64> {
+        json.and_then(|j| serde_json::from_str(j).ok()).unwrap_or_default()
+    };
+
+    let mut combined = parse(keep);
+    for id in parse(merge) {
+        if !combined.contains(&id) {
+            combined.push(id);
+        }
+    }
+
+    if combined.is_empty() {
+        None
+    } else {
+        serde_json::to_string(&combined).ok()
+    }
+}
+
+/// What merging moved, plus the duplicate's avatar files left to clean up
+struct MergedAthletes {
+    summary: MergeAthletesSummary,
+    /// The kept athlete had no avatar and took the duplicate's over
+    profile_photo_taken_over: bool,
+    /// The duplicate's avatar, no longer used by anyone
+    unused_profile_photo: Option<String>,
+}
+
+/// Move everything of `merge_id` to `keep_id` and delete the duplicate.
+/// PB/SB flags and goals of the kept athlete are re-evaluated afterwards.
+async fn merge_athlete_records(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    keep_id: i64,
+    merge_id: i64,
+) -> Result<MergedAthletes, String> {
+    if keep_id == merge_id {
+        return Err("Cannot merge an athlete with itself".to_string());
+    }
+
+    let query = format!("{} WHERE id = ?", ATHLETE_SELECT);
+    let keep = sqlx::query(&query)
+        .bind(keep_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| athlete_from_row!(row))
+        .ok_or_else(|| format!("Athlete {} not found", keep_id))?;
+    let merge = sqlx::query(&query)
+        .bind(merge_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| athlete_from_row!(row))
+        .ok_or_else(|| format!("Athlete {} not found", merge_id))?;
+
+    let mut summary = MergeAthletesSummary {
+        kept_athlete_id: keep_id,
+        merged_athlete_id: merge_id,
+        ..Default::default()
+    };
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Participations in the same competition would break UNIQUE(competition_id, athlete_id)
+    let conflicts = sqlx::query(
+        r#"SELECT m.id as merge_row_id, k.id as keep_row_id,
+                  k.disciplines_planned as keep_planned, m.disciplines_planned as merge_planned
+           FROM competition_participants m
+           JOIN competition_participants k ON k.competition_id = m.competition_id AND k.athlete_id = ?
+           WHERE m.athlete_id = ?"#
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for conflict in &conflicts {
+        let keep_planned: Option<String> = conflict.get("keep_planned");
+        let merge_planned: Option<String> = conflict.get("merge_planned");
+        let combined = combine_planned_disciplines(keep_planned.as_deref(), merge_planned.as_deref());
+
+        sqlx::query("UPDATE competition_participants SET disciplines_planned = ? WHERE id = ?")
+            .bind(&combined)
+            .bind(conflict.get::<i64, _>("keep_row_id"))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        sqlx::query("DELETE FROM competition_participants WHERE id = ?")
+            .bind(conflict.get::<i64, _>("merge_row_id"))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    summary.participants_combined = conflicts.len() as i64;
+
+    let moves = [
+        "UPDATE competition_participants SET athlete_id = ? WHERE athlete_id = ?",
+        "UPDATE results SET athlete_id = ? WHERE athlete_id = ?",
+        "UPDATE goals SET athlete_id = ? WHERE athlete_id = ?",
+        "UPDATE medals SET athlete_id = ? WHERE athlete_id = ?",
+        "UPDATE notes SET athlete_id = ? WHERE athlete_id = ?",
+        "UPDATE photos SET entity_id = ? WHERE entity_type = 'athletes' AND entity_id = ?",
+        // A guardian already linked to the kept athlete stays linked once
+        "UPDATE OR IGNORE athlete_guardians SET athlete_id = ? WHERE athlete_id = ?",
+        // A mapping already pointing to the kept athlete wins; the rest cascade away
+        "UPDATE OR IGNORE lif_athlete_mappings SET athlete_id = ? WHERE athlete_id = ?",
+    ];
+
+    let mut moved = Vec::with_capacity(moves.len());
+    for query in moves {
+        let result = sqlx::query(query)
+            .bind(keep_id)
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        moved.push(result.rows_affected() as i64);
+    }
+
+    summary.participants_moved = moved[0];
+    summary.results_moved = moved[1];
+    summary.goals_moved = moved[2];
+    summary.medals_moved = moved[3];
+    summary.notes_moved = moved[4];
+    summary.photos_moved = moved[5];
+    summary.guardians_moved = moved[6];
+
+    // Club history of the duplicate keeps its moved results credited to the
+    // same clubs. Where periods overlap the kept athlete's history wins.
+    summary.memberships_moved = sqlx::query(
+        r#"UPDATE athlete_club_memberships SET athlete_id = ?
+        WHERE athlete_id = ?
+          AND NOT EXISTS (
+              SELECT 1 FROM athlete_club_memberships k
+              WHERE k.athlete_id = ?
+                AND (k.start_date IS NULL OR athlete_club_memberships.end_date IS NULL OR k.start_date <= athlete_club_memberships.end_date)
+                AND (athlete_club_memberships.start_date IS NULL OR k.end_date IS NULL OR athlete_club_memberships.start_date <= k.end_date)
+          )"#
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .bind(keep_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected() as i64;
+
+    // Tags of the duplicate move too; a photo tagged with both keeps one tag
+    sqlx::query("UPDATE OR IGNORE photo_tags SET entity_id = ? WHERE entity_type = 'athletes' AND entity_id = ?")
+        .bind(keep_id)
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM photo_tags WHERE entity_type = 'athletes' AND entity_id = ?")
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Fill in details the kept athlete is missing. A birth date is only taken
+    // when it agrees with the kept athlete's birth year.
+    let merge_birth_date = merge
+        .birth_date
+        .as_deref()
+        .filter(|date| birth_year_from_date(date).ok() == Some(keep.birth_year));
+    sqlx::query(
+        r#"UPDATE athletes SET club_name = COALESCE(club_name, ?), photo_path = COALESCE(photo_path, ?),
+               birth_date = COALESCE(birth_date, ?), family_id = COALESCE(family_id, ?), updated_at = datetime('now')
+           WHERE id = ?"#
+    )
+    .bind(&merge.club_name)
+    .bind(&merge.photo_path)
+    .bind(merge_birth_date)
+    .bind(merge.family_id)
+    .bind(keep_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM athletes WHERE id = ?")
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    // The current club follows the combined membership history
+    if summary.memberships_moved > 0 {
+        sync_athlete_club_name(pool, keep_id).await?;
+    }
+
+    // PB/SB flags depend on the combined history
+    let groups = sqlx::query(
+        "SELECT DISTINCT discipline_id, equipment_weight, hurdle_height FROM results WHERE athlete_id = ?"
+    )
+    .bind(keep_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut disciplines: Vec<i64> = Vec::new();
+    for group in &groups {
+        let discipline_id: i64 = group.get("discipline_id");
+        recalculate_records(
+            pool,
+            keep_id,
+            discipline_id,
+            group.get("equipment_weight"),
+            group.get("hurdle_height"),
+        )
+        .await?;
+        if !disciplines.contains(&discipline_id) {
+            disciplines.push(discipline_id);
+        }
+    }
+
+    for discipline_id in &disciplines {
+        evaluate_goals(pool, keep_id, *discipline_id).await?;
+    }
+    summary.disciplines_recalculated = disciplines.len() as i64;
+
+    let profile_photo_taken_over = keep.photo_path.is_none() && merge.photo_path.is_some();
+    Ok(MergedAthletes {
+        summary,
+        profile_photo_taken_over,
+        unused_profile_photo: merge.photo_path.filter(|_| !profile_photo_taken_over),
+    })
+}
+
+/// Merge a duplicate athlete into another one.
+///
+/// Results, goals, medals, competition participations, notes, photos, guardians,
+/// club memberships and FinishLynx name mappings move to `keep_id` in one
+/// transaction and the duplicate is deleted. When both athletes take part in the
+/// same competition the planned disciplines are combined into the kept athlete's row.
+/// PB/SB flags and goals are re-evaluated afterwards for every affected discipline.
+#[tauri::command]
+pub async fn merge_athletes(app: AppHandle, keep_id: i64, merge_id: i64) -> Result<MergeAthletesSummary, String> {
+    let pool = get_pool(&app).await?;
+    let merged = merge_athlete_records(&pool, keep_id, merge_id).await?;
+
+    // Avatar files of the duplicate go unless the kept athlete took them over
+    if !merged.profile_photo_taken_over {
+        if let Some(photo_path) = &merged.unused_profile_photo {
+            remove_photo_files(photo_path, profile_thumbnail_path(photo_path).as_deref());
+        }
+        if let Err(e) = remove_profile_photos(&app, merge_id) {
+            eprintln!("Warning: Failed to remove profile photos of athlete {}: {}", merge_id, e);
+        }
+    }
+
+    Ok(merged.summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::run_migrations;
+
+    async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        run_migrations(&pool).await.expect("Failed to run migrations");
+        pool
+    }
+
+    async fn insert_athlete(pool: &sqlx::Pool<sqlx::Sqlite>, first_name: &str, birth_year: i32) -> i64 {
+        sqlx::query("INSERT INTO athletes (first_name, last_name, birth_year) VALUES (?, 'Virtanen', ?)")
+            .bind(first_name)
+            .bind(birth_year)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    /// 60 m competition result
+    async fn insert_result(pool: &sqlx::Pool<sqlx::Sqlite>, athlete_id: i64, date: &str, value: f64) -> i64 {
+        sqlx::query(
+            "INSERT INTO results (athlete_id, discipline_id, date, value, type, is_personal_best, is_season_best) VALUES (?, 2, ?, ?, 'competition', 1, 1)"
+        )
+        .bind(athlete_id)
+        .bind(date)
+        .bind(value)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid()
+    }
+
+    async fn count(pool: &sqlx::Pool<sqlx::Sqlite>, query: &str, id: i64) -> i64 {
+        sqlx::query_scalar(query).bind(id).fetch_one(pool).await.unwrap()
+    }
+
+    #[test]
+    fn test_combine_planned_disciplines() {
+        assert_eq!(combine_planned_disciplines(Some("[1,2]"), Some("[2,3]")).as_deref(), Some("[1,2,3]"));
+        assert_eq!(combine_planned_disciplines(None, Some("[4]")).as_deref(), Some("[4]"));
+        assert_eq!(combine_planned_disciplines(None, None), None);
+    }
+
+    async fn birth(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> (i32, Option<String>) {
+        sqlx::query_as("SELECT birth_year, birth_date FROM athletes WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn birth_update(birth_year: Option<i32>, birth_date: Option<&str>) -> UpdateAthlete {
+        UpdateAthlete {
+            first_name: None,
+            last_name: None,
+            birth_year,
+            birth_date: birth_date.map(str::to_string),
+            gender: None,
+            club_name: None,
+            photo_path: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_birth_year_and_date_consistent() {
+        let pool = setup_test_db().await;
+        let id = insert_athlete(&pool, "Aino", 2012).await;
+
+        update_athlete_record(&pool, id, &birth_update(None, Some("2013-03-04"))).await.unwrap();
+        assert_eq!(birth(&pool, id).await, (2013, Some("2013-03-04".to_string())));
+
+        // Same year keeps the date
+        update_athlete_record(&pool, id, &birth_update(Some(2013), None)).await.unwrap();
+        assert_eq!(birth(&pool, id).await, (2013, Some("2013-03-04".to_string())));
+
+        // A different year drops the date that no longer matches
+        update_athlete_record(&pool, id, &birth_update(Some(2012), None)).await.unwrap();
+        assert_eq!(birth(&pool, id).await, (2012, None));
+
+        // The date decides the year when both are given
+        update_athlete_record(&pool, id, &birth_update(Some(2010), Some("2011-09-30"))).await.unwrap();
+        assert_eq!(birth(&pool, id).await, (2011, Some("2011-09-30".to_string())));
+
+        update_athlete_record(&pool, id, &birth_update(None, Some(""))).await.unwrap();
+        assert_eq!(birth(&pool, id).await, (2011, None));
+    }
+
+    #[tokio::test]
+    async fn test_merge_combines_conflicting_participants() {
+        let pool = setup_test_db().await;
+        let keep = insert_athlete(&pool, "Aino", 2012).await;
+        let merge = insert_athlete(&pool, "Aino M.", 2012).await;
+
+        for (id, name) in [(1, "Kevätkisat"), (2, "Syyskisat")] {
+            sqlx::query("INSERT INTO competitions (id, name, date) VALUES (?, ?, '2025-05-10')")
+                .bind(id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (athlete_id, competition_id, planned) in [(keep, 1, "[1,2]"), (merge, 1, "[2,3]"), (merge, 2, "[4]")] {
+            sqlx::query("INSERT INTO competition_participants (competition_id, athlete_id, disciplines_planned) VALUES (?, ?, ?)")
+                .bind(competition_id)
+                .bind(athlete_id)
+                .bind(planned)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let merged = merge_athlete_records(&pool, keep, merge).await.unwrap();
+        assert_eq!(merged.summary.participants_combined, 1);
+        assert_eq!(merged.summary.participants_moved, 1);
+
+        let rows = sqlx::query("SELECT competition_id, disciplines_planned FROM competition_participants WHERE athlete_id = ? ORDER BY competition_id")
+            .bind(keep)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let planned: Vec<(i64, String)> = rows
+            .iter()
+            .map(|row| (row.get("competition_id"), row.get("disciplines_planned")))
+            .collect();
+        assert_eq!(planned, vec![(1, "[1,2,3]".to_string()), (2, "[4]".to_string())]);
+
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM athletes WHERE id = ?", merge).await, 0);
+        assert!(merge_athlete_records(&pool, keep, keep).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_history() {
+        let pool = setup_test_db().await;
+        let keep = insert_athlete(&pool, "Aino", 2012).await;
+        let merge = insert_athlete(&pool, "Aino M.", 2012).await;
+
+        let result_id = insert_result(&pool, merge, "2025-06-01", 9.20).await;
+        let old_result_id = insert_result(&pool, merge, "2024-06-01", 9.60).await;
+        sqlx::query("INSERT INTO families (id, name) VALUES (7, 'Virtanen')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE athletes SET photo_path = '/avatars/athlete_2_a.jpg', family_id = 7 WHERE id = ?")
+            .bind(merge)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for statement in [
+            "INSERT INTO goals (athlete_id, discipline_id, target_value) VALUES (?, 2, 9.0)",
+            "INSERT INTO medals (athlete_id, type, competition_name, date) VALUES (?, 'gold', 'Kisat', '2025-06-01')",
+            "INSERT INTO notes (content, athlete_id) VALUES ('Hyvä startti', ?)",
+            "INSERT INTO photos (entity_type, entity_id, file_path, original_name) VALUES ('athletes', ?, '/photos/a.jpg', 'a.jpg')",
+            "INSERT INTO lif_athlete_mappings (first_name, last_name, athlete_id) VALUES ('Aino', 'Virtanen', ?)",
+        ] {
+            sqlx::query(statement).bind(merge).execute(&pool).await.unwrap();
+        }
+
+        // Both athletes are linked to the same guardian; the duplicate has one more
+        sqlx::query("INSERT INTO guardians (id, name) VALUES (1, 'Liisa'), (2, 'Pekka')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO athlete_guardians (athlete_id, guardian_id) VALUES (?, 1), (?, 1), (?, 2)")
+            .bind(keep)
+            .bind(merge)
+            .bind(merge)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // The duplicate's old club period moves; the overlapping current one gives way
+        sqlx::query("INSERT INTO clubs (id, name) VALUES (1, 'Tampereen Pyrintö'), (2, 'Kalevan Kisa')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO athlete_club_memberships (athlete_id, club_id, start_date, end_date) VALUES
+                (?, 2, '2025-01-01', NULL),
+                (?, 1, NULL, '2024-12-31'),
+                (?, 2, '2025-01-01', NULL)"#,
+        )
+        .bind(keep)
+        .bind(merge)
+        .bind(merge)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let merged = merge_athlete_records(&pool, keep, merge).await.unwrap();
+        let summary = &merged.summary;
+        assert_eq!(summary.results_moved, 2);
+        assert_eq!(summary.goals_moved, 1);
+        assert_eq!(summary.medals_moved, 1);
+        assert_eq!(summary.notes_moved, 1);
+        assert_eq!(summary.photos_moved, 1);
+        assert_eq!(summary.guardians_moved, 1);
+        assert_eq!(summary.memberships_moved, 1);
+        assert_eq!(summary.disciplines_recalculated, 1);
+
+        let result_owner: i64 = sqlx::query_scalar("SELECT athlete_id FROM results WHERE id = ?")
+            .bind(result_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(result_owner, keep);
+        for query in [
+            "SELECT COUNT(*) FROM goals WHERE athlete_id = ?",
+            "SELECT COUNT(*) FROM medals WHERE athlete_id = ?",
+            "SELECT COUNT(*) FROM notes WHERE athlete_id = ?",
+            "SELECT COUNT(*) FROM photos WHERE entity_type = 'athletes' AND entity_id = ?",
+            "SELECT COUNT(*) FROM lif_athlete_mappings WHERE athlete_id = ?",
+        ] {
+            assert_eq!(count(&pool, query, keep).await, 1, "{}", query);
+        }
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM athlete_guardians WHERE athlete_id = ?", keep).await, 2);
+        assert_eq!(count(&pool, "SELECT COUNT(*) FROM athlete_club_memberships WHERE athlete_id = ?", keep).await, 2);
+
+        // The 2024 result stays credited to the old club
+        let club_2024: Option<i64> = sqlx::query_scalar(&format!(
+            "SELECT {} FROM results r WHERE r.id = ?",
+            crate::commands::clubs::RESULT_CLUB_SQL
+        ))
+        .bind(old_result_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(club_2024, Some(1));
+
+        // Missing details come from the duplicate, whose avatar is taken over
+        let kept = sqlx::query("SELECT family_id, photo_path FROM athletes WHERE id = ?")
+            .bind(keep)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(kept.get::<Option<i64>, _>("family_id"), Some(7));
+        assert_eq!(kept.get::<Option<String>, _>("photo_path").as_deref(), Some("/avatars/athlete_2_a.jpg"));
+        assert!(merged.profile_photo_taken_over);
+        assert_eq!(merged.unused_profile_photo, None);
+    }
+
+    #[tokio::test]
+    async fn test_merge_recalculates_records() {
+        let pool = setup_test_db().await;
+        let keep = insert_athlete(&pool, "Aino", 2012).await;
+        let merge = insert_athlete(&pool, "Aino M.", 2012).await;
+
+        // Both athletes have their own PB and SB flags before the merge
+        let keep_2025 = insert_result(&pool, keep, "2025-06-01", 9.00).await;
+        let merge_2024 = insert_result(&pool, merge, "2024-06-01", 8.80).await;
+        let merge_2025 = insert_result(&pool, merge, "2025-07-01", 9.10).await;
+
+        merge_athlete_records(&pool, keep, merge).await.unwrap();
+
+        let flags = |id: i64| {
+            let pool = pool.clone();
+            async move {
+                let row = sqlx::query("SELECT is_personal_best, is_season_best FROM results WHERE id = ?")
+                    .bind(id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                (row.get::<bool, _>("is_personal_best"), row.get::<bool, _>("is_season_best"))
+            }
+        };
+        assert_eq!(flags(merge_2024).await, (true, true));
+        assert_eq!(flags(keep_2025).await, (false, true));
+        assert_eq!(flags(merge_2025).await, (false, false));
+    }
 }
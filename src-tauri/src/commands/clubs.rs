@@ -0,0 +1,678 @@
+use crate::commands::results::records::is_wind_assisted_on_date;
+use crate::database::get_pool;
+use crate::types::{
+    Club, ClubMembership, ClubTopListEntry, CreateClub, CreateClubMembership, UpdateClub, UpdateClubMembership,
+};
+use sqlx::Row;
+use std::collections::HashSet;
+use tauri::AppHandle;
+
+const CLUB_SELECT: &str = "SELECT id, name, abbreviation, created_at FROM clubs";
+
+const MEMBERSHIP_SELECT: &str = r#"SELECT m.id, m.athlete_id, m.club_id, c.name as club_name, m.start_date, m.end_date
+    FROM athlete_club_memberships m
+    JOIN clubs c ON c.id = m.club_id"#;
+
+/// SQL expression for the club an athlete represented on a result's date.
+/// Expects the results table aliased as `r`.
+pub(crate) const RESULT_CLUB_SQL: &str = r#"(SELECT m.club_id FROM athlete_club_memberships m
+    WHERE m.athlete_id = r.athlete_id
+      AND (m.start_date IS NULL OR m.start_date <= r.date)
+      AND (m.end_date IS NULL OR m.end_date >= r.date)
+    ORDER BY m.start_date DESC LIMIT 1)"#;
+
+fn club_from_row(row: &sqlx::sqlite::SqliteRow) -> Club {
+    Club {
+        id: row.get("id"),
+        name: row.get("name"),
+        abbreviation: row.get("abbreviation"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn membership_from_row(row: &sqlx::sqlite::SqliteRow) -> ClubMembership {
+    ClubMembership {
+        id: row.get("id"),
+        athlete_id: row.get("athlete_id"),
+        club_id: row.get("club_id"),
+        club_name: row.get("club_name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+    }
+}
+
+async fn fetch_club(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Club, String> {
+    let row = sqlx::query(&format!("{} WHERE id = ?", CLUB_SELECT))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(club_from_row(&row))
+}
+
+async fn fetch_membership(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<ClubMembership, String> {
+    let row = sqlx::query(&format!("{} WHERE m.id = ?", MEMBERSHIP_SELECT))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(membership_from_row(&row))
+}
+
+/// Whether two membership periods share a day. `None` dates are open-ended.
+pub(crate) fn periods_overlap(
+    a: (Option<&str>, Option<&str>),
+    b: (Option<&str>, Option<&str>),
+) -> bool {
+    let starts_before_end = |start: Option<&str>, end: Option<&str>| match (start, end) {
+        (Some(start), Some(end)) => start <= end,
+        _ => true,
+    };
+    starts_before_end(a.0, b.1) && starts_before_end(b.0, a.1)
+}
+
+/// Validate a membership period against the athlete's other memberships
+async fn validate_membership(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    exclude_id: Option<i64>,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<(), String> {
+    if let (Some(start), Some(end)) = (start_date, end_date) {
+        if start > end {
+            return Err("Membership cannot end before it starts".to_string());
+        }
+    }
+
+    let others = sqlx::query(&format!("{} WHERE m.athlete_id = ? AND m.id != ?", MEMBERSHIP_SELECT))
+        .bind(athlete_id)
+        .bind(exclude_id.unwrap_or(0))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for other in others.iter().map(membership_from_row) {
+        if periods_overlap(
+            (start_date, end_date),
+            (other.start_date.as_deref(), other.end_date.as_deref()),
+        ) {
+            return Err(format!("Membership overlaps with {} membership", other.club_name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Keep athletes.club_name in sync with the athlete's current membership
+pub(crate) async fn sync_athlete_club_name(pool: &sqlx::Pool<sqlx::Sqlite>, athlete_id: i64) -> Result<(), String> {
+    sqlx::query(
+        r#"UPDATE athletes SET club_name = (
+            SELECT c.name FROM athlete_club_memberships m
+            JOIN clubs c ON c.id = m.club_id
+            WHERE m.athlete_id = athletes.id
+              AND (m.start_date IS NULL OR m.start_date <= date('now'))
+              AND (m.end_date IS NULL OR m.end_date >= date('now'))
+            ORDER BY m.start_date DESC LIMIT 1
+        ), updated_at = datetime('now')
+        WHERE id = ?"#
+    )
+    .bind(athlete_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// End the athlete's open memberships yesterday. A membership that started
+/// today would end before it began, so it is removed instead.
+async fn end_open_memberships(pool: &sqlx::Pool<sqlx::Sqlite>, athlete_id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM athlete_club_memberships WHERE athlete_id = ? AND end_date IS NULL AND start_date >= date('now')")
+        .bind(athlete_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query("UPDATE athlete_club_memberships SET end_date = date('now', '-1 day') WHERE athlete_id = ? AND end_date IS NULL")
+        .bind(athlete_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Make `club_name` the athlete's current club (used when the club is edited
+/// as free text on the athlete form). The club is created if needed, open
+/// memberships in other clubs end yesterday and a new one starts today.
+/// The first membership of an athlete has no start date. No club ends the
+/// open memberships.
+pub(crate) async fn set_current_club(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    club_name: Option<&str>,
+) -> Result<(), String> {
+    let Some(club_name) = club_name.map(str::trim).filter(|name| !name.is_empty()) else {
+        return end_open_memberships(pool, athlete_id).await;
+    };
+
+    sqlx::query("INSERT OR IGNORE INTO clubs (name) VALUES (?)")
+        .bind(club_name)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let club_id: i64 = sqlx::query_scalar("SELECT id FROM clubs WHERE name = ?")
+        .bind(club_name)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let open: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT id, club_id FROM athlete_club_memberships WHERE athlete_id = ? AND end_date IS NULL"
+    )
+    .bind(athlete_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if open.iter().any(|(_, open_club)| *open_club == club_id) {
+        return Ok(());
+    }
+
+    end_open_memberships(pool, athlete_id).await?;
+
+    let has_history: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM athlete_club_memberships WHERE athlete_id = ?")
+        .bind(athlete_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "INSERT INTO athlete_club_memberships (athlete_id, club_id, start_date) VALUES (?, ?, CASE WHEN ? THEN date('now') END)"
+    )
+    .bind(athlete_id)
+    .bind(club_id)
+    .bind(has_history)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// ==================== Clubs ====================
+
+#[tauri::command]
+pub async fn get_clubs(app: AppHandle) -> Result<Vec<Club>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!("{} ORDER BY name", CLUB_SELECT))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(club_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn create_club(app: AppHandle, club: CreateClub) -> Result<Club, String> {
+    let pool = get_pool(&app).await?;
+
+    let id = sqlx::query("INSERT INTO clubs (name, abbreviation) VALUES (?, ?)")
+        .bind(club.name.trim())
+        .bind(&club.abbreviation)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+
+    fetch_club(&pool, id).await
+}
+
+/// Update a club. Renaming also updates the current club name of its members.
+#[tauri::command]
+pub async fn update_club(app: AppHandle, id: i64, club: UpdateClub) -> Result<Club, String> {
+    let pool = get_pool(&app).await?;
+
+    sqlx::query("UPDATE clubs SET name = COALESCE(?, name), abbreviation = ? WHERE id = ?")
+        .bind(club.name.as_deref().map(str::trim))
+        .bind(&club.abbreviation)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let athlete_ids: Vec<i64> = sqlx::query_scalar("SELECT DISTINCT athlete_id FROM athlete_club_memberships WHERE club_id = ?")
+        .bind(id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for athlete_id in athlete_ids {
+        sync_athlete_club_name(&pool, athlete_id).await?;
+    }
+
+    fetch_club(&pool, id).await
+}
+
+/// Delete a club and its memberships
+#[tauri::command]
+pub async fn delete_club(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let athlete_ids: Vec<i64> = sqlx::query_scalar("SELECT DISTINCT athlete_id FROM athlete_club_memberships WHERE club_id = ?")
+        .bind(id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = sqlx::query("DELETE FROM clubs WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for athlete_id in athlete_ids {
+        sync_athlete_club_name(&pool, athlete_id).await?;
+    }
+
+    Ok(result.rows_affected() > 0)
+}
+
+// ==================== Memberships ====================
+
+/// Membership history of an athlete, newest first
+#[tauri::command]
+pub async fn get_athlete_memberships(app: AppHandle, athlete_id: i64) -> Result<Vec<ClubMembership>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE m.athlete_id = ? ORDER BY m.start_date IS NULL, m.start_date DESC",
+        MEMBERSHIP_SELECT
+    ))
+    .bind(athlete_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(membership_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn add_club_membership(app: AppHandle, membership: CreateClubMembership) -> Result<ClubMembership, String> {
+    let pool = get_pool(&app).await?;
+
+    validate_membership(
+        &pool,
+        membership.athlete_id,
+        None,
+        membership.start_date.as_deref(),
+        membership.end_date.as_deref(),
+    )
+    .await?;
+
+    let id = sqlx::query(
+        "INSERT INTO athlete_club_memberships (athlete_id, club_id, start_date, end_date) VALUES (?, ?, ?, ?)"
+    )
+    .bind(membership.athlete_id)
+    .bind(membership.club_id)
+    .bind(&membership.start_date)
+    .bind(&membership.end_date)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+
+    sync_athlete_club_name(&pool, membership.athlete_id).await?;
+
+    fetch_membership(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn update_club_membership(
+    app: AppHandle,
+    id: i64,
+    membership: UpdateClubMembership,
+) -> Result<ClubMembership, String> {
+    let pool = get_pool(&app).await?;
+
+    let existing = fetch_membership(&pool, id).await?;
+    validate_membership(
+        &pool,
+        existing.athlete_id,
+        Some(id),
+        membership.start_date.as_deref(),
+        membership.end_date.as_deref(),
+    )
+    .await?;
+
+    sqlx::query(
+        "UPDATE athlete_club_memberships SET club_id = COALESCE(?, club_id), start_date = ?, end_date = ? WHERE id = ?"
+    )
+    .bind(membership.club_id)
+    .bind(&membership.start_date)
+    .bind(&membership.end_date)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sync_athlete_club_name(&pool, existing.athlete_id).await?;
+
+    fetch_membership(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn delete_club_membership(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let athlete_id: Option<i64> = sqlx::query_scalar("SELECT athlete_id FROM athlete_club_memberships WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(athlete_id) = athlete_id else {
+        return Ok(false);
+    };
+
+    sqlx::query("DELETE FROM athlete_club_memberships WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sync_athlete_club_name(&pool, athlete_id).await?;
+
+    Ok(true)
+}
+
+// ==================== Top lists ====================
+
+/// Best competition result per athlete in a discipline, counting only results
+/// made while the athlete represented the club. Like personal bests, invalid
+/// and wind-assisted results don't count and each implement (throw weight or
+/// hurdle height) has its own list, ranked separately and limited to `limit`
+/// entries. Archived athletes are included.
+pub(crate) async fn build_club_top_list(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    club_id: i64,
+    discipline_id: i64,
+    season: Option<i32>,
+    limit: i64,
+) -> Result<Vec<ClubTopListEntry>, String> {
+    let Some(discipline) = sqlx::query("SELECT name, lower_is_better FROM disciplines WHERE id = ?")
+        .bind(discipline_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(Vec::new());
+    };
+    let discipline_name: String = discipline.get("name");
+    let order = if discipline.get::<i32, _>("lower_is_better") == 1 { "ASC" } else { "DESC" };
+
+    let query = format!(
+        r#"SELECT r.athlete_id, a.first_name, a.last_name, a.birth_year, a.birth_date,
+                  r.id as result_id, r.value, r.date, r.wind, r.competition_name, r.equipment_weight, r.hurdle_height
+           FROM results r
+           JOIN athletes a ON a.id = r.athlete_id
+           WHERE r.discipline_id = ? AND r.type = 'competition'
+             AND COALESCE(r.status, 'valid') = 'valid' AND r.value > 0
+             AND (? IS NULL OR strftime('%Y', r.date) = ?)
+             AND {club} = ?
+           ORDER BY r.equipment_weight ASC, r.hurdle_height ASC, r.value {order}, r.date ASC"#,
+        order = order,
+        club = RESULT_CLUB_SQL
+    );
+
+    let season = season.map(|s| s.to_string());
+    let rows = sqlx::query(&query)
+        .bind(discipline_id)
+        .bind(&season)
+        .bind(&season)
+        .bind(club_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut entries: Vec<ClubTopListEntry> = Vec::new();
+    let mut listed = HashSet::new();
+    for row in &rows {
+        let date: String = row.get("date");
+        let birth_date: Option<String> = row.get("birth_date");
+        if is_wind_assisted_on_date(row.get("wind"), &discipline_name, row.get("birth_year"), birth_date.as_deref(), &date) {
+            continue;
+        }
+
+        let athlete_id: i64 = row.get("athlete_id");
+        let equipment_weight: Option<f64> = row.get("equipment_weight");
+        let hurdle_height: Option<i32> = row.get("hurdle_height");
+        // Rows come best first within each implement, so the first one per athlete is their best
+        if !listed.insert((athlete_id, equipment_weight.map(f64::to_bits), hurdle_height)) {
+            continue;
+        }
+
+        let rank = match entries.last() {
+            Some(last) if last.equipment_weight == equipment_weight && last.hurdle_height == hurdle_height => last.rank + 1,
+            _ => 1,
+        };
+        if i64::from(rank) > limit {
+            continue;
+        }
+
+        entries.push(ClubTopListEntry {
+            rank,
+            athlete_id,
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            result_id: row.get("result_id"),
+            value: row.get("value"),
+            date,
+            competition_name: row.get("competition_name"),
+            equipment_weight,
+            hurdle_height,
+        });
+    }
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn get_club_top_list(
+    app: AppHandle,
+    club_id: i64,
+    discipline_id: i64,
+    season: Option<i32>,
+    limit: Option<i64>,
+) -> Result<Vec<ClubTopListEntry>, String> {
+    let pool = get_pool(&app).await?;
+    build_club_top_list(&pool, club_id, discipline_id, season, limit.unwrap_or(20)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE disciplines (id INTEGER PRIMARY KEY, name TEXT NOT NULL, lower_is_better INTEGER NOT NULL DEFAULT 1);
+            CREATE TABLE athletes (
+                id INTEGER PRIMARY KEY,
+                first_name TEXT NOT NULL,
+                last_name TEXT NOT NULL,
+                birth_year INTEGER NOT NULL,
+                birth_date TEXT,
+                club_name TEXT,
+                archived_at TEXT
+            );
+            CREATE TABLE results (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                athlete_id INTEGER NOT NULL,
+                discipline_id INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                value REAL NOT NULL,
+                type TEXT NOT NULL DEFAULT 'competition',
+                competition_name TEXT,
+                wind REAL,
+                status TEXT DEFAULT 'valid',
+                equipment_weight REAL,
+                hurdle_height INTEGER
+            );
+            CREATE TABLE clubs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                abbreviation TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE TABLE athlete_club_memberships (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                athlete_id INTEGER NOT NULL,
+                club_id INTEGER NOT NULL,
+                start_date TEXT,
+                end_date TEXT
+            );
+
+            INSERT INTO disciplines (id, name, lower_is_better) VALUES (1, '60m', 1), (2, 'Kuula', 0);
+            INSERT INTO clubs (id, name) VALUES (1, 'Tampereen Pyrintö'), (2, 'Kalevan Kisa');
+            INSERT INTO athletes (id, first_name, last_name, birth_year, archived_at) VALUES
+                (1, 'Aino', 'Virtanen', 2008, NULL),
+                (2, 'Emma', 'Korhonen', 2008, '2025-01-01');
+            -- Aino moved from club 1 to club 2 in 2025; Emma (archived) stayed in club 1
+            INSERT INTO athlete_club_memberships (athlete_id, club_id, start_date, end_date) VALUES
+                (1, 1, NULL, '2024-12-31'),
+                (1, 2, '2025-01-01', NULL),
+                (2, 1, NULL, NULL);
+            INSERT INTO results (athlete_id, discipline_id, date, value) VALUES
+                (1, 1, '2024-06-01', 9.10),
+                (1, 1, '2025-06-01', 8.80),
+                (2, 1, '2024-07-01', 9.00);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to create schema");
+
+        pool
+    }
+
+    #[test]
+    fn test_periods_overlap() {
+        assert!(periods_overlap((None, None), (Some("2025-01-01"), None)));
+        assert!(!periods_overlap((None, Some("2024-12-31")), (Some("2025-01-01"), None)));
+        assert!(periods_overlap((Some("2024-01-01"), Some("2025-01-01")), (Some("2025-01-01"), None)));
+        assert!(!periods_overlap(
+            (Some("2023-01-01"), Some("2023-12-31")),
+            (Some("2024-01-01"), Some("2024-12-31"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_top_list_credits_club_on_result_date() {
+        let pool = setup_test_db().await;
+
+        // Aino's faster 2025 result belongs to club 2, so club 1 only gets her 2024 result
+        let old_club = build_club_top_list(&pool, 1, 1, None, 20).await.unwrap();
+        assert_eq!(old_club.len(), 2);
+        assert_eq!((old_club[0].athlete_id, old_club[0].value), (2, 9.00));
+        assert_eq!((old_club[1].athlete_id, old_club[1].value), (1, 9.10));
+        assert_eq!(old_club[1].rank, 2);
+
+        let new_club = build_club_top_list(&pool, 2, 1, None, 20).await.unwrap();
+        assert_eq!(new_club.len(), 1);
+        assert_eq!(new_club[0].value, 8.80);
+
+        let season_2024 = build_club_top_list(&pool, 2, 1, Some(2024), 20).await.unwrap();
+        assert!(season_2024.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_top_list_uses_record_rules() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"INSERT INTO results (athlete_id, discipline_id, date, value, type, wind, status, equipment_weight) VALUES
+                (2, 1, '2024-08-01', 8.70, 'competition', 2.5, 'valid', NULL),
+                (2, 1, '2024-08-02', 8.60, 'training', NULL, 'valid', NULL),
+                (2, 1, '2024-08-03', 8.50, 'competition', NULL, 'dq', NULL),
+                (2, 2, '2024-05-01', 11.20, 'competition', NULL, 'valid', 3.0),
+                (2, 2, '2024-06-01', 9.40, 'competition', NULL, 'valid', 4.0),
+                (1, 2, '2024-06-01', 10.10, 'competition', NULL, 'valid', 3.0)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Wind-assisted, training and disqualified results don't count
+        let sprint = build_club_top_list(&pool, 1, 1, None, 20).await.unwrap();
+        assert_eq!((sprint[0].athlete_id, sprint[0].value), (2, 9.00));
+
+        // Each shot weight is ranked on its own
+        let shot = build_club_top_list(&pool, 1, 2, None, 20).await.unwrap();
+        let ranked: Vec<(Option<f64>, i32, i64)> = shot.iter().map(|e| (e.equipment_weight, e.rank, e.athlete_id)).collect();
+        assert_eq!(ranked, vec![(Some(3.0), 1, 2), (Some(3.0), 2, 1), (Some(4.0), 1, 2)]);
+
+        let limited = build_club_top_list(&pool, 1, 2, None, 1).await.unwrap();
+        assert_eq!(limited.len(), 2);
+    }
+
+    async fn memberships(pool: &sqlx::Pool<sqlx::Sqlite>) -> Vec<(i64, Option<String>, Option<String>)> {
+        sqlx::query_as(
+            "SELECT club_id, start_date, end_date FROM athlete_club_memberships WHERE athlete_id = 3 ORDER BY id"
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_set_current_club_transitions() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO athletes (id, first_name, last_name, birth_year) VALUES (3, 'Sara', 'Mäkinen', 2012)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let today: String = sqlx::query_scalar("SELECT date('now')").fetch_one(&pool).await.unwrap();
+        let yesterday: String = sqlx::query_scalar("SELECT date('now', '-1 day')").fetch_one(&pool).await.unwrap();
+
+        // The first membership has no start date
+        set_current_club(&pool, 3, Some("Tampereen Pyrintö")).await.unwrap();
+        assert_eq!(memberships(&pool).await, vec![(1, None, None)]);
+
+        // Same club again changes nothing
+        set_current_club(&pool, 3, Some(" Tampereen Pyrintö ")).await.unwrap();
+        assert_eq!(memberships(&pool).await.len(), 1);
+
+        // Switching ends the old membership yesterday and starts the new one today
+        set_current_club(&pool, 3, Some("Kalevan Kisa")).await.unwrap();
+        assert_eq!(
+            memberships(&pool).await,
+            vec![(1, None, Some(yesterday.clone())), (2, Some(today.clone()), None)]
+        );
+
+        // A membership started today is replaced, not ended before it began
+        set_current_club(&pool, 3, Some("Lahden Ahkera")).await.unwrap();
+        let lahti: i64 = sqlx::query_scalar("SELECT id FROM clubs WHERE name = 'Lahden Ahkera'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            memberships(&pool).await,
+            vec![(1, None, Some(yesterday.clone())), (lahti, Some(today), None)]
+        );
+
+        // No club removes today's membership too
+        set_current_club(&pool, 3, None).await.unwrap();
+        assert_eq!(memberships(&pool).await, vec![(1, None, Some(yesterday))]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::commands::photos::remove_entity_tags;
 use crate::database::get_pool;
 use crate::types::{Competition, CompetitionParticipant, CreateCompetition, CreateCompetitionParticipant, UpdateCompetition};
 use sqlx::Row;
@@ -207,6 +208,10 @@ pub async fn delete_competition(app: AppHandle, id: i64) -> Result<bool, String>
         .await
         .map_err(|e| e.to_string())?;
 
+    if result.rows_affected() > 0 {
+        remove_entity_tags(&pool, "competitions", id).await?;
+    }
+
     Ok(result.rows_affected() > 0)
 }
 
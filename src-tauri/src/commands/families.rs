@@ -0,0 +1,349 @@
+use crate::database::get_pool;
+use crate::types::{
+    Athlete, CreateFamily, CreateGuardian, Family, FamilyCompetition, Guardian, UpdateFamily, UpdateGuardian,
+};
+use chrono::{Datelike, NaiveDate};
+use sqlx::Row;
+use tauri::AppHandle;
+
+const FAMILY_SELECT: &str = "SELECT id, name, notes, created_at FROM families";
+
+const GUARDIAN_SELECT: &str =
+    "SELECT id, name, phone, email, relationship, family_id, created_at, updated_at FROM guardians";
+
+fn family_from_row(row: &sqlx::sqlite::SqliteRow) -> Family {
+    Family {
+        id: row.get("id"),
+        name: row.get("name"),
+        notes: row.get("notes"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn guardian_from_row(row: &sqlx::sqlite::SqliteRow) -> Guardian {
+    Guardian {
+        id: row.get("id"),
+        name: row.get("name"),
+        phone: row.get("phone"),
+        email: row.get("email"),
+        relationship: row.get("relationship"),
+        family_id: row.get("family_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+async fn fetch_family(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Family, String> {
+    let row = sqlx::query(&format!("{} WHERE id = ?", FAMILY_SELECT))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(family_from_row(&row))
+}
+
+async fn fetch_guardian(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Guardian, String> {
+    let row = sqlx::query(&format!("{} WHERE id = ?", GUARDIAN_SELECT))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(guardian_from_row(&row))
+}
+
+/// First and last day of the month `date` falls in
+pub(crate) fn month_range(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let start = date.with_day(1).unwrap_or(date);
+    let next_month = if start.month() == 12 {
+        NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+    };
+    let end = next_month.and_then(|d| d.pred_opt()).unwrap_or(start);
+    (start, end)
+}
+
+// ==================== Families ====================
+
+#[tauri::command]
+pub async fn get_families(app: AppHandle) -> Result<Vec<Family>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!("{} ORDER BY name", FAMILY_SELECT))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(family_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn create_family(app: AppHandle, family: CreateFamily) -> Result<Family, String> {
+    let pool = get_pool(&app).await?;
+
+    let id = sqlx::query("INSERT INTO families (name, notes) VALUES (?, ?)")
+        .bind(&family.name)
+        .bind(&family.notes)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+
+    fetch_family(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn update_family(app: AppHandle, id: i64, family: UpdateFamily) -> Result<Family, String> {
+    let pool = get_pool(&app).await?;
+
+    sqlx::query("UPDATE families SET name = COALESCE(?, name), notes = ? WHERE id = ?")
+        .bind(&family.name)
+        .bind(&family.notes)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    fetch_family(&pool, id).await
+}
+
+/// Delete a family. Its athletes and guardians are kept without a family.
+#[tauri::command]
+pub async fn delete_family(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM families WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Add an athlete to a family, or remove them from their family with `None`
+#[tauri::command]
+pub async fn set_athlete_family(app: AppHandle, athlete_id: i64, family_id: Option<i64>) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("UPDATE athletes SET family_id = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(family_id)
+        .bind(athlete_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn get_family_athletes(app: AppHandle, family_id: i64) -> Result<Vec<Athlete>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(
+        r#"SELECT id, first_name, last_name, birth_year, birth_date, gender, club_name, photo_path, archived_at, family_id,
+                  photo_consent, photo_consent_date, photo_consent_note, created_at, updated_at
+        FROM athletes
+        WHERE family_id = ? AND archived_at IS NULL
+        ORDER BY COALESCE(birth_date, birth_year || '-12-31'), first_name"#
+    )
+    .bind(family_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| athlete_from_row!(row)).collect())
+}
+
+/// Competitions within a date range where any family member takes part.
+/// Without dates the current month is used. Multi-day competitions count
+/// when any of their days falls within the range.
+#[tauri::command]
+pub async fn get_family_competitions(
+    app: AppHandle,
+    family_id: i64,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<FamilyCompetition>, String> {
+    let pool = get_pool(&app).await?;
+
+    let (month_start, month_end) = month_range(chrono::Local::now().date_naive());
+    let start_date = start_date.unwrap_or_else(|| month_start.format("%Y-%m-%d").to_string());
+    let end_date = end_date.unwrap_or_else(|| month_end.format("%Y-%m-%d").to_string());
+
+    let rows = sqlx::query(
+        r#"SELECT c.id, c.name, c.date, c.end_date, c.location, c.address, c.level, c.custom_level_name, c.notes,
+                  c.reminder_enabled, c.reminder_days_before, c.created_at,
+                  GROUP_CONCAT(cp.athlete_id) as athlete_ids
+           FROM competitions c
+           JOIN competition_participants cp ON cp.competition_id = c.id
+           JOIN athletes a ON a.id = cp.athlete_id
+           WHERE a.family_id = ? AND a.archived_at IS NULL
+             AND c.date <= ? AND COALESCE(c.end_date, c.date) >= ?
+           GROUP BY c.id
+           ORDER BY c.date ASC"#
+    )
+    .bind(family_id)
+    .bind(&end_date)
+    .bind(&start_date)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let athlete_ids: Option<String> = row.get("athlete_ids");
+            FamilyCompetition {
+                competition: competition_from_row!(row),
+                athlete_ids: athlete_ids
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter_map(|id| id.parse().ok())
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+// ==================== Guardians ====================
+
+/// All guardians, optionally only those of one family
+#[tauri::command]
+pub async fn get_guardians(app: AppHandle, family_id: Option<i64>) -> Result<Vec<Guardian>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!("{} WHERE (? IS NULL OR family_id = ?) ORDER BY name", GUARDIAN_SELECT))
+        .bind(family_id)
+        .bind(family_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(guardian_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn get_athlete_guardians(app: AppHandle, athlete_id: i64) -> Result<Vec<Guardian>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(
+        r#"SELECT g.id, g.name, g.phone, g.email, g.relationship, g.family_id, g.created_at, g.updated_at
+        FROM guardians g
+        JOIN athlete_guardians ag ON ag.guardian_id = g.id
+        WHERE ag.athlete_id = ?
+        ORDER BY g.name"#
+    )
+    .bind(athlete_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(guardian_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn create_guardian(app: AppHandle, guardian: CreateGuardian) -> Result<Guardian, String> {
+    let pool = get_pool(&app).await?;
+
+    let id = sqlx::query(
+        "INSERT INTO guardians (name, phone, email, relationship, family_id) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&guardian.name)
+    .bind(&guardian.phone)
+    .bind(&guardian.email)
+    .bind(&guardian.relationship)
+    .bind(guardian.family_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+
+    fetch_guardian(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn update_guardian(app: AppHandle, id: i64, guardian: UpdateGuardian) -> Result<Guardian, String> {
+    let pool = get_pool(&app).await?;
+
+    sqlx::query(
+        r#"UPDATE guardians SET
+            name = COALESCE(?, name),
+            phone = ?,
+            email = ?,
+            relationship = ?,
+            family_id = ?,
+            updated_at = datetime('now')
+        WHERE id = ?"#
+    )
+    .bind(&guardian.name)
+    .bind(&guardian.phone)
+    .bind(&guardian.email)
+    .bind(&guardian.relationship)
+    .bind(guardian.family_id)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    fetch_guardian(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn delete_guardian(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM guardians WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn link_guardian(app: AppHandle, athlete_id: i64, guardian_id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("INSERT OR IGNORE INTO athlete_guardians (athlete_id, guardian_id) VALUES (?, ?)")
+        .bind(athlete_id)
+        .bind(guardian_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn unlink_guardian(app: AppHandle, athlete_id: i64, guardian_id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM athlete_guardians WHERE athlete_id = ? AND guardian_id = ?")
+        .bind(athlete_id)
+        .bind(guardian_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_month_range() {
+        assert_eq!(month_range(date("2025-06-14")), (date("2025-06-01"), date("2025-06-30")));
+        assert_eq!(month_range(date("2025-12-31")), (date("2025-12-01"), date("2025-12-31")));
+        assert_eq!(month_range(date("2024-02-10")), (date("2024-02-01"), date("2024-02-29")));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::database::get_pool;
+use crate::types::{CreateGoal, Goal, UpdateGoal};
+use sqlx::Row;
+use tauri::AppHandle;
+
+use chrono::Datelike;
+
+use super::evaluation::evaluate_goal_by_id;
+
+pub(crate) const GOAL_SELECT: &str =
+    "SELECT id, athlete_id, discipline_id, goal_type, target_value, target_date, status, achieved_at, achieved_result_id, skill_mark, season, created_at FROM goals";
+
+pub(crate) const GOAL_TYPES: &[&str] = &["value", "skill_mark", "competition_count", "pb_count", "improvement_percent"];
+
+/// Goal types that are tied to a single discipline
+pub(crate) fn goal_type_requires_discipline(goal_type: &str) -> bool {
+    !matches!(goal_type, "competition_count" | "pb_count")
+}
+
+pub(crate) async fn fetch_goal(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Goal, String> {
+    let query = format!("{} WHERE id = ?", GOAL_SELECT);
+    let row = sqlx::query(&query)
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(goal_from_row!(row))
+}
+
+#[tauri::command]
+pub async fn get_all_goals(app: AppHandle) -> Result<Vec<Goal>, String> {
+    let pool = get_pool(&app).await?;
+
+    let query = format!("{} ORDER BY created_at DESC", GOAL_SELECT);
+    let rows = sqlx::query(&query)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| goal_from_row!(row)).collect())
+}
+
+#[tauri::command]
+pub async fn get_goals_by_athlete(app: AppHandle, athlete_id: i64) -> Result<Vec<Goal>, String> {
+    let pool = get_pool(&app).await?;
+
+    let query = format!("{} WHERE athlete_id = ? ORDER BY created_at DESC", GOAL_SELECT);
+    let rows = sqlx::query(&query)
+        .bind(athlete_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| goal_from_row!(row)).collect())
+}
+
+#[tauri::command]
+pub async fn get_active_goals(app: AppHandle) -> Result<Vec<Goal>, String> {
+    let pool = get_pool(&app).await?;
+
+    let query = format!("{} WHERE status = 'active' ORDER BY target_date IS NULL, target_date ASC", GOAL_SELECT);
+    let rows = sqlx::query(&query)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| goal_from_row!(row)).collect())
+}
+
+#[tauri::command]
+pub async fn create_goal(app: AppHandle, goal: CreateGoal) -> Result<Goal, String> {
+    let pool = get_pool(&app).await?;
+
+    let goal_type = goal.goal_type.as_deref().unwrap_or("value");
+    if !GOAL_TYPES.contains(&goal_type) {
+        return Err(format!("Invalid goal type: {}", goal_type));
+    }
+    if goal_type_requires_discipline(goal_type) && goal.discipline_id.is_none() {
+        return Err(format!("Goal type {} requires a discipline", goal_type));
+    }
+    if goal_type == "skill_mark" && !matches!(goal.skill_mark.as_deref(), Some("A" | "B" | "C")) {
+        return Err("Skill mark goal requires skill mark A, B or C".to_string());
+    }
+
+    // Count and improvement goals are tracked per season (defaults to current year)
+    let season = match goal_type {
+        "competition_count" | "pb_count" | "improvement_percent" => {
+            Some(goal.season.unwrap_or_else(|| chrono::Local::now().year()))
+        }
+        _ => goal.season,
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO goals (athlete_id, discipline_id, goal_type, target_value, target_date, skill_mark, season) VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(goal.athlete_id)
+    .bind(goal.discipline_id)
+    .bind(goal_type)
+    .bind(goal.target_value)
+    .bind(&goal.target_date)
+    .bind(&goal.skill_mark)
+    .bind(season)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let id = result.last_insert_rowid();
+
+    // The goal may already be met by earlier results
+    evaluate_goal_by_id(&pool, id).await?;
+
+    fetch_goal(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn update_goal(app: AppHandle, id: i64, goal: UpdateGoal) -> Result<Goal, String> {
+    let pool = get_pool(&app).await?;
+
+    sqlx::query(
+        r#"UPDATE goals SET
+            target_value = COALESCE(?, target_value),
+            target_date = ?,
+            status = COALESCE(?, status)
+        WHERE id = ?"#
+    )
+    .bind(goal.target_value)
+    .bind(&goal.target_date)
+    .bind(&goal.status)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // A changed target may no longer be met by the linked result
+    if goal.target_value.is_some() {
+        evaluate_goal_by_id(&pool, id).await?;
+    }
+
+    fetch_goal(&pool, id).await
+}
+
+/// Mark a goal achieved by hand. Manually achieved goals have no linked result
+/// and are never reopened automatically.
+#[tauri::command]
+pub async fn mark_goal_achieved(app: AppHandle, id: i64) -> Result<Goal, String> {
+    let pool = get_pool(&app).await?;
+
+    sqlx::query(
+        "UPDATE goals SET status = 'achieved', achieved_at = datetime('now'), achieved_result_id = NULL WHERE id = ?"
+    )
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    fetch_goal(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn delete_goal(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM goals WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,446 @@
+use crate::commands::results::records::is_wind_assisted_on_date;
+use crate::types::Goal;
+use chrono::Datelike;
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+
+use super::crud::{fetch_goal, GOAL_SELECT};
+use super::skill_limits::{reaches_skill_mark, skill_mark_for_result, skill_mark_limit};
+
+// Goals that can be (re)evaluated: active ones and ones achieved by a result
+const EVALUABLE_GOALS: &str =
+    "(status = 'active' OR (status = 'achieved' AND achieved_result_id IS NOT NULL))";
+
+/// Whether `value` meets `target` in the discipline's direction
+pub(crate) fn meets_target(value: f64, target: f64, lower_is_better: bool) -> bool {
+    if lower_is_better {
+        value <= target
+    } else {
+        value >= target
+    }
+}
+
+/// Target value for an "improve N % over last season's SB" goal
+pub(crate) fn improvement_target(baseline: f64, percent: f64, lower_is_better: bool) -> f64 {
+    if lower_is_better {
+        baseline * (1.0 - percent / 100.0)
+    } else {
+        baseline * (1.0 + percent / 100.0)
+    }
+}
+
+/// Season a goal is tracked in (explicit season or the year the goal was created)
+pub(crate) fn goal_season(goal: &Goal) -> i32 {
+    goal.season.unwrap_or_else(|| {
+        goal.created_at
+            .get(..4)
+            .and_then(|y| y.parse().ok())
+            .unwrap_or_else(|| chrono::Local::now().year())
+    })
+}
+
+/// Chronological result used by PB counting
+pub(crate) struct PbCandidate {
+    pub id: i64,
+    pub date: String,
+    pub value: f64,
+    /// Discipline + equipment combination; PBs are tracked separately per key
+    pub key: String,
+    pub lower_is_better: bool,
+    pub wind_assisted: bool,
+}
+
+/// Results that were personal bests when they were made, in chronological order.
+/// Matches the PB engine: the first valid result in a discipline counts as a PB
+/// and wind-assisted results never do.
+pub(crate) fn personal_best_results(candidates: &[PbCandidate]) -> Vec<(i64, String)> {
+    records_when_made(candidates, |c| (c.key.as_str(), ""))
+}
+
+/// Results that were season bests when they were made, in chronological order.
+/// Same rules as `personal_best_results`, with the best kept per calendar year.
+pub(crate) fn season_best_results(candidates: &[PbCandidate]) -> Vec<(i64, String)> {
+    records_when_made(candidates, |c| (c.key.as_str(), c.date.get(..4).unwrap_or("")))
+}
+
+fn records_when_made<'a>(
+    candidates: &'a [PbCandidate],
+    group: impl Fn(&'a PbCandidate) -> (&'a str, &'a str),
+) -> Vec<(i64, String)> {
+    let mut best: HashMap<(&str, &str), f64> = HashMap::new();
+    let mut records = Vec::new();
+
+    for c in candidates.iter().filter(|c| !c.wind_assisted) {
+        let is_record = match best.get(&group(c)) {
+            None => true,
+            Some(previous) => {
+                if c.lower_is_better { c.value < *previous } else { c.value > *previous }
+            }
+        };
+        if is_record {
+            best.insert(group(c), c.value);
+            records.push((c.id, c.date.clone()));
+        }
+    }
+
+    records
+}
+
+/// The result that completes the Nth distinct competition, if reached.
+/// `results` are (result_id, date, competition_key) in chronological order.
+pub(crate) fn nth_competition_result(results: &[(i64, String, String)], target: usize) -> Option<(i64, String)> {
+    if target == 0 {
+        return None;
+    }
+    let mut seen = HashSet::new();
+    for (id, date, key) in results {
+        if seen.insert(key.as_str()) && seen.len() == target {
+            return Some((*id, date.clone()));
+        }
+    }
+    None
+}
+
+async fn lower_is_better(pool: &sqlx::Pool<sqlx::Sqlite>, discipline_id: i64) -> Result<bool, String> {
+    Ok(sqlx::query_scalar::<_, i32>("SELECT lower_is_better FROM disciplines WHERE id = ?")
+        .bind(discipline_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|v| v == 1)
+        .unwrap_or(true))
+}
+
+/// Best valid result of an athlete in a discipline during a season (season best)
+pub(crate) async fn season_best(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    discipline_id: i64,
+    season: i32,
+) -> Result<Option<f64>, String> {
+    let lower = lower_is_better(pool, discipline_id).await?;
+    let query = if lower {
+        "SELECT MIN(value) FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND COALESCE(status, 'valid') = 'valid' AND value > 0"
+    } else {
+        "SELECT MAX(value) FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND COALESCE(status, 'valid') = 'valid' AND value > 0"
+    };
+
+    sqlx::query_scalar(query)
+        .bind(athlete_id)
+        .bind(discipline_id)
+        .bind(season.to_string())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Effective target of a goal in result units, if it has one.
+/// Improvement goals have no target until the previous season has a result, and skill
+/// mark goals have none when no limit applies to the athlete's age on the target date.
+pub(crate) async fn effective_target(pool: &sqlx::Pool<sqlx::Sqlite>, goal: &Goal) -> Result<Option<f64>, String> {
+    match (goal.goal_type.as_str(), goal.discipline_id) {
+        ("skill_mark", Some(discipline_id)) => {
+            let Some(mark) = goal.skill_mark.as_deref() else {
+                return Ok(None);
+            };
+            let birth_year = athlete_birth_year(pool, goal.athlete_id).await?;
+            let date = goal
+                .target_date
+                .clone()
+                .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+            Ok(skill_mark_limit(discipline_id, birth_year, &date, mark))
+        }
+        ("improvement_percent", Some(discipline_id)) => {
+            let season = goal_season(goal);
+            let baseline = season_best(pool, goal.athlete_id, discipline_id, season - 1).await?;
+            let lower = lower_is_better(pool, discipline_id).await?;
+            Ok(baseline.map(|b| improvement_target(b, goal.target_value, lower)))
+        }
+        _ => Ok(Some(goal.target_value)),
+    }
+}
+
+/// First result meeting `target`, optionally limited to one season. Uses the
+/// same rules as the PB engine: wind-assisted results and combined event
+/// sub-results don't count, and only results made with the athlete's current
+/// implement (weight or hurdle height of the latest result) are compared.
+async fn first_result_meeting(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    discipline_id: i64,
+    target: f64,
+    season: Option<i32>,
+) -> Result<Option<(i64, String)>, String> {
+    let candidates = pb_candidates(pool, athlete_id, Some(discipline_id)).await?;
+    Ok(first_candidate_meeting(&candidates, target, season))
+}
+
+async fn athlete_birth_year(pool: &sqlx::Pool<sqlx::Sqlite>, athlete_id: i64) -> Result<i32, String> {
+    sqlx::query_scalar("SELECT birth_year FROM athletes WHERE id = ?")
+        .bind(athlete_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// First result whose skill mark is at least the target mark. The limit is resolved from
+/// the discipline and the athlete's age category in the year of each result.
+async fn first_result_reaching_skill_mark(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    discipline_id: i64,
+    target_mark: &str,
+) -> Result<Option<(i64, String)>, String> {
+    let birth_year = athlete_birth_year(pool, athlete_id).await?;
+    let candidates = pb_candidates(pool, athlete_id, Some(discipline_id)).await?;
+    Ok(candidates
+        .iter()
+        .filter(|c| !c.wind_assisted)
+        .find(|c| {
+            skill_mark_for_result(c.value, discipline_id, birth_year, &c.date, c.lower_is_better)
+                .is_some_and(|mark| reaches_skill_mark(mark, target_mark))
+        })
+        .map(|c| (c.id, c.date.clone())))
+}
+
+pub(crate) fn first_candidate_meeting(
+    candidates: &[PbCandidate],
+    target: f64,
+    season: Option<i32>,
+) -> Option<(i64, String)> {
+    let current_key = candidates.last()?.key.as_str();
+    let season_prefix = season.map(|s| s.to_string());
+
+    candidates
+        .iter()
+        .filter(|c| c.key == current_key && !c.wind_assisted)
+        .filter(|c| season_prefix.as_deref().is_none_or(|prefix| c.date.starts_with(prefix)))
+        .find(|c| meets_target(c.value, target, c.lower_is_better))
+        .map(|c| (c.id, c.date.clone()))
+}
+
+/// Competition results of the season in chronological order, keyed by competition.
+/// Results are linked to the competitions table by name and date when possible.
+pub(crate) async fn season_competition_results(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    discipline_id: Option<i64>,
+    season: i32,
+) -> Result<Vec<(i64, String, String)>, String> {
+    let rows = sqlx::query(
+        r#"SELECT r.id, r.date,
+                  COALESCE('c' || (SELECT c.id FROM competitions c
+                                   WHERE LOWER(TRIM(c.name)) = LOWER(TRIM(r.competition_name))
+                                     AND r.date BETWEEN c.date AND COALESCE(c.end_date, c.date)
+                                   LIMIT 1),
+                           LOWER(TRIM(COALESCE(r.competition_name, ''))) || '|' || r.date) as competition_key
+           FROM results r
+           WHERE r.athlete_id = ? AND r.type = 'competition'
+             AND COALESCE(r.status, 'valid') != 'dns'
+             AND r.combined_event_id IS NULL
+             AND (? IS NULL OR r.discipline_id = ?)
+             AND strftime('%Y', r.date) = ?
+           ORDER BY r.date ASC, r.id ASC"#
+    )
+    .bind(athlete_id)
+    .bind(discipline_id)
+    .bind(discipline_id)
+    .bind(season.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("date"), row.get("competition_key")))
+        .collect())
+}
+
+/// All valid results of an athlete as PB candidates, in chronological order
+pub(crate) async fn pb_candidates(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    discipline_id: Option<i64>,
+) -> Result<Vec<PbCandidate>, String> {
+    let rows = sqlx::query(
+        r#"SELECT r.id, r.date, r.value, r.wind, r.discipline_id, r.equipment_weight, r.hurdle_height,
+                  d.name as discipline_name, d.lower_is_better, a.birth_year, a.birth_date
+           FROM results r
+           JOIN disciplines d ON r.discipline_id = d.id
+           JOIN athletes a ON r.athlete_id = a.id
+           WHERE r.athlete_id = ?
+             AND (? IS NULL OR r.discipline_id = ?)
+             AND COALESCE(r.status, 'valid') = 'valid' AND r.value > 0
+             AND r.combined_event_id IS NULL
+           ORDER BY r.date ASC, r.id ASC"#
+    )
+    .bind(athlete_id)
+    .bind(discipline_id)
+    .bind(discipline_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let date: String = row.get("date");
+            let birth_date: Option<String> = row.get("birth_date");
+            let discipline_name: String = row.get("discipline_name");
+            let equipment_weight: Option<f64> = row.get("equipment_weight");
+            let hurdle_height: Option<i32> = row.get("hurdle_height");
+            PbCandidate {
+                id: row.get("id"),
+                key: format!(
+                    "{}|{}|{}",
+                    row.get::<i64, _>("discipline_id"),
+                    equipment_weight.map(|w| w.to_string()).unwrap_or_default(),
+                    hurdle_height.map(|h| h.to_string()).unwrap_or_default()
+                ),
+                value: row.get("value"),
+                lower_is_better: row.get::<i32, _>("lower_is_better") == 1,
+                wind_assisted: is_wind_assisted_on_date(
+                    row.get("wind"),
+                    &discipline_name,
+                    row.get("birth_year"),
+                    birth_date.as_deref(),
+                    &date,
+                ),
+                date,
+            }
+        })
+        .collect())
+}
+
+/// Find the result that achieves a goal, according to its type
+pub(crate) async fn find_achieving_result(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    goal: &Goal,
+) -> Result<Option<(i64, String)>, String> {
+    let season = goal_season(goal);
+    let target_count = goal.target_value.max(0.0).ceil() as usize;
+
+    match goal.goal_type.as_str() {
+        "competition_count" => {
+            let results = season_competition_results(pool, goal.athlete_id, goal.discipline_id, season).await?;
+            Ok(nth_competition_result(&results, target_count))
+        }
+        "pb_count" => {
+            let candidates = pb_candidates(pool, goal.athlete_id, goal.discipline_id).await?;
+            let season_prefix = season.to_string();
+            Ok(personal_best_results(&candidates)
+                .into_iter()
+                .filter(|(_, date)| date.starts_with(&season_prefix))
+                .nth(target_count.saturating_sub(1))
+                .filter(|_| target_count > 0))
+        }
+        "improvement_percent" => {
+            let (Some(discipline_id), Some(target)) = (goal.discipline_id, effective_target(pool, goal).await?) else {
+                return Ok(None);
+            };
+            first_result_meeting(pool, goal.athlete_id, discipline_id, target, Some(season)).await
+        }
+        "skill_mark" => {
+            let (Some(discipline_id), Some(target_mark)) = (goal.discipline_id, goal.skill_mark.as_deref()) else {
+                return Ok(None);
+            };
+            first_result_reaching_skill_mark(pool, goal.athlete_id, discipline_id, target_mark).await
+        }
+        // "value"
+        _ => {
+            let Some(discipline_id) = goal.discipline_id else {
+                return Ok(None);
+            };
+            first_result_meeting(pool, goal.athlete_id, discipline_id, goal.target_value, None).await
+        }
+    }
+}
+
+/// Achieve or reopen the given goals. Returns the goals whose state changed.
+async fn apply_evaluation(pool: &sqlx::Pool<sqlx::Sqlite>, goals: Vec<Goal>) -> Result<Vec<Goal>, String> {
+    let mut changed = Vec::new();
+
+    for goal in goals {
+        match find_achieving_result(pool, &goal).await? {
+            Some((result_id, date)) => {
+                if goal.status == "achieved" && goal.achieved_result_id == Some(result_id) {
+                    continue;
+                }
+                sqlx::query(
+                    "UPDATE goals SET status = 'achieved', achieved_at = ?, achieved_result_id = ? WHERE id = ?"
+                )
+                .bind(&date)
+                .bind(result_id)
+                .bind(goal.id)
+                .execute(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            }
+            None => {
+                if goal.status == "active" {
+                    continue;
+                }
+                sqlx::query(
+                    "UPDATE goals SET status = 'active', achieved_at = NULL, achieved_result_id = NULL WHERE id = ?"
+                )
+                .bind(goal.id)
+                .execute(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            }
+        }
+
+        changed.push(fetch_goal(pool, goal.id).await?);
+    }
+
+    Ok(changed)
+}
+
+async fn load_goals(pool: &sqlx::Pool<sqlx::Sqlite>, query: &str, binds: &[i64]) -> Result<Vec<Goal>, String> {
+    let mut q = sqlx::query(query);
+    for bind in binds {
+        q = q.bind(bind);
+    }
+    Ok(q.fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| goal_from_row!(row))
+        .collect())
+}
+
+/// Re-evaluate goals affected by a result change of one athlete/discipline.
+///
+/// Active goals that are met become achieved, linked to the result that met
+/// them and dated by that result. Goals achieved by a result are reopened when
+/// they are no longer met (result deleted or edited). Manually achieved goals
+/// (no linked result) and abandoned goals are left alone.
+/// Discipline-independent goals (competition and PB counts) are always included.
+/// Returns the goals whose state changed.
+pub(crate) async fn evaluate_goals(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: i64,
+    discipline_id: i64,
+) -> Result<Vec<Goal>, String> {
+    let query = format!(
+        "{} WHERE athlete_id = ? AND (discipline_id = ? OR goal_type IN ('competition_count', 'pb_count')) AND {}",
+        GOAL_SELECT, EVALUABLE_GOALS
+    );
+    let goals = load_goals(pool, &query, &[athlete_id, discipline_id]).await?;
+    apply_evaluation(pool, goals).await
+}
+
+/// Re-evaluate a single goal (after it was created or its target changed)
+pub(crate) async fn evaluate_goal_by_id(pool: &sqlx::Pool<sqlx::Sqlite>, goal_id: i64) -> Result<Vec<Goal>, String> {
+    let query = format!("{} WHERE id = ? AND {}", GOAL_SELECT, EVALUABLE_GOALS);
+    let goals = load_goals(pool, &query, &[goal_id]).await?;
+    apply_evaluation(pool, goals).await
+}
+
+/// Re-evaluate all goals (after a data import)
+pub(crate) async fn evaluate_all_goals(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<(), String> {
+    let query = format!("{} WHERE {}", GOAL_SELECT, EVALUABLE_GOALS);
+    let goals = load_goals(pool, &query, &[]).await?;
+    apply_evaluation(pool, goals).await?;
+    Ok(())
+}
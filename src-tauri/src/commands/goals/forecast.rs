@@ -0,0 +1,356 @@
+use crate::database::get_pool;
+use crate::types::{Goal, GoalForecast};
+use chrono::{Datelike, NaiveDate};
+use sqlx::Row;
+use tauri::AppHandle;
+
+use super::crud::{fetch_goal, GOAL_SELECT};
+use super::evaluation::{
+    effective_target, goal_season, meets_target, pb_candidates, personal_best_results,
+    season_competition_results,
+};
+
+// Minimum number of results and time span for a meaningful trend
+const MIN_TREND_RESULTS: usize = 3;
+const MIN_TREND_SPAN_DAYS: f64 = 14.0;
+
+// Only the recent trend matters for young athletes
+const TREND_WINDOW_DAYS: i64 = 730;
+
+// Without a target date, projections further than this are not realistic
+const NO_DEADLINE_ON_TRACK_DAYS: i64 = 365;
+const NO_DEADLINE_AT_RISK_DAYS: i64 = 730;
+
+// Minimum slack after target date that still counts as "at risk"
+const MIN_AT_RISK_SLACK_DAYS: i64 = 30;
+
+/// Least squares fit of ln(value) = a + b * day. Returns (a, b).
+pub(crate) fn fit_log_trend(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|(_, v)| *v > 0.0)
+        .map(|(t, v)| (*t, v.ln()))
+        .collect();
+
+    if points.len() < MIN_TREND_RESULTS {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let var_t: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    let span = points.iter().map(|(t, _)| *t).fold(f64::MIN, f64::max)
+        - points.iter().map(|(t, _)| *t).fold(f64::MAX, f64::min);
+    if span < MIN_TREND_SPAN_DAYS || var_t == 0.0 {
+        return None;
+    }
+
+    let cov: f64 = points.iter().map(|(t, y)| (t - mean_t) * (y - mean_y)).sum();
+    let b = cov / var_t;
+    let a = mean_y - b * mean_t;
+
+    Some((a, b))
+}
+
+/// Day on which the trend line reaches `target`, or None if the trend is not improving
+pub(crate) fn project_target_day(trend: (f64, f64), target: f64, lower_is_better: bool) -> Option<f64> {
+    let (a, b) = trend;
+    let improving = if lower_is_better { b < 0.0 } else { b > 0.0 };
+    if !improving || target <= 0.0 {
+        return None;
+    }
+    Some((target.ln() - a) / b)
+}
+
+/// Compare the projected date with the target date
+pub(crate) fn assess_projection(
+    projected: Option<NaiveDate>,
+    target_date: Option<NaiveDate>,
+    today: NaiveDate,
+) -> &'static str {
+    let Some(projected) = projected else {
+        return "unrealistic";
+    };
+
+    match target_date {
+        Some(target_date) => {
+            if target_date < today {
+                return "unrealistic";
+            }
+            if projected <= target_date {
+                return "on_track";
+            }
+            // Allow slack proportional to the remaining time
+            let window = (target_date - today).num_days();
+            let slack = (window / 2).max(MIN_AT_RISK_SLACK_DAYS);
+            if (projected - target_date).num_days() <= slack {
+                "at_risk"
+            } else {
+                "unrealistic"
+            }
+        }
+        None => {
+            let days = (projected - today).num_days();
+            if days <= NO_DEADLINE_ON_TRACK_DAYS {
+                "on_track"
+            } else if days <= NO_DEADLINE_AT_RISK_DAYS {
+                "at_risk"
+            } else {
+                "unrealistic"
+            }
+        }
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d").ok()
+}
+
+/// Build a forecast from a goal and its athlete's valid results in the discipline.
+/// `target` is the goal's effective target value (derived for improvement goals).
+pub(crate) fn build_forecast(
+    goal: &Goal,
+    target: f64,
+    lower_is_better: bool,
+    results: &[(NaiveDate, f64)],
+    today: NaiveDate,
+) -> GoalForecast {
+    let target_date = goal.target_date.as_deref().and_then(parse_date);
+    let days_remaining = target_date.map(|d| (d - today).num_days());
+
+    let current_best = if lower_is_better {
+        results.iter().map(|(_, v)| *v).reduce(f64::min)
+    } else {
+        results.iter().map(|(_, v)| *v).reduce(f64::max)
+    };
+
+    let gap = current_best.map(|best| {
+        let gap = if lower_is_better { best - target } else { target - best };
+        gap.max(0.0)
+    });
+
+    let mut forecast = GoalForecast {
+        goal_id: goal.id,
+        current_best,
+        target: Some(target),
+        gap,
+        projected_date: None,
+        assessment: "insufficient_data".to_string(),
+        days_remaining,
+        result_count: 0,
+    };
+
+    if goal.status == "achieved"
+        || current_best.is_some_and(|best| meets_target(best, target, lower_is_better))
+    {
+        forecast.assessment = "achieved".to_string();
+        return forecast;
+    }
+
+    let window_start = today - chrono::Duration::days(TREND_WINDOW_DAYS);
+    let recent: Vec<&(NaiveDate, f64)> = results.iter().filter(|(d, _)| *d >= window_start).collect();
+    let used: Vec<&(NaiveDate, f64)> = if recent.len() >= MIN_TREND_RESULTS {
+        recent
+    } else {
+        results.iter().collect()
+    };
+    forecast.result_count = used.len() as i32;
+
+    // Days are counted from today so the intercept stays small
+    let points: Vec<(f64, f64)> = used
+        .iter()
+        .map(|(d, v)| ((*d - today).num_days() as f64, *v))
+        .collect();
+
+    let Some(trend) = fit_log_trend(&points) else {
+        return forecast;
+    };
+
+    let projected = project_target_day(trend, target, lower_is_better).map(|day| {
+        // The trend may already be past the target even though no result is; that means "now"
+        let day = day.clamp(0.0, 36_500.0);
+        today + chrono::Duration::days(day.ceil() as i64)
+    });
+
+    forecast.projected_date = projected.map(|d| d.format("%Y-%m-%d").to_string());
+    forecast.assessment = assess_projection(projected, target_date, today).to_string();
+    forecast
+}
+
+/// Forecast for a count goal (competitions or PBs) from the dates counted so far
+/// this season. Progress is projected at the season's average rate, and the
+/// deadline defaults to the end of the season.
+pub(crate) fn build_count_forecast(
+    goal: &Goal,
+    season: i32,
+    counted_dates: &[NaiveDate],
+    today: NaiveDate,
+) -> GoalForecast {
+    let season_start = NaiveDate::from_ymd_opt(season, 1, 1).unwrap_or(today);
+    let season_end = NaiveDate::from_ymd_opt(season, 12, 31).unwrap_or(today);
+    let target_date = goal.target_date.as_deref().and_then(parse_date).or(Some(season_end));
+    let count = counted_dates.len() as f64;
+
+    let mut forecast = GoalForecast {
+        goal_id: goal.id,
+        current_best: Some(count),
+        target: Some(goal.target_value),
+        gap: Some((goal.target_value - count).max(0.0)),
+        projected_date: None,
+        assessment: "insufficient_data".to_string(),
+        days_remaining: target_date.map(|d| (d - today).num_days()),
+        result_count: counted_dates.len() as i32,
+    };
+
+    if goal.status == "achieved" || count >= goal.target_value {
+        forecast.assessment = "achieved".to_string();
+        return forecast;
+    }
+
+    let elapsed = (today.min(season_end) - season_start).num_days() as f64;
+    if count == 0.0 || elapsed < MIN_TREND_SPAN_DAYS {
+        return forecast;
+    }
+
+    let per_day = count / elapsed;
+    let days_needed = ((goal.target_value - count) / per_day).clamp(0.0, 36_500.0);
+    let projected = today + chrono::Duration::days(days_needed.ceil() as i64);
+
+    forecast.projected_date = Some(projected.format("%Y-%m-%d").to_string());
+    forecast.assessment = assess_projection(Some(projected), target_date, today).to_string();
+    forecast
+}
+
+/// Lower rank = more urgent
+fn urgency_rank(assessment: &str) -> i32 {
+    match assessment {
+        "unrealistic" => 0,
+        "at_risk" => 1,
+        "insufficient_data" => 2,
+        "on_track" => 3,
+        _ => 4,
+    }
+}
+
+async fn forecast_goal(pool: &sqlx::Pool<sqlx::Sqlite>, goal: &Goal) -> Result<GoalForecast, String> {
+    let today = chrono::Local::now().date_naive();
+    let season = goal_season(goal);
+
+    match goal.goal_type.as_str() {
+        "competition_count" => {
+            let dates: Vec<NaiveDate> = season_competition_results(pool, goal.athlete_id, goal.discipline_id, season)
+                .await?
+                .iter()
+                .fold(Vec::<(String, NaiveDate)>::new(), |mut seen, (_, date, key)| {
+                    if let Some(d) = parse_date(date) {
+                        if !seen.iter().any(|(k, _)| k == key) {
+                            seen.push((key.clone(), d));
+                        }
+                    }
+                    seen
+                })
+                .into_iter()
+                .map(|(_, d)| d)
+                .collect();
+            return Ok(build_count_forecast(goal, season, &dates, today));
+        }
+        "pb_count" => {
+            let candidates = pb_candidates(pool, goal.athlete_id, goal.discipline_id).await?;
+            let dates: Vec<NaiveDate> = personal_best_results(&candidates)
+                .iter()
+                .filter_map(|(_, date)| parse_date(date))
+                .filter(|d| d.year() == season)
+                .collect();
+            return Ok(build_count_forecast(goal, season, &dates, today));
+        }
+        _ => {}
+    }
+
+    let lower_is_better: bool = sqlx::query_scalar::<_, i32>("SELECT lower_is_better FROM disciplines WHERE id = ?")
+        .bind(goal.discipline_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|v| v == 1)
+        .unwrap_or(true);
+
+    // Improvement goals only count results of their own season
+    let season_filter = (goal.goal_type == "improvement_percent").then(|| season.to_string());
+
+    let results: Vec<(NaiveDate, f64)> = sqlx::query(
+        r#"SELECT date, value FROM results
+        WHERE athlete_id = ? AND discipline_id = ?
+          AND COALESCE(status, 'valid') = 'valid' AND value > 0
+          AND (? IS NULL OR strftime('%Y', date) = ?)
+        ORDER BY date ASC"#
+    )
+    .bind(goal.athlete_id)
+    .bind(goal.discipline_id)
+    .bind(&season_filter)
+    .bind(&season_filter)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .filter_map(|row| {
+        let date: String = row.get("date");
+        parse_date(&date).map(|d| (d, row.get("value")))
+    })
+    .collect();
+
+    let Some(target) = effective_target(pool, goal).await? else {
+        // Improvement goal without a previous season, or skill mark goal outside the limit ages
+        return Ok(GoalForecast {
+            goal_id: goal.id,
+            current_best: None,
+            target: None,
+            gap: None,
+            projected_date: None,
+            assessment: "insufficient_data".to_string(),
+            days_remaining: goal.target_date.as_deref().and_then(parse_date).map(|d| (d - today).num_days()),
+            result_count: results.len() as i32,
+        });
+    };
+
+    Ok(build_forecast(goal, target, lower_is_better, &results, today))
+}
+
+#[tauri::command]
+pub async fn get_goal_forecast(app: AppHandle, goal_id: i64) -> Result<GoalForecast, String> {
+    let pool = get_pool(&app).await?;
+    let goal = fetch_goal(&pool, goal_id).await?;
+    forecast_goal(&pool, &goal).await
+}
+
+/// Forecasts for all active goals (optionally one athlete), most urgent first
+#[tauri::command]
+pub async fn get_goal_forecasts(app: AppHandle, athlete_id: Option<i64>) -> Result<Vec<GoalForecast>, String> {
+    let pool = get_pool(&app).await?;
+
+    let query = format!("{} WHERE status = 'active' AND (? IS NULL OR athlete_id = ?)", GOAL_SELECT);
+    let goals: Vec<Goal> = sqlx::query(&query)
+        .bind(athlete_id)
+        .bind(athlete_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| goal_from_row!(row))
+        .collect();
+
+    let mut forecasts = Vec::with_capacity(goals.len());
+    for goal in &goals {
+        forecasts.push(forecast_goal(&pool, goal).await?);
+    }
+
+    forecasts.sort_by_key(|f| {
+        (
+            urgency_rank(&f.assessment),
+            f.days_remaining.unwrap_or(i64::MAX),
+        )
+    });
+
+    Ok(forecasts)
+}
@@ -0,0 +1,11 @@
+// Re-export everything for generate_handler! macro to access __cmd__ items
+pub mod crud;
+pub(crate) mod evaluation;
+pub mod forecast;
+pub(crate) mod skill_limits;
+
+pub use crud::*;
+pub use forecast::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,247 @@
+//! Skill mark (taitomerkki) limits for youth athletics.
+//!
+//! Same SUL limits and discipline mapping as `src/data/skillLimits.ts`, so skill mark
+//! goals are achieved by exactly the results that show an A/B/C badge.
+
+use AgeCategory::*;
+
+const MARKS: [&str; 3] = ["A", "B", "C"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AgeCategory {
+    T8,
+    T9,
+    T11,
+    T13,
+    T15,
+}
+
+/// Limits valid until 31.12.2025, as (category, skill key, [A, B, C])
+const LIMITS_2014: &[(AgeCategory, &str, [f64; 3])] = &[
+    (T8, "40m", [7.20, 7.35, 7.80]),
+    (T8, "1000m", [247.0, 260.0, 280.0]), // 4:07, 4:20, 4:40
+    (T8, "600m_kavely", [265.0, 305.0, 340.0]), // 4:25, 5:05, 5:40
+    (T8, "korkeus", [1.00, 0.90, 0.80]),
+    (T8, "seivas", [1.30, 1.10, 0.90]),
+    (T8, "pituus", [3.30, 3.10, 2.85]),
+    (T8, "3-loikka", [7.30, 6.70, 6.00]),
+    (T8, "kuula_2kg", [5.30, 4.70, 4.20]),
+    (T8, "kiekko_600g", [12.00, 9.00, 7.00]),
+    (T8, "moukari_2.5kg", [10.50, 8.50, 7.00]),
+    (T8, "keihas_400g", [13.50, 11.00, 8.50]),
+    (T8, "3-ottelu", [450.0, 370.0, 270.0]),
+    (T8, "4-ottelu", [490.0, 380.0, 290.0]),
+    (T9, "40m", [6.95, 7.15, 7.45]),
+    (T9, "1000m", [242.0, 256.0, 274.0]), // 4:02, 4:16, 4:34
+    (T9, "600m_kavely", [260.0, 270.0, 290.0]), // 4:20, 4:30, 5:30 (approximate)
+    (T9, "korkeus", [1.06, 1.00, 0.90]),
+    (T9, "seivas", [1.30, 1.10, 0.90]),
+    (T9, "pituus", [3.50, 3.30, 3.10]),
+    (T9, "3-loikka", [7.70, 7.10, 6.30]),
+    (T9, "kuula_2kg", [5.80, 5.25, 4.75]),
+    (T9, "kiekko_600g", [14.00, 11.50, 8.50]),
+    (T9, "moukari_2.5kg", [14.00, 12.00, 8.50]),
+    (T9, "keihas_400g", [15.00, 13.00, 9.50]),
+    (T9, "3-ottelu", [550.0, 450.0, 350.0]),
+    (T9, "4-ottelu", [725.0, 600.0, 400.0]),
+    (T11, "60m", [9.10, 9.45, 9.75]),
+    (T11, "1000m", [218.0, 233.0, 250.0]), // 3:38, 3:53, 4:10
+    (T11, "60m_aj", [11.10, 12.00, 13.20]),
+    (T11, "1000m_kavely", [405.0, 445.0, 490.0]), // 6:45, 7:25, 8:10
+    (T11, "korkeus", [1.28, 1.19, 1.13]),
+    (T11, "seivas", [1.60, 1.35, 1.10]),
+    (T11, "pituus", [4.20, 3.95, 3.70]),
+    (T11, "3-loikka", [8.60, 7.90, 7.10]),
+    (T11, "kuula_2kg", [8.40, 7.50, 6.75]),
+    (T11, "kiekko_600g", [19.00, 15.00, 10.00]),
+    (T11, "moukari_2.5kg", [19.00, 15.00, 10.00]),
+    (T11, "keihas_400g", [23.00, 18.00, 12.00]),
+    (T11, "3-ottelu", [850.0, 700.0, 550.0]),
+    (T11, "4-ottelu", [1200.0, 1000.0, 725.0]),
+    (T13, "60m", [8.65, 8.95, 9.40]),
+    (T13, "200m", [29.00, 30.50, 33.00]),
+    (T13, "1000m", [206.0, 222.0, 250.0]), // 3:26, 3:42, 4:10
+    (T13, "60m_aj", [10.60, 11.50, 13.30]),
+    (T13, "200m_aj", [34.00, 36.40, 40.00]),
+    (T13, "2000m_kavely", [780.0, 870.0, 990.0]), // 13:00, 14:30, 16:30
+    (T13, "korkeus", [1.43, 1.34, 1.19]),
+    (T13, "seivas", [2.05, 1.80, 1.45]),
+    (T13, "pituus", [4.55, 4.30, 3.80]),
+    (T13, "3-loikka", [9.40, 9.00, 7.80]),
+    (T13, "kuula_3kg", [9.80, 8.90, 7.50]),
+    (T13, "kiekko_750g", [27.00, 22.00, 16.00]),
+    (T13, "moukari_3kg", [27.00, 22.00, 16.00]),
+    (T13, "keihas_500g", [29.00, 24.00, 17.50]),
+    (T13, "4-ottelu", [1350.0, 1100.0, 750.0]),
+    (T13, "5-ottelu", [1750.0, 1425.0, 1050.0]),
+    (T15, "100m", [13.40, 13.95, 14.80]),
+    (T15, "300m", [44.70, 46.80, 49.50]),
+    (T15, "800m", [153.0, 163.0, 173.0]), // 2:33, 2:43, 2:53
+    (T15, "2000m", [460.0, 490.0, 540.0]), // 7:40, 8:10, 9:00
+    (T15, "1500m_ej", [380.0, 405.0, 440.0]), // 6:20, 6:45, 7:20 (estejuoksu)
+    (T15, "80m_aj", [13.10, 13.95, 14.90]),
+    (T15, "300m_aj", [49.50, 52.00, 54.50]),
+    (T15, "3000m_kavely", [1150.0, 1260.0, 1380.0]), // 19:10, 21:00, 23:00
+    (T15, "korkeus", [1.49, 1.39, 1.26]),
+    (T15, "seivas", [2.20, 1.95, 1.60]),
+    (T15, "pituus", [4.90, 4.50, 3.90]),
+    (T15, "3-loikka", [10.20, 9.50, 8.60]),
+    (T15, "kuula_3kg", [10.20, 9.40, 8.40]),
+    (T15, "kiekko_1kg", [28.00, 24.00, 19.00]),
+    (T15, "moukari_3kg", [32.00, 26.00, 21.00]),
+    (T15, "keihas_500g", [34.00, 29.00, 23.00]),
+    (T15, "5-ottelu", [1900.0, 1550.0, 1100.0]),
+];
+
+/// Limits valid from 1.1.2026. Only T8 has been published so far.
+const LIMITS_2026: &[(AgeCategory, &str, [f64; 3])] = &[
+    (T8, "40m", [7.20, 7.35, 7.80]),
+    (T8, "150m", [27.30, 28.50, 30.50]),
+    (T8, "800m", [197.0, 207.0, 222.0]), // 3:17, 3:27, 3:42
+    (T8, "60m_aj", [13.20, 14.00, 15.80]),
+    (T8, "800m_kavely", [380.0, 418.0, 485.0]), // 6:20, 6:58, 8:05
+    (T8, "korkeus", [1.00, 0.90, 0.80]),
+    (T8, "seivas", [1.30, 1.10, 0.90]),
+    (T8, "pituus", [3.30, 3.10, 2.85]),
+    (T8, "3-loikka", [6.80, 6.00, 5.00]),
+    (T8, "kuula_2kg", [4.70, 4.20, 3.80]),
+    (T8, "kiekko_600g", [10.00, 8.00, 6.00]),
+    (T8, "moukari_2.5kg", [9.00, 7.00, 5.00]),
+    (T8, "keihas_400g", [10.00, 8.00, 6.00]),
+    (T8, "3-ottelu", [400.0, 300.0, 250.0]),
+    (T8, "4-ottelu", [450.0, 350.0, 300.0]),
+];
+
+/// Discipline ID to skill key per age category. Throws use the implement of the category.
+const DISCIPLINE_SKILL_KEYS: &[(i64, AgeCategory, &str)] = &[
+    (1, T8, "40m"),
+    (1, T9, "40m"),
+    (2, T11, "60m"),
+    (2, T13, "60m"),
+    (3, T15, "100m"),
+    (4, T8, "150m"),
+    (5, T13, "200m"),
+    (6, T15, "300m"),
+    (9, T8, "800m"),
+    (9, T15, "800m"),
+    (10, T8, "1000m"),
+    (10, T9, "1000m"),
+    (10, T11, "1000m"),
+    (10, T13, "1000m"),
+    (12, T15, "2000m"),
+    (16, T8, "60m_aj"),
+    (16, T11, "60m_aj"),
+    (16, T13, "60m_aj"),
+    (17, T15, "80m_aj"),
+    (19, T13, "200m_aj"),
+    (20, T15, "300m_aj"),
+    (22, T8, "pituus"),
+    (22, T9, "pituus"),
+    (22, T11, "pituus"),
+    (22, T13, "pituus"),
+    (22, T15, "pituus"),
+    (23, T8, "korkeus"),
+    (23, T9, "korkeus"),
+    (23, T11, "korkeus"),
+    (23, T13, "korkeus"),
+    (23, T15, "korkeus"),
+    (24, T8, "3-loikka"),
+    (24, T9, "3-loikka"),
+    (24, T11, "3-loikka"),
+    (24, T13, "3-loikka"),
+    (24, T15, "3-loikka"),
+    (25, T8, "seivas"),
+    (25, T9, "seivas"),
+    (25, T11, "seivas"),
+    (25, T13, "seivas"),
+    (25, T15, "seivas"),
+    (26, T8, "kuula_2kg"),
+    (26, T9, "kuula_2kg"),
+    (26, T11, "kuula_2kg"),
+    (26, T13, "kuula_3kg"),
+    (26, T15, "kuula_3kg"),
+    (27, T8, "kiekko_600g"),
+    (27, T9, "kiekko_600g"),
+    (27, T11, "kiekko_600g"),
+    (27, T13, "kiekko_750g"),
+    (27, T15, "kiekko_1kg"),
+    (28, T8, "keihas_400g"),
+    (28, T9, "keihas_400g"),
+    (28, T11, "keihas_400g"),
+    (28, T13, "keihas_500g"),
+    (28, T15, "keihas_500g"),
+    (29, T8, "moukari_2.5kg"),
+    (29, T9, "moukari_2.5kg"),
+    (29, T11, "moukari_2.5kg"),
+    (29, T13, "moukari_3kg"),
+    (29, T15, "moukari_3kg"),
+    (31, T8, "3-ottelu"),
+    (31, T9, "3-ottelu"),
+    (31, T11, "3-ottelu"),
+    (32, T8, "4-ottelu"),
+    (32, T9, "4-ottelu"),
+    (32, T11, "4-ottelu"),
+    (32, T13, "4-ottelu"),
+    (33, T13, "5-ottelu"),
+    (33, T15, "5-ottelu"),
+    (35, T8, "600m_kavely"),
+    (35, T9, "600m_kavely"),
+    (36, T8, "800m_kavely"),
+    (41, T11, "1000m_kavely"),
+    (37, T13, "2000m_kavely"),
+    (38, T15, "3000m_kavely"),
+];
+
+/// Age category by the age the athlete turns during the result year
+fn age_category(birth_year: i32, result_year: i32) -> Option<AgeCategory> {
+    match result_year - birth_year {
+        8 => Some(T8),
+        9..=10 => Some(T9),
+        11..=12 => Some(T11),
+        13..=14 => Some(T13),
+        15..=16 => Some(T15),
+        _ => None,
+    }
+}
+
+/// [A, B, C] limits of a discipline for the athlete's age category on the given date
+fn skill_limits(discipline_id: i64, birth_year: i32, date: &str) -> Option<[f64; 3]> {
+    let year: i32 = date.get(..4)?.parse().ok()?;
+    let category = age_category(birth_year, year)?;
+    let (_, _, key) = DISCIPLINE_SKILL_KEYS
+        .iter()
+        .find(|(id, c, _)| *id == discipline_id && *c == category)?;
+
+    let table = if date >= "2026-01-01" { LIMITS_2026 } else { LIMITS_2014 };
+    table
+        .iter()
+        .find(|(c, k, _)| *c == category && k == key)
+        .map(|(_, _, limits)| *limits)
+}
+
+/// Limit of the given mark ("A", "B" or "C") on the given date
+pub(crate) fn skill_mark_limit(discipline_id: i64, birth_year: i32, date: &str, mark: &str) -> Option<f64> {
+    let limits = skill_limits(discipline_id, birth_year, date)?;
+    MARKS.iter().position(|m| *m == mark).map(|i| limits[i])
+}
+
+/// Skill mark earned by a result, or None when no limit applies
+pub(crate) fn skill_mark_for_result(
+    value: f64,
+    discipline_id: i64,
+    birth_year: i32,
+    date: &str,
+    lower_is_better: bool,
+) -> Option<&'static str> {
+    let limits = skill_limits(discipline_id, birth_year, date)?;
+    MARKS
+        .into_iter()
+        .zip(limits)
+        .find(|&(_, limit)| if lower_is_better { value <= limit } else { value >= limit })
+        .map(|(mark, _)| mark)
+}
+
+/// Whether an earned mark is at least the target mark (A is the best)
+pub(crate) fn reaches_skill_mark(mark: &str, target: &str) -> bool {
+    mark <= target
+}
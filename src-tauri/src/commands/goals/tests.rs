@@ -0,0 +1,566 @@
+use super::crud::fetch_goal;
+use super::evaluation::{
+    evaluate_goal_by_id, evaluate_goals, first_candidate_meeting, improvement_target, meets_target,
+    nth_competition_result, personal_best_results, season_best_results, PbCandidate,
+};
+use super::forecast::{assess_projection, build_count_forecast, build_forecast, fit_log_trend, project_target_day};
+use super::skill_limits::{reaches_skill_mark, skill_mark_for_result, skill_mark_limit};
+use crate::types::Goal;
+use chrono::NaiveDate;
+
+// ==================== Test helpers ====================
+
+async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(":memory:")
+        .await
+        .expect("Failed to create in-memory database");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE disciplines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            full_name TEXT NOT NULL,
+            category TEXT NOT NULL,
+            unit TEXT NOT NULL,
+            lower_is_better INTEGER NOT NULL DEFAULT 1,
+            icon_name TEXT
+        );
+
+        CREATE TABLE athletes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            birth_year INTEGER NOT NULL,
+            birth_date TEXT
+        );
+
+        CREATE TABLE competitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            date TEXT NOT NULL,
+            end_date TEXT
+        );
+
+        CREATE TABLE results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            athlete_id INTEGER NOT NULL,
+            discipline_id INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            value REAL NOT NULL,
+            type TEXT NOT NULL DEFAULT 'competition',
+            competition_name TEXT,
+            wind REAL,
+            status TEXT DEFAULT 'valid',
+            equipment_weight REAL,
+            hurdle_height INTEGER,
+            combined_event_id INTEGER,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE goals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            athlete_id INTEGER NOT NULL,
+            discipline_id INTEGER,
+            goal_type TEXT NOT NULL DEFAULT 'value',
+            target_value REAL NOT NULL,
+            skill_mark TEXT,
+            season INTEGER,
+            target_date TEXT,
+            status TEXT NOT NULL DEFAULT 'active',
+            achieved_at TEXT,
+            achieved_result_id INTEGER,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        INSERT INTO disciplines (id, name, full_name, category, unit, lower_is_better) VALUES
+            (1, '60 m', '60 metriä', 'sprints', 'time', 1),
+            (2, 'Pituus', 'Pituushyppy', 'jumps', 'distance', 0);
+
+        INSERT INTO athletes (id, birth_year) VALUES (1, 2013);
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create schema");
+
+    pool
+}
+
+async fn insert_goal(pool: &sqlx::Pool<sqlx::Sqlite>, discipline_id: i64, target: f64) -> i64 {
+    sqlx::query("INSERT INTO goals (athlete_id, discipline_id, target_value) VALUES (1, ?, ?)")
+        .bind(discipline_id)
+        .bind(target)
+        .execute(pool)
+        .await
+        .expect("Failed to insert goal")
+        .last_insert_rowid()
+}
+
+async fn insert_result(pool: &sqlx::Pool<sqlx::Sqlite>, discipline_id: i64, date: &str, value: f64, status: &str) -> i64 {
+    sqlx::query("INSERT INTO results (athlete_id, discipline_id, date, value, status) VALUES (1, ?, ?, ?, ?)")
+        .bind(discipline_id)
+        .bind(date)
+        .bind(value)
+        .bind(status)
+        .execute(pool)
+        .await
+        .expect("Failed to insert result")
+        .last_insert_rowid()
+}
+
+async fn insert_typed_goal(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    goal_type: &str,
+    discipline_id: Option<i64>,
+    target: f64,
+    season: i32,
+) -> i64 {
+    sqlx::query("INSERT INTO goals (athlete_id, discipline_id, goal_type, target_value, season) VALUES (1, ?, ?, ?, ?)")
+        .bind(discipline_id)
+        .bind(goal_type)
+        .bind(target)
+        .bind(season)
+        .execute(pool)
+        .await
+        .expect("Failed to insert goal")
+        .last_insert_rowid()
+}
+
+async fn insert_competition_result(pool: &sqlx::Pool<sqlx::Sqlite>, discipline_id: i64, date: &str, competition: &str) -> i64 {
+    sqlx::query("INSERT INTO results (athlete_id, discipline_id, date, value, competition_name) VALUES (1, ?, ?, 9.0, ?)")
+        .bind(discipline_id)
+        .bind(date)
+        .bind(competition)
+        .execute(pool)
+        .await
+        .expect("Failed to insert result")
+        .last_insert_rowid()
+}
+
+// ==================== meets_target ====================
+
+#[test]
+fn test_meets_target_direction() {
+    // Times: lower is better
+    assert!(meets_target(8.9, 9.0, true));
+    assert!(meets_target(9.0, 9.0, true));
+    assert!(!meets_target(9.1, 9.0, true));
+
+    // Distances: higher is better
+    assert!(meets_target(4.5, 4.5, false));
+    assert!(!meets_target(4.4, 4.5, false));
+}
+
+// ==================== evaluate_goals ====================
+
+#[tokio::test]
+async fn test_goal_achieved_by_result() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_goal(&pool, 2, 4.50).await;
+
+    insert_result(&pool, 2, "2025-05-01", 4.20, "valid").await;
+    assert!(evaluate_goals(&pool, 1, 2).await.unwrap().is_empty());
+
+    let result_id = insert_result(&pool, 2, "2025-06-14", 4.56, "valid").await;
+    let changed = evaluate_goals(&pool, 1, 2).await.unwrap();
+    assert_eq!(changed.len(), 1);
+
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_at.as_deref(), Some("2025-06-14"));
+    assert_eq!(goal.achieved_result_id, Some(result_id));
+}
+
+#[tokio::test]
+async fn test_lower_is_better_goal() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_goal(&pool, 1, 9.00).await;
+
+    insert_result(&pool, 1, "2025-05-01", 9.12, "valid").await;
+    evaluate_goals(&pool, 1, 1).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+
+    insert_result(&pool, 1, "2025-05-20", 8.98, "valid").await;
+    evaluate_goals(&pool, 1, 1).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "achieved");
+}
+
+#[tokio::test]
+async fn test_invalid_result_does_not_achieve_goal() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_goal(&pool, 2, 4.50).await;
+
+    insert_result(&pool, 2, "2025-05-01", 4.80, "dq").await;
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+}
+
+#[tokio::test]
+async fn test_goal_reopened_when_result_deleted() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_goal(&pool, 2, 4.50).await;
+    let result_id = insert_result(&pool, 2, "2025-06-14", 4.56, "valid").await;
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+
+    sqlx::query("DELETE FROM results WHERE id = ?")
+        .bind(result_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "active");
+    assert_eq!(goal.achieved_at, None);
+    assert_eq!(goal.achieved_result_id, None);
+}
+
+#[tokio::test]
+async fn test_goal_relinked_when_result_edited() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_goal(&pool, 2, 4.50).await;
+    let first = insert_result(&pool, 2, "2025-06-01", 4.52, "valid").await;
+    let second = insert_result(&pool, 2, "2025-07-01", 4.60, "valid").await;
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().achieved_result_id, Some(first));
+
+    // The first result was a typo; the later one still meets the target
+    sqlx::query("UPDATE results SET value = 4.25 WHERE id = ?")
+        .bind(first)
+        .execute(&pool)
+        .await
+        .unwrap();
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_result_id, Some(second));
+    assert_eq!(goal.achieved_at.as_deref(), Some("2025-07-01"));
+}
+
+#[tokio::test]
+async fn test_manually_achieved_goal_is_kept() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_goal(&pool, 2, 4.50).await;
+    sqlx::query("UPDATE goals SET status = 'achieved', achieved_at = '2025-01-01' WHERE id = ?")
+        .bind(goal_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // No result meets the target, but the coach marked it achieved by hand
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_at.as_deref(), Some("2025-01-01"));
+}
+
+// ==================== Goal types ====================
+
+#[test]
+fn test_improvement_target() {
+    // 5 % faster than 10.00 s, 5 % longer than 4.00 m
+    assert!((improvement_target(10.0, 5.0, true) - 9.5).abs() < 1e-9);
+    assert!((improvement_target(4.0, 5.0, false) - 4.2).abs() < 1e-9);
+}
+
+fn pb(id: i64, date: &str, value: f64, key: &str, wind_assisted: bool) -> PbCandidate {
+    PbCandidate {
+        id,
+        date: date.to_string(),
+        value,
+        key: key.to_string(),
+        lower_is_better: true,
+        wind_assisted,
+    }
+}
+
+#[test]
+fn test_personal_best_results() {
+    let candidates = vec![
+        pb(1, "2024-06-01", 9.20, "1", false),
+        pb(2, "2025-05-01", 9.30, "1", false),
+        pb(3, "2025-05-10", 9.05, "1", true),
+        pb(4, "2025-06-01", 9.10, "1", false),
+        pb(5, "2025-06-02", 12.0, "3", false),
+    ];
+    let ids: Vec<i64> = personal_best_results(&candidates).into_iter().map(|(id, _)| id).collect();
+    // The first result counts, slower and wind-assisted ones don't
+    assert_eq!(ids, vec![1, 4, 5]);
+}
+
+#[test]
+fn test_season_best_results() {
+    let candidates = vec![
+        pb(1, "2024-06-01", 9.20, "1", false),
+        pb(2, "2025-05-01", 9.30, "1", false),
+        pb(3, "2025-05-10", 9.25, "1", false),
+        pb(4, "2025-06-01", 9.28, "1", false),
+    ];
+    let ids: Vec<i64> = season_best_results(&candidates).into_iter().map(|(id, _)| id).collect();
+    // Each season starts over, even when slower than last season
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_first_candidate_meeting() {
+    let candidates = vec![
+        pb(1, "2024-06-01", 8.90, "1||68", false),
+        pb(2, "2025-05-10", 8.95, "1||76", true),
+        pb(3, "2025-06-01", 9.05, "1||76", false),
+        pb(4, "2025-06-20", 8.98, "1||76", false),
+    ];
+    // Lower hurdles and wind-assisted results don't count
+    assert_eq!(first_candidate_meeting(&candidates, 9.0, None), Some((4, "2025-06-20".to_string())));
+    assert_eq!(first_candidate_meeting(&candidates, 9.1, Some(2025)), Some((3, "2025-06-01".to_string())));
+    assert_eq!(first_candidate_meeting(&candidates, 9.1, Some(2024)), None);
+    assert_eq!(first_candidate_meeting(&[], 9.1, None), None);
+}
+
+#[test]
+fn test_nth_competition_result() {
+    let results = vec![
+        (1, "2025-05-01".to_string(), "c1".to_string()),
+        (2, "2025-05-01".to_string(), "c1".to_string()),
+        (3, "2025-05-20".to_string(), "kisa|2025-05-20".to_string()),
+        (4, "2025-06-01".to_string(), "c2".to_string()),
+    ];
+    assert_eq!(nth_competition_result(&results, 2), Some((3, "2025-05-20".to_string())));
+    assert_eq!(nth_competition_result(&results, 3), Some((4, "2025-06-01".to_string())));
+    assert_eq!(nth_competition_result(&results, 4), None);
+    assert_eq!(nth_competition_result(&results, 0), None);
+}
+
+#[tokio::test]
+async fn test_competition_count_goal() {
+    let pool = setup_test_db().await;
+    sqlx::query("INSERT INTO competitions (name, date, end_date) VALUES ('Kalevan kisat', '2025-07-01', '2025-07-02')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let goal_id = insert_typed_goal(&pool, "competition_count", None, 2.0, 2025).await;
+
+    // Two disciplines on a two-day competition count once
+    insert_competition_result(&pool, 1, "2025-07-01", "Kalevan kisat").await;
+    insert_competition_result(&pool, 2, "2025-07-02", "Kalevan kisat").await;
+    // Previous season doesn't count
+    insert_competition_result(&pool, 1, "2024-08-01", "Syyskisat").await;
+    evaluate_goal_by_id(&pool, goal_id).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+
+    let second = insert_competition_result(&pool, 2, "2025-08-10", "Syyskisat").await;
+    let changed = evaluate_goals(&pool, 1, 2).await.unwrap();
+    assert_eq!(changed.len(), 1);
+
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_result_id, Some(second));
+}
+
+#[tokio::test]
+async fn test_pb_count_goal() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_typed_goal(&pool, "pb_count", None, 2.0, 2025).await;
+
+    insert_result(&pool, 2, "2024-06-01", 4.00, "valid").await;
+    insert_result(&pool, 2, "2025-05-01", 3.90, "valid").await;
+    insert_result(&pool, 2, "2025-05-20", 4.10, "valid").await;
+    evaluate_goal_by_id(&pool, goal_id).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+
+    // First 60 m result is a PB too
+    let second = insert_result(&pool, 1, "2025-06-01", 9.40, "valid").await;
+    evaluate_goals(&pool, 1, 1).await.unwrap();
+
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_result_id, Some(second));
+}
+
+#[tokio::test]
+async fn test_improvement_goal_uses_last_season() {
+    let pool = setup_test_db().await;
+    let goal_id = insert_typed_goal(&pool, "improvement_percent", Some(2), 5.0, 2025).await;
+
+    // Without a baseline the goal can't be achieved
+    insert_result(&pool, 2, "2025-05-01", 4.50, "valid").await;
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+
+    // SB 2024 = 4.40 -> target 4.62
+    insert_result(&pool, 2, "2024-07-01", 4.40, "valid").await;
+    insert_result(&pool, 2, "2025-06-01", 4.60, "valid").await;
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+
+    let result_id = insert_result(&pool, 2, "2025-07-01", 4.63, "valid").await;
+    evaluate_goals(&pool, 1, 2).await.unwrap();
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_result_id, Some(result_id));
+}
+
+// ==================== Skill marks ====================
+
+#[test]
+fn test_skill_mark_for_result() {
+    // Long jump (22), born 2013: T11 in 2024, T13 in 2026 which has no 2026 limits yet
+    assert_eq!(skill_mark_for_result(4.20, 22, 2013, "2024-06-01", false), Some("A"));
+    assert_eq!(skill_mark_for_result(3.95, 22, 2013, "2024-06-01", false), Some("B"));
+    assert_eq!(skill_mark_for_result(3.69, 22, 2013, "2024-06-01", false), None);
+    assert_eq!(skill_mark_for_result(4.20, 22, 2013, "2026-06-01", false), None);
+    // 60 m (2) has no T9 limit
+    assert_eq!(skill_mark_for_result(8.00, 2, 2015, "2024-06-01", true), None);
+    assert_eq!(skill_mark_for_result(9.40, 2, 2013, "2024-06-01", true), Some("B"));
+
+    assert_eq!(skill_mark_limit(22, 2013, "2024-06-01", "C"), Some(3.70));
+    assert!(reaches_skill_mark("A", "B"));
+    assert!(!reaches_skill_mark("C", "B"));
+}
+
+#[tokio::test]
+async fn test_skill_mark_goal_ignores_target_value() {
+    let pool = setup_test_db().await;
+    sqlx::query("INSERT INTO disciplines (id, name, full_name, category, unit, lower_is_better) VALUES (22, 'Pituus T', 'Pituushyppy', 'jumps', 'distance', 0)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    // A stale client-side limit must not decide the goal
+    let goal_id = sqlx::query("INSERT INTO goals (athlete_id, discipline_id, goal_type, target_value, skill_mark) VALUES (1, 22, 'skill_mark', 3.00, 'B')")
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+    insert_result(&pool, 22, "2024-05-01", 3.80, "valid").await;
+    evaluate_goals(&pool, 1, 22).await.unwrap();
+    assert_eq!(fetch_goal(&pool, goal_id).await.unwrap().status, "active");
+
+    let result_id = insert_result(&pool, 22, "2024-06-01", 4.00, "valid").await;
+    evaluate_goals(&pool, 1, 22).await.unwrap();
+    let goal = fetch_goal(&pool, goal_id).await.unwrap();
+    assert_eq!(goal.status, "achieved");
+    assert_eq!(goal.achieved_result_id, Some(result_id));
+}
+
+// ==================== Forecast ====================
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn goal(target_value: f64, target_date: Option<&str>) -> Goal {
+    Goal {
+        id: 1,
+        athlete_id: 1,
+        discipline_id: Some(2),
+        goal_type: "value".to_string(),
+        target_value,
+        skill_mark: None,
+        season: None,
+        target_date: target_date.map(|d| d.to_string()),
+        status: "active".to_string(),
+        achieved_at: None,
+        achieved_result_id: None,
+        created_at: "2025-01-01".to_string(),
+    }
+}
+
+#[test]
+fn test_fit_log_trend_recovers_growth() {
+    // value = 4.0 * e^(0.001 * day)
+    let points: Vec<(f64, f64)> = (0..5)
+        .map(|i| {
+            let day = i as f64 * 30.0;
+            (day, 4.0 * (0.001 * day).exp())
+        })
+        .collect();
+    let (a, b) = fit_log_trend(&points).unwrap();
+    assert!((a - 4.0f64.ln()).abs() < 1e-9);
+    assert!((b - 0.001).abs() < 1e-9);
+}
+
+#[test]
+fn test_fit_log_trend_needs_data() {
+    assert!(fit_log_trend(&[(0.0, 4.0), (30.0, 4.2)]).is_none());
+    // Three results on nearly the same day give no trend
+    assert!(fit_log_trend(&[(0.0, 4.0), (1.0, 4.1), (2.0, 4.2)]).is_none());
+}
+
+#[test]
+fn test_project_target_day_direction() {
+    // Improving distance
+    let day = project_target_day((4.0f64.ln(), 0.001), 4.0 * 0.1f64.exp(), false).unwrap();
+    assert!((day - 100.0).abs() < 1e-6);
+    // Times getting slower never reach a lower target
+    assert!(project_target_day((9.0f64.ln(), 0.0001), 8.5, true).is_none());
+}
+
+#[test]
+fn test_assess_projection() {
+    let today = date("2025-06-01");
+    let target = Some(date("2025-09-01"));
+    assert_eq!(assess_projection(Some(date("2025-08-01")), target, today), "on_track");
+    assert_eq!(assess_projection(Some(date("2025-09-20")), target, today), "at_risk");
+    assert_eq!(assess_projection(Some(date("2026-06-01")), target, today), "unrealistic");
+    assert_eq!(assess_projection(None, target, today), "unrealistic");
+    // Deadline already passed
+    assert_eq!(assess_projection(Some(date("2025-06-10")), Some(date("2025-05-01")), today), "unrealistic");
+    // No deadline
+    assert_eq!(assess_projection(Some(date("2025-12-01")), None, today), "on_track");
+}
+
+#[test]
+fn test_build_forecast_improving_athlete() {
+    let today = date("2025-06-01");
+    let results = vec![
+        (date("2025-03-01"), 4.00),
+        (date("2025-04-01"), 4.10),
+        (date("2025-05-01"), 4.20),
+        (date("2025-05-25"), 4.28),
+    ];
+    let forecast = build_forecast(&goal(4.50, Some("2025-09-30")), 4.50, false, &results, today);
+
+    assert_eq!(forecast.current_best, Some(4.28));
+    assert!((forecast.gap.unwrap() - 0.22).abs() < 1e-9);
+    assert_eq!(forecast.result_count, 4);
+    assert_eq!(forecast.assessment, "on_track");
+    assert!(forecast.projected_date.unwrap().as_str() <= "2025-09-30");
+}
+
+#[test]
+fn test_build_forecast_edge_cases() {
+    let today = date("2025-06-01");
+
+    let met = build_forecast(&goal(4.00, None), 4.00, false, &[(date("2025-05-01"), 4.10)], today);
+    assert_eq!(met.assessment, "achieved");
+    assert_eq!(met.gap, Some(0.0));
+
+    let sparse = build_forecast(&goal(4.50, None), 4.50, false, &[(date("2025-05-01"), 4.10)], today);
+    assert_eq!(sparse.assessment, "insufficient_data");
+    assert_eq!(sparse.projected_date, None);
+
+    let empty = build_forecast(&goal(9.0, None), 9.0, true, &[], today);
+    assert_eq!(empty.current_best, None);
+    assert_eq!(empty.gap, None);
+}
+
+#[test]
+fn test_build_count_forecast() {
+    let today = date("2025-07-01");
+    let mut count_goal = goal(6.0, None);
+    count_goal.goal_type = "competition_count".to_string();
+    count_goal.discipline_id = None;
+
+    // 3 competitions in the first half of the season: 6 by the end of the year
+    let dates = vec![date("2025-02-01"), date("2025-04-01"), date("2025-06-01")];
+    let forecast = build_count_forecast(&count_goal, 2025, &dates, today);
+    assert_eq!(forecast.current_best, Some(3.0));
+    assert_eq!(forecast.gap, Some(3.0));
+    assert_eq!(forecast.days_remaining, Some(183));
+    assert_eq!(forecast.assessment, "on_track");
+
+    let none = build_count_forecast(&count_goal, 2025, &[], today);
+    assert_eq!(none.assessment, "insufficient_data");
+}
@@ -1,6 +1,8 @@
+use crate::commands::settings::load_photo_settings;
+use crate::database::get_pool;
 use crate::google_drive;
 use crate::types::{AuthStatus, CloudBackup, CloudPhoto, LocalPhoto, SyncOptions, SyncResult};
-use tauri::async_runtime;
+use tauri::{async_runtime, AppHandle};
 
 /// Check the current Google Drive authentication status
 #[tauri::command]
@@ -88,12 +90,19 @@ pub async fn disconnect_drive() -> Result<bool, String> {
     Ok(true)
 }
 
+/// Whether photos are uploaded without location and identifying metadata
+async fn strip_uploaded_metadata(app: &AppHandle) -> Result<bool, String> {
+    let pool = get_pool(app).await?;
+    Ok(load_photo_settings(&pool).await?.strip_metadata)
+}
+
 /// Sync local data to Google Drive
 #[tauri::command]
-pub async fn sync_to_drive() -> Result<SyncResult, String> {
+pub async fn sync_to_drive(app: AppHandle) -> Result<SyncResult, String> {
     let access_token = google_drive::get_valid_token().await?;
+    let strip_metadata = strip_uploaded_metadata(&app).await?;
 
-    match google_drive::sync_database_to_drive(&access_token).await {
+    match google_drive::sync_database_to_drive(&access_token, strip_metadata).await {
         Ok(items_synced) => Ok(SyncResult {
             success: true,
             message: format!("Synkronoitu {} kohdetta", items_synced),
@@ -160,10 +169,11 @@ pub fn list_local_photos() -> Result<Vec<LocalPhoto>, String> {
 
 /// Sync to drive with options (selective sync)
 #[tauri::command]
-pub async fn sync_to_drive_with_options(options: SyncOptions) -> Result<SyncResult, String> {
+pub async fn sync_to_drive_with_options(app: AppHandle, options: SyncOptions) -> Result<SyncResult, String> {
     let access_token = google_drive::get_valid_token().await?;
+    let strip_metadata = strip_uploaded_metadata(&app).await?;
 
-    match google_drive::sync_with_options(&access_token, &options).await {
+    match google_drive::sync_with_options(&access_token, &options, strip_metadata).await {
         Ok(items_synced) => Ok(SyncResult {
             success: true,
             message: format!("Synkronoitu {} kohdetta", items_synced),
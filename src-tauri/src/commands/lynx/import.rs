@@ -0,0 +1,298 @@
+use crate::commands::goals::evaluation::evaluate_goals;
+use crate::commands::results::medals::sync_result_medal;
+use crate::commands::results::records::recalculate_records;
+use crate::database::get_pool;
+use crate::types::{CommitLynxImport, LynxImportPreview, LynxImportSummary, LynxPreviewRow};
+use sqlx::Row;
+use tauri::AppHandle;
+
+use super::parser::{decode_lynx_bytes, normalize_name, parse_evt, parse_lif, suggest_discipline, LifRow};
+
+/// Athlete fields needed for matching file rows
+pub(crate) struct MatchCandidate {
+    pub id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub club_name: Option<String>,
+}
+
+/// Saved manual mapping (names as written in the file)
+pub(crate) struct SavedMapping {
+    pub first_name: String,
+    pub last_name: String,
+    pub affiliation: String,
+    pub athlete_id: i64,
+}
+
+fn clubs_match(a: &str, b: &str) -> bool {
+    let a = normalize_name(a);
+    let b = normalize_name(b);
+    !a.is_empty() && !b.is_empty() && (a == b || a.contains(&b) || b.contains(&a))
+}
+
+/// Match a LIF row to an athlete. Saved manual mappings win, then name + club,
+/// then name alone when exactly one athlete has that name.
+pub(crate) fn match_athlete(
+    row: &LifRow,
+    athletes: &[MatchCandidate],
+    mappings: &[SavedMapping],
+) -> (Option<i64>, &'static str) {
+    let first = normalize_name(&row.first_name);
+    let last = normalize_name(&row.last_name);
+    let affiliation = normalize_name(row.affiliation.as_deref().unwrap_or(""));
+
+    if let Some(mapping) = mappings.iter().find(|m| {
+        normalize_name(&m.first_name) == first
+            && normalize_name(&m.last_name) == last
+            && normalize_name(&m.affiliation) == affiliation
+    }) {
+        return (Some(mapping.athlete_id), "mapping");
+    }
+
+    let same_name: Vec<&MatchCandidate> = athletes
+        .iter()
+        .filter(|a| normalize_name(&a.first_name) == first && normalize_name(&a.last_name) == last)
+        .collect();
+
+    if let Some(athlete) = same_name.iter().find(|a| {
+        a.club_name
+            .as_deref()
+            .is_some_and(|club| clubs_match(club, &affiliation))
+    }) {
+        return (Some(athlete.id), "name_club");
+    }
+
+    if same_name.len() == 1 {
+        return (Some(same_name[0].id), "name");
+    }
+
+    (None, "none")
+}
+
+async fn load_match_data(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+) -> Result<(Vec<MatchCandidate>, Vec<SavedMapping>), String> {
+    let athletes = sqlx::query("SELECT id, first_name, last_name, club_name FROM athletes")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| MatchCandidate {
+            id: row.get("id"),
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            club_name: row.get("club_name"),
+        })
+        .collect();
+
+    let mappings = sqlx::query("SELECT first_name, last_name, affiliation, athlete_id FROM lif_athlete_mappings")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| SavedMapping {
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            affiliation: row.get("affiliation"),
+            athlete_id: row.get("athlete_id"),
+        })
+        .collect();
+
+    Ok((athletes, mappings))
+}
+
+/// Parse a FinishLynx .lif file and match its rows to athletes without saving anything.
+/// An optional .evt file fills in the event name when the LIF header lacks one.
+#[tauri::command]
+pub async fn preview_lynx_import(
+    app: AppHandle,
+    file_path: String,
+    evt_path: Option<String>,
+) -> Result<LynxImportPreview, String> {
+    let pool = get_pool(&app).await?;
+
+    let bytes = std::fs::read(&file_path).map_err(|e| format!("Failed to read LIF file: {}", e))?;
+    let lif = parse_lif(&decode_lynx_bytes(&bytes))?;
+    let mut header = lif.header;
+
+    if header.event_name.is_empty() {
+        if let Some(evt_path) = evt_path {
+            let evt_bytes = std::fs::read(&evt_path).map_err(|e| format!("Failed to read EVT file: {}", e))?;
+            if let Some(event) = parse_evt(&decode_lynx_bytes(&evt_bytes)).into_iter().find(|e| {
+                e.event_number == header.event_number && e.round == header.round && e.heat == header.heat
+            }) {
+                header.event_name = event.event_name;
+            }
+        }
+    }
+
+    let disciplines: Vec<(i64, String)> = sqlx::query("SELECT id, name FROM disciplines")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| (row.get("id"), row.get("name")))
+        .collect();
+
+    let (athletes, mappings) = load_match_data(&pool).await?;
+
+    let rows = lif
+        .rows
+        .iter()
+        .map(|row| {
+            let (athlete_id, match_type) = match_athlete(row, &athletes, &mappings);
+            LynxPreviewRow {
+                place: row.place,
+                status: row.status.clone(),
+                bib: row.bib.clone(),
+                lane: row.lane,
+                first_name: row.first_name.clone(),
+                last_name: row.last_name.clone(),
+                affiliation: row.affiliation.clone(),
+                value: row.time,
+                athlete_id,
+                match_type: match_type.to_string(),
+            }
+        })
+        .collect();
+
+    Ok(LynxImportPreview {
+        suggested_discipline_id: suggest_discipline(&header.event_name, &disciplines),
+        event_number: header.event_number,
+        round: header.round,
+        heat: header.heat,
+        event_name: header.event_name,
+        wind: header.wind,
+        rows,
+    })
+}
+
+/// Create results from confirmed preview rows. Every row is validated before
+/// anything is written, and the results are inserted in one transaction so a
+/// failing row leaves the database untouched.
+#[tauri::command]
+pub async fn commit_lynx_import(app: AppHandle, import: CommitLynxImport) -> Result<LynxImportSummary, String> {
+    let pool = get_pool(&app).await?;
+    import_lynx_rows(&pool, &import).await
+}
+
+pub(crate) async fn import_lynx_rows(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    import: &CommitLynxImport,
+) -> Result<LynxImportSummary, String> {
+    let mut values = Vec::with_capacity(import.rows.len());
+    for row in &import.rows {
+        let status = row.status.as_str();
+        if !["valid", "dns", "dnf", "dq"].contains(&status) {
+            return Err(format!("Invalid status: {}", status));
+        }
+
+        values.push(if status == "valid" {
+            row.value.ok_or_else(|| format!("Missing time for {} {}", row.first_name, row.last_name))?
+        } else {
+            0.0
+        });
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut created_ids = Vec::new();
+    let mut skipped_duplicates = 0;
+
+    for (row, &value) in import.rows.iter().zip(&values) {
+        let status = row.status.as_str();
+
+        if row.remember_mapping.unwrap_or(false) {
+            sqlx::query(
+                r#"INSERT INTO lif_athlete_mappings (first_name, last_name, affiliation, athlete_id)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (first_name, last_name, affiliation) DO UPDATE SET athlete_id = excluded.athlete_id"#
+            )
+            .bind(row.first_name.trim())
+            .bind(row.last_name.trim())
+            .bind(row.affiliation.as_deref().unwrap_or("").trim())
+            .bind(row.athlete_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+
+        // Re-importing the same file must not create duplicates
+        let exists: bool = sqlx::query_scalar(
+            r#"SELECT COUNT(*) > 0 FROM results
+            WHERE athlete_id = ? AND discipline_id = ? AND date = ? AND value = ?
+              AND COALESCE(status, 'valid') = ? AND COALESCE(heat, '') = COALESCE(?, '')"#
+        )
+        .bind(row.athlete_id)
+        .bind(import.discipline_id)
+        .bind(&import.date)
+        .bind(value)
+        .bind(status)
+        .bind(&import.heat)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if exists {
+            skipped_duplicates += 1;
+            continue;
+        }
+
+        // PB/SB flags are recalculated once everything is in
+        let id = sqlx::query(
+            r#"INSERT INTO results (athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, wind, status, heat)
+            VALUES (?, ?, ?, ?, 'competition', ?, ?, ?, ?, ?, ?, ?, ?)"#
+        )
+        .bind(row.athlete_id)
+        .bind(import.discipline_id)
+        .bind(&import.date)
+        .bind(value)
+        .bind(&import.competition_name)
+        .bind(&import.competition_level)
+        .bind(&import.custom_level_name)
+        .bind(&import.location)
+        .bind(if status == "valid" { row.place } else { None })
+        .bind(import.wind)
+        .bind(status)
+        .bind(&import.heat)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+
+        created_ids.push((id, row.athlete_id));
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let mut athlete_ids: Vec<i64> = created_ids.iter().map(|&(_, athlete_id)| athlete_id).collect();
+    athlete_ids.sort_unstable();
+    athlete_ids.dedup();
+
+    for &athlete_id in &athlete_ids {
+        recalculate_records(pool, athlete_id, import.discipline_id, None, None).await?;
+    }
+
+    // Placement 1-3 in a competition creates a medal
+    for &(id, _) in &created_ids {
+        sync_result_medal(pool, id).await?;
+    }
+
+    for &athlete_id in &athlete_ids {
+        evaluate_goals(pool, athlete_id, import.discipline_id).await?;
+    }
+
+    let mut created = Vec::with_capacity(created_ids.len());
+    for &(id, _) in &created_ids {
+        let row = sqlx::query("SELECT * FROM results WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        created.push(result_from_row!(row));
+    }
+
+    Ok(LynxImportSummary {
+        created,
+        skipped_duplicates,
+    })
+}
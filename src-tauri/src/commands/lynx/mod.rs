@@ -0,0 +1,8 @@
+// FinishLynx (.lif / .evt) results file import
+pub mod import;
+pub(crate) mod parser;
+
+pub use import::*;
+
+#[cfg(test)]
+mod tests;
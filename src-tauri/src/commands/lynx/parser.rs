@@ -0,0 +1,240 @@
+//! Parsers for FinishLynx result (.lif) and event schedule (.evt) files.
+//!
+//! LIF layout:
+//!   header: EventNumber,Round,Heat,EventName,Wind,WindUnit,...
+//!   rows:   Place,ID,Lane,LastName,FirstName,Affiliation,Time,License,DeltaTime,ReactionTime,...
+//!
+//! EVT layout:
+//!   header: EventNumber,Round,Heat,EventName
+//!   rows:   ,ID,Lane,LastName,FirstName,Affiliation
+
+/// Event header shared by LIF and EVT files
+#[derive(Debug, Clone, PartialEq)]
+pub struct LynxEventHeader {
+    pub event_number: String,
+    pub round: String,
+    pub heat: String,
+    pub event_name: String,
+    pub wind: Option<f64>,
+}
+
+/// Single competitor row from a LIF file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifRow {
+    pub place: Option<i32>,
+    /// "valid", "dns", "dnf" or "dq"
+    pub status: String,
+    pub bib: Option<String>,
+    pub lane: Option<i32>,
+    pub last_name: String,
+    pub first_name: String,
+    pub affiliation: Option<String>,
+    /// Time in seconds, rounded up to 1/100 s
+    pub time: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifFile {
+    pub header: LynxEventHeader,
+    pub rows: Vec<LifRow>,
+}
+
+/// Windows-1252 characters for bytes 0x80-0x9F. The five bytes the code page
+/// leaves undefined map to the matching C1 control as in the WHATWG decoder.
+const CP1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Decode file bytes. FinishLynx often writes Windows-1252, which is used
+/// when the file is not valid UTF-8.
+pub fn decode_lynx_bytes(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes
+            .iter()
+            .map(|&b| match b {
+                0x80..=0x9F => CP1252_HIGH[(b - 0x80) as usize],
+                _ => b as char,
+            })
+            .collect(),
+    }
+}
+
+/// Split a comma separated line, honoring double-quoted fields
+pub fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                fields.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    fields.push(current.trim().to_string());
+    fields
+}
+
+fn field(fields: &[String], index: usize) -> Option<&str> {
+    fields.get(index).map(|f| f.as_str()).filter(|f| !f.is_empty())
+}
+
+/// Map a LIF place/time token to a result status
+fn parse_status(token: &str) -> Option<&'static str> {
+    match token.trim().to_uppercase().as_str() {
+        "DNS" => Some("dns"),
+        "DNF" => Some("dnf"),
+        "DQ" | "DSQ" => Some("dq"),
+        _ => None,
+    }
+}
+
+/// Parse "12.34", "1:02.34" or "1:02:03.45" into seconds, rounded up to 1/100 s
+pub fn parse_lynx_time(token: &str) -> Option<f64> {
+    let token = token.trim();
+    if token.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    for part in token.split(':') {
+        let value: f64 = part.trim().parse().ok()?;
+        seconds = seconds * 60.0 + value;
+    }
+
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return None;
+    }
+
+    // Fully automatic times are rounded up to the next hundredth
+    Some(((seconds * 100.0) - 1e-6).ceil() / 100.0)
+}
+
+/// Parse wind like "+1.2", "-0.4" or "1,2". "NWI" (no wind information) gives None.
+pub fn parse_wind(token: &str) -> Option<f64> {
+    token.trim().replace(',', ".").parse::<f64>().ok()
+}
+
+fn parse_header(fields: &[String]) -> LynxEventHeader {
+    LynxEventHeader {
+        event_number: field(fields, 0).unwrap_or_default().to_string(),
+        round: field(fields, 1).unwrap_or_default().to_string(),
+        heat: field(fields, 2).unwrap_or_default().to_string(),
+        event_name: field(fields, 3).unwrap_or_default().to_string(),
+        wind: field(fields, 4).and_then(parse_wind),
+    }
+}
+
+/// Parse the contents of a .lif file
+pub fn parse_lif(content: &str) -> Result<LifFile, String> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+
+    let header_line = lines.next().ok_or("LIF file is empty")?;
+    let header = parse_header(&split_fields(header_line));
+
+    let mut rows = Vec::new();
+    for line in lines {
+        let fields = split_fields(line);
+
+        let last_name = field(&fields, 3).unwrap_or_default().to_string();
+        let first_name = field(&fields, 4).unwrap_or_default().to_string();
+        if last_name.is_empty() && first_name.is_empty() {
+            continue;
+        }
+
+        let place_token = field(&fields, 0).unwrap_or_default();
+        let time_token = field(&fields, 6).unwrap_or_default();
+        let status = parse_status(place_token)
+            .or_else(|| parse_status(time_token))
+            .unwrap_or("valid");
+
+        let time = if status == "valid" { parse_lynx_time(time_token) } else { None };
+        // A row without a time is treated as DNF (e.g. "" in the time column)
+        let status = if status == "valid" && time.is_none() { "dnf" } else { status };
+
+        rows.push(LifRow {
+            place: if status == "valid" { place_token.parse().ok() } else { None },
+            status: status.to_string(),
+            bib: field(&fields, 1).map(|s| s.to_string()),
+            lane: field(&fields, 2).and_then(|s| s.parse().ok()),
+            last_name,
+            first_name,
+            affiliation: field(&fields, 5).map(|s| s.to_string()),
+            time,
+        });
+    }
+
+    Ok(LifFile { header, rows })
+}
+
+/// Parse the contents of a .evt file into its event headers
+pub fn parse_evt(content: &str) -> Vec<LynxEventHeader> {
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(split_fields)
+        // Entrant lines start with an empty event number
+        .filter(|fields| field(fields, 0).is_some())
+        .map(|fields| parse_header(&fields))
+        .collect()
+}
+
+/// Lowercase and collapse whitespace for name comparisons
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Find the discipline whose name best matches a Lynx event name ("T11 60m aj" -> "60m aj").
+/// Returns the id of the longest discipline name found at a word boundary.
+pub fn suggest_discipline(event_name: &str, disciplines: &[(i64, String)]) -> Option<i64> {
+    let event = normalize_name(event_name);
+
+    disciplines
+        .iter()
+        .filter_map(|(id, name)| {
+            let name = normalize_name(name);
+            let compact = name.replace(' ', "");
+            if contains_at_boundary(&event, &name) || contains_at_boundary(&event, &compact) {
+                Some((*id, compact.len()))
+            } else {
+                None
+            }
+        })
+        .max_by_key(|(_, len)| *len)
+        .map(|(id, _)| id)
+}
+
+fn contains_at_boundary(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return false;
+    }
+    haystack.match_indices(needle).any(|(start, _)| {
+        let end = start + needle.len();
+        let before_ok = haystack[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        let after_ok = haystack[end..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric());
+        before_ok && after_ok
+    })
+}
@@ -0,0 +1,278 @@
+use super::import::{import_lynx_rows, match_athlete, MatchCandidate, SavedMapping};
+use crate::database::run_migrations;
+use crate::types::{CommitLynxImport, LynxImportRow};
+use super::parser::{
+    decode_lynx_bytes, parse_evt, parse_lif, parse_lynx_time, parse_wind, split_fields,
+    suggest_discipline, LifRow,
+};
+
+const SAMPLE_LIF: &str = "\
+3,1,2,T13 60m,+1.4,m/s,UNOFFICIAL,12:31:05.123
+1,12,4,Virtanen,Aino,Tampereen Pyrintö,8.531,,,0.152
+2,15,5,Korhonen,Emma,Kalevan Kisa,8.74,,0.21,0.160
+,21,3,Nieminen,Lotta,\"Lahden Ahkera, LA\",DNF
+DQ,22,6,Mäkinen,Sara,Tampereen Pyrintö,8.90
+DNS,23,2,Laine,Iida,Kalevan Kisa,
+";
+
+fn candidate(id: i64, first: &str, last: &str, club: Option<&str>) -> MatchCandidate {
+    MatchCandidate {
+        id,
+        first_name: first.to_string(),
+        last_name: last.to_string(),
+        club_name: club.map(|c| c.to_string()),
+    }
+}
+
+fn lif_row(first: &str, last: &str, affiliation: Option<&str>) -> LifRow {
+    LifRow {
+        place: Some(1),
+        status: "valid".to_string(),
+        bib: None,
+        lane: None,
+        last_name: last.to_string(),
+        first_name: first.to_string(),
+        affiliation: affiliation.map(|a| a.to_string()),
+        time: Some(10.0),
+    }
+}
+
+// ==================== Parser ====================
+
+#[test]
+fn test_parse_lif_header() {
+    let lif = parse_lif(SAMPLE_LIF).unwrap();
+    assert_eq!(lif.header.event_number, "3");
+    assert_eq!(lif.header.round, "1");
+    assert_eq!(lif.header.heat, "2");
+    assert_eq!(lif.header.event_name, "T13 60m");
+    assert_eq!(lif.header.wind, Some(1.4));
+}
+
+#[test]
+fn test_parse_lif_rows_and_statuses() {
+    let lif = parse_lif(SAMPLE_LIF).unwrap();
+    assert_eq!(lif.rows.len(), 5);
+
+    let first = &lif.rows[0];
+    assert_eq!(first.place, Some(1));
+    assert_eq!(first.status, "valid");
+    assert_eq!(first.lane, Some(4));
+    assert_eq!(first.first_name, "Aino");
+    assert_eq!(first.affiliation.as_deref(), Some("Tampereen Pyrintö"));
+    // 8.531 is rounded up to 8.54
+    assert_eq!(first.time, Some(8.54));
+
+    assert_eq!(lif.rows[2].status, "dnf");
+    assert_eq!(lif.rows[2].affiliation.as_deref(), Some("Lahden Ahkera, LA"));
+    assert_eq!(lif.rows[3].status, "dq");
+    assert_eq!(lif.rows[3].place, None);
+    assert_eq!(lif.rows[3].time, None);
+    assert_eq!(lif.rows[4].status, "dns");
+}
+
+#[test]
+fn test_parse_lif_empty_file() {
+    assert!(parse_lif("").is_err());
+    assert!(parse_lif("\n\n").is_err());
+}
+
+#[test]
+fn test_parse_lynx_time_formats() {
+    assert_eq!(parse_lynx_time("12.34"), Some(12.34));
+    assert_eq!(parse_lynx_time("12.341"), Some(12.35));
+    assert_eq!(parse_lynx_time("1:02.50"), Some(62.5));
+    assert_eq!(parse_lynx_time("1:02:03.45"), Some(3723.45));
+    assert_eq!(parse_lynx_time(""), None);
+    assert_eq!(parse_lynx_time("DNF"), None);
+}
+
+#[test]
+fn test_parse_wind() {
+    assert_eq!(parse_wind("+1.2"), Some(1.2));
+    assert_eq!(parse_wind("-0.4"), Some(-0.4));
+    assert_eq!(parse_wind("2,1"), Some(2.1));
+    assert_eq!(parse_wind("NWI"), None);
+}
+
+#[test]
+fn test_split_fields_quotes() {
+    assert_eq!(split_fields("a,\"b, c\",d"), vec!["a", "b, c", "d"]);
+    assert_eq!(split_fields("\"say \"\"hi\"\"\","), vec!["say \"hi\"", ""]);
+}
+
+#[test]
+fn test_decode_windows_1252() {
+    // "Mäkinen" in Windows-1252
+    let bytes = [0x4D, 0xE4, 0x6B, 0x69, 0x6E, 0x65, 0x6E];
+    assert_eq!(decode_lynx_bytes(&bytes), "Mäkinen");
+    assert_eq!(decode_lynx_bytes("Mäkinen".as_bytes()), "Mäkinen");
+
+    // Š, ž and quotes live in the 0x80-0x9F range Latin-1 lacks
+    let bytes = [0x8A, 0x74, 0x65, 0x72, 0x6E, 0x20, 0x9E, 0x20, 0x93, 0x41, 0x94, 0x20, 0x80];
+    assert_eq!(decode_lynx_bytes(&bytes), "Štern ž \u{201C}A\u{201D} €");
+}
+
+#[test]
+fn test_parse_evt_skips_entrant_lines() {
+    let evt = "1,1,1,P11 60m\n,5,3,Virtanen,Aino,TaPy\n2,1,1,T13 Pituus\n";
+    let events = parse_evt(evt);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_name, "P11 60m");
+    assert_eq!(events[1].event_number, "2");
+}
+
+#[test]
+fn test_suggest_discipline() {
+    let disciplines = vec![
+        (1, "60 m".to_string()),
+        (2, "600 m".to_string()),
+        (3, "60 m aj".to_string()),
+        (4, "Pituus".to_string()),
+    ];
+    assert_eq!(suggest_discipline("T13 60m", &disciplines), Some(1));
+    assert_eq!(suggest_discipline("P15 60 m aj", &disciplines), Some(3));
+    assert_eq!(suggest_discipline("600m T11", &disciplines), Some(2));
+    assert_eq!(suggest_discipline("T13 pituus", &disciplines), Some(4));
+    assert_eq!(suggest_discipline("Kuula", &disciplines), None);
+}
+
+// ==================== Matching ====================
+
+#[test]
+fn test_match_by_name_and_club() {
+    let athletes = vec![
+        candidate(1, "Aino", "Virtanen", Some("Kalevan Kisa")),
+        candidate(2, "Aino", "Virtanen", Some("Tampereen Pyrintö")),
+    ];
+    let row = lif_row("Aino", "Virtanen", Some("Tampereen Pyrintö"));
+    assert_eq!(match_athlete(&row, &athletes, &[]), (Some(2), "name_club"));
+}
+
+#[test]
+fn test_match_by_unique_name() {
+    let athletes = vec![candidate(1, "Emma", "Korhonen", None)];
+    let row = lif_row("emma", "KORHONEN", Some("Kalevan Kisa"));
+    assert_eq!(match_athlete(&row, &athletes, &[]), (Some(1), "name"));
+}
+
+#[test]
+fn test_ambiguous_name_is_not_matched() {
+    let athletes = vec![
+        candidate(1, "Aino", "Virtanen", None),
+        candidate(2, "Aino", "Virtanen", None),
+    ];
+    let row = lif_row("Aino", "Virtanen", Some("Kalevan Kisa"));
+    assert_eq!(match_athlete(&row, &athletes, &[]), (None, "none"));
+}
+
+#[test]
+fn test_saved_mapping_is_used() {
+    let athletes = vec![candidate(7, "Aino-Maria", "Virtanen", None)];
+    let mappings = vec![SavedMapping {
+        first_name: "Aino".to_string(),
+        last_name: "Virtanen".to_string(),
+        affiliation: "TaPy".to_string(),
+        athlete_id: 7,
+    }];
+    let row = lif_row("Aino", "Virtanen", Some("TaPy"));
+    assert_eq!(match_athlete(&row, &athletes, &mappings), (Some(7), "mapping"));
+
+    let other_club = lif_row("Aino", "Virtanen", Some("KaKi"));
+    assert_eq!(match_athlete(&other_club, &athletes, &mappings), (None, "none"));
+}
+
+async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(":memory:")
+        .await
+        .expect("Failed to create in-memory database");
+
+    run_migrations(&pool).await.expect("Failed to run migrations");
+
+    for first_name in ["Aino", "Emma"] {
+        sqlx::query("INSERT INTO athletes (first_name, last_name, birth_year) VALUES (?, 'Virtanen', 2012)")
+            .bind(first_name)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    pool
+}
+
+fn import_row(athlete_id: i64, status: &str, value: Option<f64>, remember_mapping: bool) -> LynxImportRow {
+    LynxImportRow {
+        athlete_id,
+        first_name: "Aino".to_string(),
+        last_name: "Virtanen".to_string(),
+        affiliation: Some("Tampereen Pyrintö".to_string()),
+        place: Some(athlete_id as i32),
+        status: status.to_string(),
+        value,
+        remember_mapping: Some(remember_mapping),
+    }
+}
+
+/// 60 m heat 2
+fn commit(rows: Vec<LynxImportRow>) -> CommitLynxImport {
+    CommitLynxImport {
+        discipline_id: 2,
+        date: "2024-02-10".to_string(),
+        competition_name: Some("Hallikisat".to_string()),
+        competition_level: Some("hallikisat".to_string()),
+        custom_level_name: None,
+        location: None,
+        heat: Some("2".to_string()),
+        wind: None,
+        rows,
+    }
+}
+
+async fn count(pool: &sqlx::Pool<sqlx::Sqlite>, query: &str) -> i64 {
+    sqlx::query_scalar(query).fetch_one(pool).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_invalid_row_imports_nothing() {
+    let pool = setup_test_db().await;
+
+    let import = commit(vec![
+        import_row(1, "valid", Some(8.53), true),
+        import_row(2, "valid", None, false),
+    ]);
+    assert!(import_lynx_rows(&pool, &import).await.is_err());
+
+    let import = commit(vec![import_row(1, "valid", Some(8.53), true), import_row(2, "late", None, false)]);
+    assert!(import_lynx_rows(&pool, &import).await.is_err());
+
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM results").await, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM lif_athlete_mappings").await, 0);
+}
+
+#[tokio::test]
+async fn test_import_sets_records_and_skips_duplicates() {
+    let pool = setup_test_db().await;
+
+    let import = commit(vec![
+        import_row(1, "valid", Some(8.53), true),
+        import_row(2, "dnf", None, false),
+    ]);
+    let summary = import_lynx_rows(&pool, &import).await.unwrap();
+
+    assert_eq!(summary.created.len(), 2);
+    assert_eq!(summary.skipped_duplicates, 0);
+    assert!(summary.created[0].is_personal_best);
+    assert!(summary.created[0].is_season_best);
+    assert_eq!(summary.created[0].heat.as_deref(), Some("2"));
+    assert!(!summary.created[1].is_personal_best);
+    assert_eq!(summary.created[1].placement, None);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM lif_athlete_mappings").await, 1);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM medals WHERE athlete_id = 1 AND derived = 1").await, 1);
+
+    // Importing the same file again creates nothing
+    let summary = import_lynx_rows(&pool, &import).await.unwrap();
+    assert!(summary.created.is_empty());
+    assert_eq!(summary.skipped_duplicates, 2);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM results").await, 2);
+}
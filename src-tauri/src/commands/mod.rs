@@ -1,14 +1,23 @@
 pub mod athletes;
+pub mod clubs;
 pub mod competitions;
+pub mod families;
 pub mod goals;
 pub mod google_drive;
+pub mod lynx;
 pub mod photos;
+pub mod reports;
 pub mod results;
+pub mod settings;
 pub mod sync;
 
 pub use athletes::*;
+pub use clubs::*;
 pub use competitions::*;
+pub use families::*;
 pub use goals::*;
 pub use google_drive::*;
 pub use photos::*;
+pub use reports::*;
+pub use settings::*;
 pub use sync::*;
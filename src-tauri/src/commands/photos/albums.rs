@@ -0,0 +1,286 @@
+use super::gallery::PHOTO_COLUMNS;
+use crate::database::get_pool;
+use crate::types::{Album, CreateAlbum, Photo, UpdateAlbum};
+use sqlx::Row;
+use std::collections::HashSet;
+use tauri::AppHandle;
+
+/// Album columns with the photo count and the cover to show: the chosen cover
+/// or else the first photo
+const ALBUM_SELECT: &str = r#"SELECT al.id, al.name, al.description, al.cover_photo_id, al.created_at, al.updated_at,
+       (SELECT COUNT(*) FROM album_photos ap WHERE ap.album_id = al.id) as photo_count,
+       (SELECT COALESCE(p.thumbnail_path, p.file_path) FROM photos p
+        WHERE p.id = COALESCE(al.cover_photo_id,
+            (SELECT ap.photo_id FROM album_photos ap WHERE ap.album_id = al.id ORDER BY ap.position LIMIT 1))) as cover_thumbnail_path
+FROM albums al"#;
+
+pub(crate) fn album_from_row(row: &sqlx::sqlite::SqliteRow) -> Album {
+    Album {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        cover_photo_id: row.get("cover_photo_id"),
+        cover_thumbnail_path: row.get("cover_thumbnail_path"),
+        photo_count: row.get("photo_count"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+async fn fetch_album(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Album, String> {
+    let row = sqlx::query(&format!("{} WHERE al.id = ?", ALBUM_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Album {} not found", id))?;
+
+    Ok(album_from_row(&row))
+}
+
+async fn album_photo_ids(pool: &sqlx::Pool<sqlx::Sqlite>, album_id: i64) -> Result<Vec<i64>, String> {
+    sqlx::query_scalar("SELECT photo_id FROM album_photos WHERE album_id = ? ORDER BY position")
+        .bind(album_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A new order must list every photo of the album exactly once
+pub(crate) fn validate_album_order(current: &[i64], requested: &[i64]) -> Result<(), String> {
+    let current_set: HashSet<i64> = current.iter().copied().collect();
+    let requested_set: HashSet<i64> = requested.iter().copied().collect();
+
+    if requested_set.len() != requested.len() {
+        return Err("Photo order lists a photo more than once".to_string());
+    }
+    if current_set != requested_set {
+        return Err("Photo order must contain exactly the photos of the album".to_string());
+    }
+
+    Ok(())
+}
+
+fn validate_album_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Album name is required".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_albums(app: AppHandle) -> Result<Vec<Album>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!("{} ORDER BY al.updated_at DESC, al.id DESC", ALBUM_SELECT))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(album_from_row).collect())
+}
+
+#[tauri::command]
+pub async fn get_album(app: AppHandle, id: i64) -> Result<Album, String> {
+    let pool = get_pool(&app).await?;
+
+    fetch_album(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn create_album(app: AppHandle, album: CreateAlbum) -> Result<Album, String> {
+    let pool = get_pool(&app).await?;
+
+    validate_album_name(&album.name)?;
+
+    let id = sqlx::query("INSERT INTO albums (name, description) VALUES (?, ?)")
+        .bind(album.name.trim())
+        .bind(&album.description)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid();
+
+    fetch_album(&pool, id).await
+}
+
+/// Update an album. The cover must be one of the album's photos; `None`
+/// shows the first photo.
+#[tauri::command]
+pub async fn update_album(app: AppHandle, id: i64, album: UpdateAlbum) -> Result<Album, String> {
+    let pool = get_pool(&app).await?;
+
+    if let Some(name) = &album.name {
+        validate_album_name(name)?;
+    }
+
+    if let Some(cover_photo_id) = album.cover_photo_id {
+        if !album_photo_ids(&pool, id).await?.contains(&cover_photo_id) {
+            return Err("Cover photo must be in the album".to_string());
+        }
+    }
+
+    sqlx::query(
+        r#"UPDATE albums SET name = COALESCE(?, name), description = ?, cover_photo_id = ?, updated_at = datetime('now')
+           WHERE id = ?"#
+    )
+    .bind(album.name.as_deref().map(str::trim))
+    .bind(&album.description)
+    .bind(album.cover_photo_id)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    fetch_album(&pool, id).await
+}
+
+/// Delete an album. Its photos are kept.
+#[tauri::command]
+pub async fn delete_album(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM albums WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Get the photos of an album in album order
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_album_photos(app: AppHandle, album_id: i64) -> Result<Vec<Photo>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!(
+        r#"SELECT {} FROM photos
+           JOIN (SELECT photo_id, position FROM album_photos WHERE album_id = ?) ap ON ap.photo_id = photos.id
+           ORDER BY ap.position"#,
+        PHOTO_COLUMNS
+    ))
+    .bind(album_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| photo_from_row!(row)).collect())
+}
+
+/// Add photos to the end of an album in the given order. Photos already in
+/// the album keep their place.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn add_photos_to_album(app: AppHandle, album_id: i64, photo_ids: Vec<i64>) -> Result<Album, String> {
+    let pool = get_pool(&app).await?;
+
+    fetch_album(&pool, album_id).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for photo_id in photo_ids {
+        sqlx::query(
+            r#"INSERT OR IGNORE INTO album_photos (album_id, photo_id, position)
+               SELECT ?, ?, COALESCE((SELECT MAX(position) + 1 FROM album_photos WHERE album_id = ?), 0)"#
+        )
+        .bind(album_id)
+        .bind(photo_id)
+        .bind(album_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query("UPDATE albums SET updated_at = datetime('now') WHERE id = ?")
+        .bind(album_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    fetch_album(&pool, album_id).await
+}
+
+/// Remove photos from an album. The photos themselves are kept; a removed
+/// cover falls back to the first photo.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn remove_photos_from_album(app: AppHandle, album_id: i64, photo_ids: Vec<i64>) -> Result<Album, String> {
+    let pool = get_pool(&app).await?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for photo_id in photo_ids {
+        sqlx::query("DELETE FROM album_photos WHERE album_id = ? AND photo_id = ?")
+            .bind(album_id)
+            .bind(photo_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        sqlx::query("UPDATE albums SET cover_photo_id = NULL WHERE id = ? AND cover_photo_id = ?")
+            .bind(album_id)
+            .bind(photo_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query("UPDATE albums SET updated_at = datetime('now') WHERE id = ?")
+        .bind(album_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    fetch_album(&pool, album_id).await
+}
+
+/// Set the order of an album's photos. `photo_ids` must list every photo of
+/// the album once.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn reorder_album_photos(app: AppHandle, album_id: i64, photo_ids: Vec<i64>) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let current = album_photo_ids(&pool, album_id).await?;
+    validate_album_order(&current, &photo_ids)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for (position, photo_id) in photo_ids.iter().enumerate() {
+        sqlx::query("UPDATE album_photos SET position = ? WHERE album_id = ? AND photo_id = ?")
+            .bind(position as i64)
+            .bind(album_id)
+            .bind(photo_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query("UPDATE albums SET updated_at = datetime('now') WHERE id = ?")
+        .bind(album_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_album_order() {
+        assert!(validate_album_order(&[1, 2, 3], &[3, 1, 2]).is_ok());
+        assert!(validate_album_order(&[], &[]).is_ok());
+
+        // Missing, extra and repeated photos
+        assert!(validate_album_order(&[1, 2, 3], &[1, 2]).is_err());
+        assert!(validate_album_order(&[1, 2], &[1, 2, 4]).is_err());
+        assert!(validate_album_order(&[1, 2], &[1, 1, 2]).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use std::io::Write;
+
+const LOCAL_HEADER: u32 = 0x04034b50;
+const CENTRAL_HEADER: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+/// Names are UTF-8 (general purpose flag bit 11)
+const FLAG_UTF8: u16 = 0x0800;
+
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    time: u16,
+    date: u16,
+    offset: u32,
+}
+
+/// Minimal zip writer. Entries are stored without compression: photos are
+/// already compressed and the sidecar files are small.
+pub(crate) struct ZipWriter<W: Write> {
+    out: W,
+    offset: u64,
+    entries: Vec<CentralEntry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W) -> Self {
+        ZipWriter { out, offset: 0, entries: Vec::new() }
+    }
+
+    /// Add a file. Archives are limited to 4 GB and 65535 files.
+    pub fn add_file(&mut self, name: &str, data: &[u8], modified: Option<NaiveDateTime>) -> Result<(), String> {
+        let offset = u32::try_from(self.offset).map_err(|_| "Export is larger than 4 GB".to_string())?;
+        let size = u32::try_from(data.len()).map_err(|_| format!("{} is larger than 4 GB", name))?;
+        if self.entries.len() == u16::MAX as usize {
+            return Err("Export has too many files".to_string());
+        }
+
+        let (time, date) = modified.map(dos_date_time).unwrap_or((0, 0x21));
+        let entry = CentralEntry { name: name.to_string(), crc: crc32fast::hash(data), size, time, date, offset };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        header.extend_from_slice(&10u16.to_le_bytes()); // Version needed
+        header.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // Stored
+        header.extend_from_slice(&entry.time.to_le_bytes());
+        header.extend_from_slice(&entry.date.to_le_bytes());
+        header.extend_from_slice(&entry.crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // Extra field
+        header.extend_from_slice(name.as_bytes());
+
+        self.write(&header)?;
+        self.write(data)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and return the output
+    pub fn finish(mut self) -> Result<W, String> {
+        let directory_offset = u32::try_from(self.offset).map_err(|_| "Export is larger than 4 GB".to_string())?;
+
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes()); // Version made by
+            directory.extend_from_slice(&10u16.to_le_bytes()); // Version needed
+            directory.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+            directory.extend_from_slice(&0u16.to_le_bytes()); // Stored
+            directory.extend_from_slice(&entry.time.to_le_bytes());
+            directory.extend_from_slice(&entry.date.to_le_bytes());
+            directory.extend_from_slice(&entry.crc.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 12]); // Extra, comment, disk, attributes
+            directory.extend_from_slice(&entry.offset.to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let count = self.entries.len() as u16;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        end.extend_from_slice(&[0; 4]); // Disk numbers
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        end.extend_from_slice(&directory_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // Comment
+
+        self.write(&directory)?;
+        self.write(&end)?;
+        self.out.flush().map_err(|e| format!("Failed to write export: {}", e))?;
+        Ok(self.out)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.out.write_all(bytes).map_err(|e| format!("Failed to write export: {}", e))?;
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+/// MS-DOS time and date, which start from 1980
+fn dos_date_time(dt: NaiveDateTime) -> (u16, u16) {
+    let time = (dt.hour() << 11 | dt.minute() << 5 | (dt.second() / 2)) as u16;
+    let date = ((dt.year().clamp(1980, 2107) - 1980) as u32) << 9 | dt.month() << 5 | dt.day();
+    (time, date as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], pos: usize) -> u16 {
+        u16::from_le_bytes([bytes[pos], bytes[pos + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], pos: usize) -> u32 {
+        u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_zip_writer_layout() {
+        let modified = NaiveDateTime::parse_from_str("2025-06-14 10:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add_file("2025-06-14_Äijälä.jpg", b"photo bytes", Some(modified)).unwrap();
+        zip.add_file("photos.json", b"[]", None).unwrap();
+        let bytes = zip.finish().unwrap();
+
+        // Local header, name and data of the first file
+        assert_eq!(u32_at(&bytes, 0), LOCAL_HEADER);
+        assert_eq!(u32_at(&bytes, 14), crc32fast::hash(b"photo bytes"));
+        assert_eq!(u16_at(&bytes, 10), 10 << 11 | 30 << 5);
+        assert_eq!(u16_at(&bytes, 12), 45 << 9 | 6 << 5 | 14);
+        let name_len = u16_at(&bytes, 26) as usize;
+        assert_eq!(&bytes[30..30 + name_len], "2025-06-14_Äijälä.jpg".as_bytes());
+        assert_eq!(&bytes[30 + name_len..30 + name_len + 11], b"photo bytes");
+
+        // End record points to a central directory listing both files
+        let end = bytes.len() - 22;
+        assert_eq!(u32_at(&bytes, end), END_OF_CENTRAL_DIRECTORY);
+        assert_eq!(u16_at(&bytes, end + 10), 2);
+        let directory = u32_at(&bytes, end + 16) as usize;
+        assert_eq!(u32_at(&bytes, directory), CENTRAL_HEADER);
+        assert_eq!(directory + u32_at(&bytes, end + 12) as usize, end);
+    }
+}
@@ -0,0 +1,94 @@
+use super::export::select_export_photos;
+use crate::database::get_pool;
+use crate::types::PhotoConsentViolation;
+use sqlx::Row;
+use tauri::AppHandle;
+
+/// Whether an athlete's photo consent covers an audience: "public" sharing
+/// needs public consent, "internal" (club use) either kind
+pub(crate) fn consent_allows(consent: &str, audience: &str) -> bool {
+    match audience {
+        "internal" => matches!(consent, "internal" | "public"),
+        _ => consent == "public",
+    }
+}
+
+pub(crate) fn validate_audience(audience: &str) -> Result<(), String> {
+    if ["internal", "public"].contains(&audience) {
+        Ok(())
+    } else {
+        Err(format!("Invalid audience: {}", audience))
+    }
+}
+
+/// Athletes tagged in the given photos, directly or through a result, whose
+/// consent doesn't cover the audience. One row per photo and athlete.
+pub(crate) async fn find_consent_violations(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    photo_ids: &[i64],
+    audience: &str,
+) -> Result<Vec<PhotoConsentViolation>, String> {
+    let ids = serde_json::to_string(photo_ids).map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query(
+        r#"SELECT DISTINCT t.photo_id, p.file_path, p.thumbnail_path, a.id as athlete_id,
+                  a.first_name || ' ' || a.last_name as athlete_name, a.photo_consent
+           FROM photo_tags t
+           JOIN photos p ON p.id = t.photo_id
+           JOIN athletes a ON a.id = CASE t.entity_type
+               WHEN 'athletes' THEN t.entity_id
+               WHEN 'results' THEN (SELECT r.athlete_id FROM results r WHERE r.id = t.entity_id)
+           END
+           WHERE t.photo_id IN (SELECT value FROM json_each(?))
+           ORDER BY t.photo_id, athlete_name"#
+    )
+    .bind(ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PhotoConsentViolation {
+            photo_id: row.get("photo_id"),
+            file_path: row.get("file_path"),
+            thumbnail_path: row.get("thumbnail_path"),
+            athlete_id: row.get("athlete_id"),
+            athlete_name: row.get("athlete_name"),
+            photo_consent: row.get("photo_consent"),
+        })
+        .filter(|v| !consent_allows(&v.photo_consent, audience))
+        .collect())
+}
+
+/// List the photos of an export (same `scope` and `id` as `export_photos`)
+/// that show athletes without consent for the audience ("internal" or "public")
+#[tauri::command]
+pub async fn get_photo_consent_violations(
+    app: AppHandle,
+    scope: String,
+    id: i64,
+    audience: String,
+) -> Result<Vec<PhotoConsentViolation>, String> {
+    let pool = get_pool(&app).await?;
+
+    validate_audience(&audience)?;
+    let ids: Vec<i64> = select_export_photos(&pool, &scope, id).await?.iter().map(|p| p.id).collect();
+
+    find_consent_violations(&pool, &ids, &audience).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consent_allows() {
+        assert!(consent_allows("public", "public"));
+        assert!(consent_allows("public", "internal"));
+        assert!(consent_allows("internal", "internal"));
+        assert!(!consent_allows("internal", "public"));
+        assert!(!consent_allows("none", "internal"));
+        assert!(!consent_allows("none", "public"));
+    }
+}
@@ -0,0 +1,251 @@
+use super::gallery::{open_oriented, PHOTO_COLUMNS};
+use crate::database::get_pool;
+use crate::types::PhotoDuplicateGroup;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Hamming distance at which two perceptual hashes count as the same picture
+pub const DEFAULT_SIMILARITY_DISTANCE: u32 = 6;
+
+/// SHA-256 of a file's bytes as lowercase hex
+pub(crate) fn content_hash(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 64-bit difference hash (dHash): each bit tells whether a pixel of a 9x8
+/// grayscale thumbnail is darker than its right neighbour. Resizing and
+/// re-encoding barely change it, so it finds the same picture in other files.
+pub(crate) fn perceptual_hash(img: &DynamicImage) -> String {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// Number of differing bits between two perceptual hashes
+pub(crate) fn hash_distance(a: &str, b: &str) -> Option<u32> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+    Some((a ^ b).count_ones())
+}
+
+pub(crate) struct HashedPhoto {
+    pub id: i64,
+    pub file_path: String,
+    pub content_hash: Option<String>,
+    pub perceptual_hash: Option<String>,
+}
+
+/// Group photos that have the same content hash or perceptual hashes within
+/// `max_distance`. Rows sharing one stored file (linked duplicates) are not
+/// compared with each other. Returns groups of two or more, as photo ids.
+pub(crate) fn group_similar(photos: &[HashedPhoto], max_distance: u32) -> Vec<Vec<i64>> {
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    let mut parent: Vec<usize> = (0..photos.len()).collect();
+
+    for i in 0..photos.len() {
+        for j in (i + 1)..photos.len() {
+            let (a, b) = (&photos[i], &photos[j]);
+            if a.file_path == b.file_path {
+                continue;
+            }
+
+            let same_content = matches!((&a.content_hash, &b.content_hash), (Some(x), Some(y)) if x == y);
+            let similar = match (&a.perceptual_hash, &b.perceptual_hash) {
+                (Some(x), Some(y)) => hash_distance(x, y).is_some_and(|d| d <= max_distance),
+                _ => false,
+            };
+
+            if same_content || similar {
+                let (root_a, root_b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[root_b.max(root_a)] = root_a.min(root_b);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<i64>> = Vec::new();
+    let mut group_of_root: std::collections::HashMap<usize, usize> = std::collections::HashMap::new();
+    for (i, photo) in photos.iter().enumerate() {
+        let root = find(&mut parent, i);
+        let index = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(photo.id);
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Compute hashes for photos imported before hashing existed
+async fn backfill_hashes(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<(), String> {
+    let rows = sqlx::query("SELECT id, file_path FROM photos WHERE content_hash IS NULL OR perceptual_hash IS NULL")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for row in rows {
+        let id: i64 = row.get("id");
+        let path = PathBuf::from(row.get::<String, _>("file_path"));
+
+        let hashes = tokio::task::spawn_blocking(move || {
+            let content = content_hash(&path).ok();
+            let perceptual = open_oriented(&path).ok().map(|img| perceptual_hash(&img));
+            (content, perceptual)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            "UPDATE photos SET content_hash = COALESCE(content_hash, ?), perceptual_hash = COALESCE(perceptual_hash, ?) WHERE id = ?"
+        )
+        .bind(hashes.0)
+        .bind(hashes.1)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Find groups of duplicate or near-duplicate photos across the library for
+/// review. `max_distance` is the perceptual hash distance (0-64) still
+/// counted as the same picture.
+#[tauri::command]
+pub async fn find_similar_photos(app: AppHandle, max_distance: Option<u32>) -> Result<Vec<PhotoDuplicateGroup>, String> {
+    let pool = get_pool(&app).await?;
+
+    backfill_hashes(&pool).await?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT {}, content_hash, perceptual_hash FROM photos ORDER BY COALESCE(taken_at, created_at), id",
+        PHOTO_COLUMNS
+    ))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let hashed: Vec<HashedPhoto> = rows
+        .iter()
+        .map(|row| HashedPhoto {
+            id: row.get("id"),
+            file_path: row.get("file_path"),
+            content_hash: row.get("content_hash"),
+            perceptual_hash: row.get("perceptual_hash"),
+        })
+        .collect();
+
+    let groups = group_similar(&hashed, max_distance.unwrap_or(DEFAULT_SIMILARITY_DISTANCE));
+
+    Ok(groups
+        .into_iter()
+        .map(|ids| {
+            let members: Vec<&sqlx::sqlite::SqliteRow> = ids
+                .iter()
+                .filter_map(|id| rows.iter().find(|row| row.get::<i64, _>("id") == *id))
+                .collect();
+            let first_hash: Option<String> = members[0].get("content_hash");
+            let exact = first_hash.is_some()
+                && members.iter().all(|row| row.get::<Option<String>, _>("content_hash") == first_hash);
+
+            PhotoDuplicateGroup {
+                exact,
+                photos: members.into_iter().map(|row| photo_from_row!(row)).collect(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn hashed(id: i64, content: Option<&str>, perceptual: Option<&str>) -> HashedPhoto {
+        HashedPhoto {
+            id,
+            file_path: format!("/photos/{}.jpg", id),
+            content_hash: content.map(String::from),
+            perceptual_hash: perceptual.map(String::from),
+        }
+    }
+
+    fn gradient(width: u32, height: u32, flip: bool) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, _| {
+            let value = (x * 255 / (width - 1)) as u8;
+            Luma([if flip { 255 - value } else { value }])
+        }))
+    }
+
+    #[test]
+    fn test_perceptual_hash_survives_resize() {
+        let large = perceptual_hash(&gradient(900, 600, false));
+        let small = perceptual_hash(&gradient(300, 200, false));
+        assert_eq!(hash_distance(&large, &small), Some(0));
+
+        // Brightening to the right sets every bit, the mirror image sets none
+        assert_eq!(large, "ffffffffffffffff");
+        let mirrored = perceptual_hash(&gradient(900, 600, true));
+        assert_eq!(hash_distance(&large, &mirrored), Some(64));
+    }
+
+    #[test]
+    fn test_hash_distance() {
+        assert_eq!(hash_distance("0000000000000000", "0000000000000003"), Some(2));
+        assert_eq!(hash_distance("ffffffffffffffff", "0000000000000000"), Some(64));
+        assert_eq!(hash_distance("not hex", "0000000000000000"), None);
+    }
+
+    #[test]
+    fn test_group_similar() {
+        let photos = vec![
+            hashed(1, Some("aaa"), Some("0000000000000000")),
+            hashed(2, Some("bbb"), Some("ffffffffffffffff")),
+            hashed(3, Some("aaa"), None), // same bytes as 1
+            hashed(4, Some("ccc"), Some("0000000000000007")), // 3 bits from 1
+            hashed(5, Some("ddd"), Some("000000000000ff00")), // 8 bits from 1, 11 from 4
+            hashed(6, None, None),
+        ];
+
+        assert_eq!(group_similar(&photos, 6), vec![vec![1, 3, 4]]);
+        assert_eq!(group_similar(&photos, 8), vec![vec![1, 3, 4, 5]]);
+        assert_eq!(group_similar(&photos, 0), vec![vec![1, 3]]);
+    }
+
+    #[test]
+    fn test_group_similar_ignores_shared_files() {
+        let mut linked = hashed(2, Some("aaa"), Some("0000000000000000"));
+        linked.file_path = "/photos/1.jpg".to_string();
+        let photos = vec![hashed(1, Some("aaa"), Some("0000000000000000")), linked];
+
+        assert!(group_similar(&photos, 6).is_empty());
+    }
+}
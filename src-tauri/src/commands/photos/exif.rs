@@ -0,0 +1,470 @@
+use chrono::NaiveDateTime;
+use image::{ImageDecoder, ImageReader};
+use std::path::{Path, PathBuf};
+
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_DATE_TIME_DIGITIZED: u16 = 0x9004;
+
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+
+/// Metadata read from a photo's EXIF block
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExifInfo {
+    /// Capture time as "YYYY-MM-DD HH:MM:SS", the same format as SQLite's datetime()
+    pub taken_at: Option<String>,
+    /// EXIF orientation (1-8)
+    pub orientation: Option<i32>,
+}
+
+/// Read EXIF metadata from an image file. Files without EXIF or with a
+/// broken EXIF block give an empty result instead of an error.
+pub fn read_exif(path: &Path) -> ExifInfo {
+    ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .ok()
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.exif_metadata().ok().flatten())
+        .map(|chunk| parse_exif(&chunk))
+        .unwrap_or_default()
+}
+
+/// Read capture date and orientation for photos stored before EXIF was read on
+/// import. Orientation doubles as the marker: photos without it get 1 (upright)
+/// once their file has been read. Missing files are retried on the next run.
+pub(crate) async fn backfill_exif_metadata(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<(), String> {
+    let photos: Vec<(i64, String)> = sqlx::query_as("SELECT id, file_path FROM photos WHERE orientation IS NULL")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for (id, file_path) in photos {
+        let path = PathBuf::from(file_path);
+        let exif = tokio::task::spawn_blocking(move || path.is_file().then(|| read_exif(&path)))
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(exif) = exif else {
+            continue;
+        };
+
+        sqlx::query("UPDATE photos SET taken_at = COALESCE(taken_at, ?), orientation = ? WHERE id = ?")
+            .bind(&exif.taken_at)
+            .bind(exif.orientation.unwrap_or(1))
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Parse a raw EXIF chunk (a TIFF structure, optionally prefixed with "Exif\0\0")
+pub fn parse_exif(chunk: &[u8]) -> ExifInfo {
+    let data = chunk.strip_prefix(b"Exif\0\0".as_slice()).unwrap_or(chunk);
+    let little_endian = match data.get(..4) {
+        Some([0x49, 0x49, 42, 0]) => true,
+        Some([0x4d, 0x4d, 0, 42]) => false,
+        _ => return ExifInfo::default(),
+    };
+    let tiff = Tiff { data, little_endian };
+
+    let Some(ifd0) = tiff.u32(4).map(|o| o as usize) else {
+        return ExifInfo::default();
+    };
+    let ifd0_entries = tiff.entries(ifd0);
+
+    let orientation = ifd0_entries
+        .iter()
+        .find(|e| e.tag == TAG_ORIENTATION && e.kind == TYPE_SHORT)
+        .and_then(|e| tiff.u16(e.value_pos))
+        .filter(|o| (1..=8).contains(o))
+        .map(i32::from);
+
+    let exif_entries = ifd0_entries
+        .iter()
+        .find(|e| e.tag == TAG_EXIF_IFD && e.kind == TYPE_LONG)
+        .and_then(|e| tiff.u32(e.value_pos))
+        .map(|offset| tiff.entries(offset as usize))
+        .unwrap_or_default();
+
+    // Prefer the moment the shutter fired over later edits
+    let date_of = |entries: &[Entry], tag: u16| {
+        entries
+            .iter()
+            .find(|e| e.tag == tag)
+            .and_then(|e| tiff.ascii(e))
+            .and_then(|s| parse_exif_date(&s))
+    };
+    let taken_at = date_of(&exif_entries, TAG_DATE_TIME_ORIGINAL)
+        .or_else(|| date_of(&exif_entries, TAG_DATE_TIME_DIGITIZED))
+        .or_else(|| date_of(&ifd0_entries, TAG_DATE_TIME));
+
+    ExifInfo { taken_at, orientation }
+}
+
+/// "2025:06:14 10:30:00" -> "2025-06-14 10:30:00"
+fn parse_exif_date(value: &str) -> Option<String> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y:%m:%d %H:%M:%S")
+        .ok()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// IFD0 fields naming the photographer or their computer
+const PRIVATE_IFD0_TAGS: [u16; 4] = [
+    0x013b, // Artist
+    0x013c, // HostComputer
+    0x9c9c, // XPComment
+    0x9c9d, // XPAuthor
+];
+
+/// Exif IFD fields with owner names, serial numbers or free text. Maker notes
+/// are vendor blobs that often repeat the serial number and location.
+const PRIVATE_EXIF_TAGS: [u16; 6] = [
+    0x927c, // MakerNote
+    0x9286, // UserComment
+    0xa420, // ImageUniqueID
+    0xa430, // CameraOwnerName
+    0xa431, // BodySerialNumber
+    0xa435, // LensSerialNumber
+];
+
+/// Remove the GPS location and identifying fields (owner, serial numbers,
+/// comments, maker notes) from a raw EXIF chunk in place. The capture time
+/// and orientation are kept. Returns whether anything was removed.
+///
+/// The chunk keeps its length so no offsets have to move: removed values are
+/// zeroed and their entries dropped from the IFD.
+pub fn strip_exif_private(chunk: &mut [u8]) -> bool {
+    let start = if chunk.starts_with(b"Exif\0\0") { 6 } else { 0 };
+    let data = &mut chunk[start..];
+    let little_endian = match data.get(..4) {
+        Some([0x49, 0x49, 42, 0]) => true,
+        Some([0x4d, 0x4d, 0, 42]) => false,
+        _ => return false,
+    };
+
+    let (ifd0, gps, exif) = {
+        let tiff = Tiff { data, little_endian };
+        let Some(ifd0) = tiff.u32(4).map(|o| o as usize) else {
+            return false;
+        };
+        let entries = tiff.entries(ifd0);
+        let pointer = |tag: u16| {
+            entries.iter()
+                .find(|e| e.tag == tag)
+                .and_then(|e| tiff.u32(e.value_pos))
+                .map(|o| o as usize)
+        };
+        (ifd0, pointer(TAG_GPS_IFD), pointer(TAG_EXIF_IFD))
+    };
+
+    // Empty the GPS IFD before unlinking it so the coordinates are gone too
+    let mut removed = gps.map_or(0, |gps| remove_ifd_entries(data, little_endian, gps, |_| true));
+    removed += remove_ifd_entries(data, little_endian, ifd0, |tag| {
+        tag == TAG_GPS_IFD || PRIVATE_IFD0_TAGS.contains(&tag)
+    });
+    if let Some(exif) = exif {
+        removed += remove_ifd_entries(data, little_endian, exif, |tag| PRIVATE_EXIF_TAGS.contains(&tag));
+    }
+
+    removed > 0
+}
+
+/// Drop the matching entries of an IFD in place: their values stored outside
+/// the IFD are zeroed and later entries and the next-IFD offset move up.
+/// Returns the number of entries removed.
+fn remove_ifd_entries(data: &mut [u8], little_endian: bool, ifd: usize, remove: impl Fn(u16) -> bool) -> usize {
+    let (indexes, count, values) = {
+        let tiff = Tiff { data, little_endian };
+        let entries = tiff.entries(ifd);
+        let mut indexes = Vec::new();
+        let mut values = Vec::new();
+        for (index, entry) in entries.iter().enumerate().filter(|(_, e)| remove(e.tag)) {
+            indexes.push(index);
+            let size = type_size(entry.kind).saturating_mul(entry.count as usize);
+            if size > 4 {
+                if let Some(offset) = tiff.u32(entry.value_pos) {
+                    values.push((offset as usize, size));
+                }
+            }
+        }
+        (indexes, entries.len(), values)
+    };
+
+    let mut end = ifd + 2 + count * 12 + 4;
+    if indexes.is_empty() || end > data.len() {
+        return 0;
+    }
+
+    for (offset, len) in values {
+        if let Some(bytes) = offset.checked_add(len).and_then(|end| data.get_mut(offset..end)) {
+            bytes.fill(0);
+        }
+    }
+
+    for &index in indexes.iter().rev() {
+        let entry = ifd + 2 + index * 12;
+        data.copy_within(entry + 12..end, entry);
+        data[end - 12..end].fill(0);
+        end -= 12;
+    }
+
+    let remaining = ((count - indexes.len()) as u16).to_le_bytes();
+    let remaining = if little_endian { remaining } else { [remaining[1], remaining[0]] };
+    data[ifd..ifd + 2].copy_from_slice(&remaining);
+
+    indexes.len()
+}
+
+/// Size in bytes of one value of a TIFF field type
+fn type_size(kind: u16) -> usize {
+    match kind {
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => 1,
+    }
+}
+
+struct Entry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    value_pos: usize,
+}
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl Tiff<'_> {
+    fn u16(&self, pos: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32(&self, pos: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn entries(&self, ifd: usize) -> Vec<Entry> {
+        let count = self.u16(ifd).unwrap_or(0) as usize;
+        (0..count)
+            .map_while(|i| {
+                let pos = ifd + 2 + i * 12;
+                Some(Entry {
+                    tag: self.u16(pos)?,
+                    kind: self.u16(pos + 2)?,
+                    count: self.u32(pos + 4)?,
+                    value_pos: pos + 8,
+                })
+            })
+            .collect()
+    }
+
+    fn ascii(&self, entry: &Entry) -> Option<String> {
+        if entry.kind != TYPE_ASCII {
+            return None;
+        }
+        let len = entry.count as usize;
+        // Values longer than four bytes are stored at an offset
+        let start = if len <= 4 { entry.value_pos } else { self.u32(entry.value_pos)? as usize };
+        let bytes = self.data.get(start..start.checked_add(len)?)?;
+        let text = std::str::from_utf8(bytes).ok()?;
+        Some(text.trim_end_matches('\0').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Little-endian TIFF with orientation in IFD0 and DateTimeOriginal in the Exif IFD
+    fn sample_exif(orientation: u16, date: &str) -> Vec<u8> {
+        let mut d = Vec::new();
+        d.extend_from_slice(b"II*\0");
+        d.extend_from_slice(&8u32.to_le_bytes());
+
+        // IFD0 at 8: two entries, ends at 38
+        d.extend_from_slice(&2u16.to_le_bytes());
+        d.extend_from_slice(&TAG_ORIENTATION.to_le_bytes());
+        d.extend_from_slice(&TYPE_SHORT.to_le_bytes());
+        d.extend_from_slice(&1u32.to_le_bytes());
+        d.extend_from_slice(&[orientation as u8, 0, 0, 0]);
+        d.extend_from_slice(&TAG_EXIF_IFD.to_le_bytes());
+        d.extend_from_slice(&TYPE_LONG.to_le_bytes());
+        d.extend_from_slice(&1u32.to_le_bytes());
+        d.extend_from_slice(&38u32.to_le_bytes());
+        d.extend_from_slice(&0u32.to_le_bytes());
+
+        // Exif IFD at 38: one entry, ends at 56
+        d.extend_from_slice(&1u16.to_le_bytes());
+        d.extend_from_slice(&TAG_DATE_TIME_ORIGINAL.to_le_bytes());
+        d.extend_from_slice(&TYPE_ASCII.to_le_bytes());
+        d.extend_from_slice(&((date.len() + 1) as u32).to_le_bytes());
+        d.extend_from_slice(&56u32.to_le_bytes());
+        d.extend_from_slice(&0u32.to_le_bytes());
+
+        d.extend_from_slice(date.as_bytes());
+        d.push(0);
+        d
+    }
+
+    #[test]
+    fn test_parse_exif_date_and_orientation() {
+        let info = parse_exif(&sample_exif(6, "2025:06:14 10:30:00"));
+        assert_eq!(info.taken_at.as_deref(), Some("2025-06-14 10:30:00"));
+        assert_eq!(info.orientation, Some(6));
+    }
+
+    #[test]
+    fn test_parse_exif_with_header_prefix() {
+        let mut chunk = b"Exif\0\0".to_vec();
+        chunk.extend(sample_exif(1, "2024:01:02 03:04:05"));
+        let info = parse_exif(&chunk);
+        assert_eq!(info.taken_at.as_deref(), Some("2024-01-02 03:04:05"));
+        assert_eq!(info.orientation, Some(1));
+    }
+
+    #[test]
+    fn test_parse_exif_invalid_values() {
+        // Zeroed-out date written by some cameras and an out-of-range orientation
+        let info = parse_exif(&sample_exif(9, "0000:00:00 00:00:00"));
+        assert_eq!(info, ExifInfo::default());
+    }
+
+    #[test]
+    fn test_parse_exif_garbage() {
+        assert_eq!(parse_exif(b""), ExifInfo::default());
+        assert_eq!(parse_exif(b"not a tiff header"), ExifInfo::default());
+        // Valid header pointing past the end
+        assert_eq!(parse_exif(b"II*\0\xff\0\0\0"), ExifInfo::default());
+    }
+
+    #[test]
+    fn test_strip_exif_gps() {
+        let date = "2025:06:14 10:30:00\0";
+        let mut d = Vec::new();
+        d.extend_from_slice(b"II*\0");
+        d.extend_from_slice(&8u32.to_le_bytes());
+
+        // IFD0 at 8: orientation, Exif and GPS pointers, ends at 50
+        d.extend_from_slice(&3u16.to_le_bytes());
+        for (tag, kind, value) in [(TAG_ORIENTATION, TYPE_SHORT, 6), (TAG_EXIF_IFD, TYPE_LONG, 50), (TAG_GPS_IFD, TYPE_LONG, 88)] {
+            d.extend_from_slice(&tag.to_le_bytes());
+            d.extend_from_slice(&kind.to_le_bytes());
+            d.extend_from_slice(&1u32.to_le_bytes());
+            d.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        d.extend_from_slice(&0u32.to_le_bytes());
+
+        // Exif IFD at 50 with the date at 68
+        d.extend_from_slice(&1u16.to_le_bytes());
+        d.extend_from_slice(&TAG_DATE_TIME_ORIGINAL.to_le_bytes());
+        d.extend_from_slice(&TYPE_ASCII.to_le_bytes());
+        d.extend_from_slice(&(date.len() as u32).to_le_bytes());
+        d.extend_from_slice(&68u32.to_le_bytes());
+        d.extend_from_slice(&0u32.to_le_bytes());
+        d.extend_from_slice(date.as_bytes());
+
+        // GPS IFD at 88: latitude as three rationals at 106
+        d.extend_from_slice(&1u16.to_le_bytes());
+        d.extend_from_slice(&2u16.to_le_bytes());
+        d.extend_from_slice(&5u16.to_le_bytes());
+        d.extend_from_slice(&3u32.to_le_bytes());
+        d.extend_from_slice(&106u32.to_le_bytes());
+        d.extend_from_slice(&0u32.to_le_bytes());
+        for value in [60u32, 1, 10, 1, 30, 1] {
+            d.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut chunk = b"Exif\0\0".to_vec();
+        chunk.extend_from_slice(&d);
+
+        assert!(strip_exif_private(&mut chunk));
+        assert_eq!(chunk.len(), 6 + d.len());
+        let tiff = Tiff { data: &chunk[6..], little_endian: true };
+        assert!(tiff.entries(8).iter().all(|e| e.tag != TAG_GPS_IFD));
+        assert!(chunk[6 + 88..].iter().all(|&b| b == 0));
+
+        // Everything else still reads
+        let info = parse_exif(&chunk);
+        assert_eq!(info.orientation, Some(6));
+        assert_eq!(info.taken_at.as_deref(), Some("2025-06-14 10:30:00"));
+
+        assert!(!strip_exif_private(&mut chunk));
+        assert!(!strip_exif_private(&mut b"not a tiff header".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_exif_metadata() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE photos (id INTEGER PRIMARY KEY, file_path TEXT NOT NULL, taken_at TEXT, orientation INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("loikka-exif-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // JPEG with an APP1 EXIF segment right after SOI
+        let mut jpeg = Vec::new();
+        image::RgbImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+            .unwrap();
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend(sample_exif(6, "2024:06:01 10:20:30"));
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend_from_slice(&((app1.len() + 2) as u16).to_be_bytes());
+        segment.extend(app1);
+        jpeg.splice(2..2, segment);
+
+        let with_exif = dir.join("exif.jpg");
+        std::fs::write(&with_exif, &jpeg).unwrap();
+        let without_exif = dir.join("plain.jpg");
+        std::fs::write(&without_exif, b"not an image").unwrap();
+
+        for (id, path) in [
+            (1, with_exif.to_string_lossy().to_string()),
+            (2, without_exif.to_string_lossy().to_string()),
+            (3, dir.join("missing.jpg").to_string_lossy().to_string()),
+        ] {
+            sqlx::query("INSERT INTO photos (id, file_path) VALUES (?, ?)")
+                .bind(id)
+                .bind(path)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        backfill_exif_metadata(&pool).await.unwrap();
+
+        let rows: Vec<(i64, Option<String>, Option<i32>)> =
+            sqlx::query_as("SELECT id, taken_at, orientation FROM photos ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (1, Some("2024-06-01 10:20:30".to_string()), Some(6)),
+                (2, None, Some(1)),
+                (3, None, None),
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,373 @@
+use super::archive::ZipWriter;
+use super::consent::{find_consent_violations, validate_audience};
+use super::gallery::PHOTO_COLUMNS;
+use super::privacy::sanitize_image_metadata;
+use super::tags::load_photo_tags;
+use crate::commands::settings::load_photo_settings;
+use crate::database::get_pool;
+use crate::types::{Photo, PhotoExport, PhotoExportSummary, PhotoTag};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// One photo in the sidecar files
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SidecarEntry {
+    file_name: String,
+    photo_id: i64,
+    original_name: String,
+    taken_at: Option<String>,
+    event_name: Option<String>,
+    tags: Vec<PhotoTag>,
+    consent_missing: Vec<String>, // Athletes without consent for the audience
+}
+
+/// A photo file to put in the archive
+struct ExportFile {
+    name: String,
+    source: PathBuf,
+    modified: Option<NaiveDateTime>,
+}
+
+/// Keep letters and digits of a name part; everything else becomes a dash
+fn file_name_part(value: &str) -> String {
+    let mut part = String::new();
+    for c in value.chars() {
+        if c.is_alphanumeric() {
+            part.push(c);
+        } else if !part.is_empty() && !part.ends_with('-') {
+            part.push('-');
+        }
+    }
+    part.trim_end_matches('-').to_string()
+}
+
+/// "date_athlete_competition.ext", numbered when the name is taken. Album
+/// exports are prefixed with the position to keep the album order.
+pub(crate) fn export_file_name(photo: &Photo, tags: &[PhotoTag], position: Option<usize>, used: &mut HashSet<String>) -> String {
+    let first_name = |entity_type: &str| {
+        tags.iter()
+            .filter(|t| t.entity_type == entity_type)
+            .find_map(|t| t.entity_name.as_deref())
+    };
+    let date = photo.taken_at.as_deref().unwrap_or(&photo.created_at);
+    let athlete = first_name("athletes").or_else(|| first_name("results"));
+
+    let mut parts: Vec<String> = position.map(|p| format!("{:03}", p + 1)).into_iter().collect();
+    parts.extend(
+        [date.get(..10), athlete, first_name("competitions")]
+            .into_iter()
+            .flatten()
+            .map(file_name_part)
+            .filter(|p| !p.is_empty()),
+    );
+    if parts.is_empty() {
+        parts.push(format!("photo-{}", photo.id));
+    }
+
+    let extension = Path::new(&photo.file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("jpg")
+        .to_lowercase();
+    let base = parts.join("_");
+
+    let mut name = format!("{}.{}", base, extension);
+    let mut n = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{}_{}.{}", base, n, extension);
+        n += 1;
+    }
+    name
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(entries: &[SidecarEntry]) -> String {
+    let mut csv = String::from("file_name,photo_id,original_name,taken_at,event_name,athletes,competitions,results,captions,consent_missing\n");
+    for entry in entries {
+        let names = |entity_type: &str| {
+            entry.tags.iter()
+                .filter(|t| t.entity_type == entity_type)
+                .filter_map(|t| t.entity_name.as_deref())
+                .collect::<Vec<_>>()
+                .join("; ")
+        };
+        let captions = entry.tags.iter().filter_map(|t| t.caption.as_deref()).collect::<Vec<_>>().join("; ");
+
+        let fields = [
+            entry.file_name.clone(),
+            entry.photo_id.to_string(),
+            entry.original_name.clone(),
+            entry.taken_at.clone().unwrap_or_default(),
+            entry.event_name.clone().unwrap_or_default(),
+            names("athletes"),
+            names("competitions"),
+            names("results"),
+            captions,
+            entry.consent_missing.join("; "),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Write the archive next to the destination and move it in place when done.
+/// Returns the archive size, photos written, photos whose metadata was
+/// stripped and missing files.
+fn write_archive(
+    destination: &Path,
+    files: &[ExportFile],
+    sidecars: &[(&str, Vec<u8>)],
+    strip_metadata: bool,
+) -> Result<(i64, i64, i64, i64), String> {
+    let tmp_path = destination.with_extension("zip.tmp");
+    let file = fs::File::create(&tmp_path).map_err(|e| format!("Failed to create export: {}", e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let (mut written, mut gps_stripped, mut missing) = (0, 0, 0);
+
+    let result = (|| {
+        for file in files {
+            let Ok(mut bytes) = fs::read(&file.source) else {
+                missing += 1;
+                continue;
+            };
+            if strip_metadata {
+                if let Some(sanitized) = sanitize_image_metadata(&bytes) {
+                    bytes = sanitized;
+                    gps_stripped += 1;
+                }
+            }
+            zip.add_file(&file.name, &bytes, file.modified)?;
+            written += 1;
+        }
+        for (name, bytes) in sidecars {
+            zip.add_file(name, bytes, None)?;
+        }
+        zip.finish()
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    fs::rename(&tmp_path, destination).map_err(|e| format!("Failed to save export: {}", e))?;
+
+    let size = fs::metadata(destination).map_err(|e| e.to_string())?.len() as i64;
+    Ok((size, written, gps_stripped, missing))
+}
+
+/// Photos of an export scope: "athlete", "competition", "album" (in album
+/// order) or "year"
+pub(crate) async fn select_export_photos(pool: &sqlx::Pool<sqlx::Sqlite>, scope: &str, id: i64) -> Result<Vec<Photo>, String> {
+    let filter = match scope {
+        "athlete" => {
+            r#"WHERE EXISTS (
+                   SELECT 1 FROM photo_tags t WHERE t.photo_id = photos.id AND (
+                       (t.entity_type = 'athletes' AND t.entity_id = ?1)
+                    OR (t.entity_type = 'results' AND t.entity_id IN (SELECT id FROM results WHERE athlete_id = ?1))))
+               ORDER BY COALESCE(taken_at, created_at), id"#
+        }
+        "competition" => {
+            r#"WHERE EXISTS (
+                   SELECT 1 FROM photo_tags t WHERE t.photo_id = photos.id AND t.entity_type = 'competitions' AND t.entity_id = ?1)
+               ORDER BY COALESCE(taken_at, created_at), id"#
+        }
+        "album" => {
+            r#"JOIN (SELECT photo_id, position FROM album_photos WHERE album_id = ?1) ap ON ap.photo_id = photos.id
+               ORDER BY ap.position"#
+        }
+        "year" => {
+            r#"WHERE CAST(strftime('%Y', COALESCE(taken_at, created_at)) AS INTEGER) = ?1
+               ORDER BY COALESCE(taken_at, created_at), id"#
+        }
+        _ => return Err(format!("Unsupported export scope: {}", scope)),
+    };
+
+    let rows = sqlx::query(&format!("SELECT {} FROM photos {}", PHOTO_COLUMNS, filter))
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| photo_from_row!(row)).collect())
+}
+
+/// Export photos to a zip file with human-readable names and `photos.json`
+/// and `photos.csv` sidecars listing the tagged athletes, competitions and
+/// results with their captions. `strip_gps` removes the location and other
+/// identifying metadata from JPEG, PNG and WebP files; it is implied when the
+/// photo settings strip metadata.
+/// Athletes' photo consent is checked against the audience (default "public").
+#[tauri::command]
+pub async fn export_photos(app: AppHandle, export: PhotoExport) -> Result<PhotoExportSummary, String> {
+    let pool = get_pool(&app).await?;
+
+    let audience = export.audience.clone().unwrap_or_else(|| "public".to_string());
+    validate_audience(&audience)?;
+    // Photos showing athletes without consent are left out, or with "flag"
+    // exported and listed in the sidecars
+    let flag_violations = match export.on_consent_violation.as_deref() {
+        None | Some("exclude") => false,
+        Some("flag") => true,
+        Some(other) => return Err(format!("Invalid consent handling: {}", other)),
+    };
+
+    let photos = select_export_photos(&pool, &export.scope, export.id).await?;
+    if photos.is_empty() {
+        return Err("No photos to export".to_string());
+    }
+
+    let ids: Vec<i64> = photos.iter().map(|p| p.id).collect();
+    let mut tags = load_photo_tags(&pool, &ids).await?;
+    let mut without_consent: HashMap<i64, Vec<String>> = HashMap::new();
+    for violation in find_consent_violations(&pool, &ids, &audience).await? {
+        without_consent.entry(violation.photo_id).or_default().push(violation.athlete_name);
+    }
+
+    let mut used = HashSet::new();
+    let mut files = Vec::new();
+    let mut entries = Vec::new();
+    let (mut excluded_for_consent, mut flagged_for_consent) = (0, 0);
+    for (index, photo) in photos.into_iter().enumerate() {
+        let consent_missing = without_consent.remove(&photo.id).unwrap_or_default();
+        if !consent_missing.is_empty() {
+            if !flag_violations {
+                excluded_for_consent += 1;
+                continue;
+            }
+            flagged_for_consent += 1;
+        }
+
+        let tags = tags.remove(&photo.id).unwrap_or_default();
+        let position = (export.scope == "album").then_some(index);
+        let name = export_file_name(&photo, &tags, position, &mut used);
+
+        if !Path::new(&photo.file_path).exists() {
+            continue;
+        }
+        files.push(ExportFile {
+            name: name.clone(),
+            source: PathBuf::from(&photo.file_path),
+            modified: photo
+                .taken_at
+                .as_deref()
+                .and_then(|t| NaiveDateTime::parse_from_str(t, "%Y-%m-%d %H:%M:%S").ok()),
+        });
+        entries.push(SidecarEntry {
+            file_name: name,
+            photo_id: photo.id,
+            original_name: photo.original_name,
+            taken_at: photo.taken_at,
+            event_name: photo.event_name,
+            tags,
+            consent_missing,
+        });
+    }
+    if files.is_empty() && excluded_for_consent > 0 {
+        return Err("No photos to export: every photo shows athletes without publishing consent".to_string());
+    }
+    let missing_files = ids.len() as i64 - excluded_for_consent - files.len() as i64;
+
+    let sidecars = vec![
+        ("photos.json", serde_json::to_vec_pretty(&entries).map_err(|e| e.to_string())?),
+        ("photos.csv", render_csv(&entries).into_bytes()),
+    ];
+
+    let destination = PathBuf::from(&export.destination_path);
+    let strip_metadata = export.strip_gps || load_photo_settings(&pool).await?.strip_metadata;
+    let (size_bytes, photo_count, gps_stripped, unreadable) =
+        tokio::task::spawn_blocking(move || write_archive(&destination, &files, &sidecars, strip_metadata))
+            .await
+            .map_err(|e| e.to_string())??;
+
+    Ok(PhotoExportSummary {
+        path: export.destination_path,
+        photo_count,
+        size_bytes,
+        gps_stripped,
+        missing_files: missing_files + unreadable,
+        excluded_for_consent,
+        flagged_for_consent,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn photo(id: i64, file_path: &str, taken_at: Option<&str>) -> Photo {
+        Photo {
+            id,
+            entity_type: "athletes".to_string(),
+            entity_id: 1,
+            file_path: file_path.to_string(),
+            thumbnail_path: None,
+            preview_path: None,
+            original_name: "IMG_0001.JPG".to_string(),
+            width: None,
+            height: None,
+            size_bytes: 0,
+            event_name: None,
+            taken_at: taken_at.map(str::to_string),
+            orientation: None,
+            created_at: "2025-01-02 08:00:00".to_string(),
+        }
+    }
+
+    fn tag(entity_type: &str, name: &str) -> PhotoTag {
+        PhotoTag {
+            id: 0,
+            photo_id: 1,
+            entity_type: entity_type.to_string(),
+            entity_id: 1,
+            caption: None,
+            entity_name: Some(name.to_string()),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_export_file_name() {
+        let mut used = HashSet::new();
+        let tags = [tag("competitions", "Kalevan kisat 2025"), tag("athletes", "Aino Mäkelä")];
+        let p = photo(1, "/photos/a.JPG", Some("2025-06-14 10:30:00"));
+
+        assert_eq!(export_file_name(&p, &tags, None, &mut used), "2025-06-14_Aino-Mäkelä_Kalevan-kisat-2025.jpg");
+        assert_eq!(export_file_name(&p, &tags, None, &mut used), "2025-06-14_Aino-Mäkelä_Kalevan-kisat-2025_2.jpg");
+
+        // Result tags name the athlete; the upload date stands in for a missing capture date
+        let p = photo(2, "/photos/b.png", None);
+        assert_eq!(export_file_name(&p, &[tag("results", "Eero Virtanen")], Some(4), &mut used), "005_2025-01-02_Eero-Virtanen.png");
+    }
+
+    #[test]
+    fn test_render_csv_escapes_fields() {
+        let mut t = tag("athletes", "Aino");
+        t.caption = Some("Finish, \"photo\" finish".to_string());
+        let csv = render_csv(&[SidecarEntry {
+            file_name: "a.jpg".to_string(),
+            photo_id: 1,
+            original_name: "IMG.JPG".to_string(),
+            taken_at: None,
+            event_name: None,
+            tags: vec![t],
+            consent_missing: vec!["Aino".to_string()],
+        }]);
+
+        assert_eq!(csv.lines().nth(1), Some("a.jpg,1,IMG.JPG,,,Aino,,,\"Finish, \"\"photo\"\" finish\",Aino"));
+    }
+}
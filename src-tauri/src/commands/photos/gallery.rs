@@ -0,0 +1,1003 @@
+use super::dedup::{content_hash, perceptual_hash};
+use super::exif::read_exif;
+use super::privacy::sanitize_photo_file;
+use super::storage::{get_originals_dir, optimize_decoded_photo};
+use super::tags::{add_photo_tag, load_photo_tags};
+use super::thumbnails::{get_thumbnail_cache_dir, render_thumbnails, ThumbnailPaths};
+use crate::commands::settings::load_photo_settings;
+use crate::database::get_pool;
+use crate::types::{CropRect, Photo, PhotoCompetitionSuggestion, PhotoSettings, PhotoTag};
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, ImageDecoder, ImageReader};
+use sqlx::Row;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+/// Columns of a `Photo` (see `photo_from_row!`)
+pub(crate) const PHOTO_COLUMNS: &str = "id, entity_type, entity_id, file_path, thumbnail_path, preview_path, original_name, width, height, size_bytes, event_name, taken_at, orientation, created_at";
+/// Image formats accepted for photos
+pub(crate) const SUPPORTED_EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "gif", "webp"];
+const AVATAR_SIZE: u32 = 800;
+const AVATAR_THUMBNAIL_SIZE: u32 = 300;
+
+/// Get the photos directory for a specific entity type and ID
+fn get_photos_dir(app: &AppHandle, entity_type: &str, entity_id: i64) -> Result<PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+
+    let photos_dir = app_data
+        .join("photos")
+        .join(entity_type)
+        .join(entity_id.to_string());
+
+    // Create directories if they don't exist
+    fs::create_dir_all(&photos_dir).map_err(|e| e.to_string())?;
+
+    Ok(photos_dir)
+}
+
+/// Open an image with its EXIF orientation applied, so it is upright
+pub(crate) fn open_oriented(path: &Path) -> Result<DynamicImage, String> {
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to open image: {}", e))?;
+
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut img = DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to open image: {}", e))?;
+    img.apply_orientation(orientation);
+
+    Ok(img)
+}
+
+/// Save an image in the format of the path's extension. JPEG has no alpha
+/// channel, so transparent images are flattened first.
+pub(crate) fn write_image(img: &DynamicImage, path: &Path) -> Result<(), String> {
+    let is_jpeg = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"));
+
+    let result = if is_jpeg && img.color().has_alpha() {
+        DynamicImage::ImageRgb8(img.to_rgb8()).save(path)
+    } else {
+        img.save(path)
+    };
+
+    result.map_err(|e| format!("Failed to save image: {}", e))
+}
+
+/// Result of the image work of an import
+struct ImportedImage {
+    file_path: PathBuf,
+    original_path: Option<String>,
+    optimized: bool,
+    taken_at: Option<String>,
+    orientation: Option<i32>,
+    width: Option<i32>,
+    height: Option<i32>,
+    perceptual_hash: Option<String>,
+    thumbnails: Option<ThumbnailPaths>,
+}
+
+/// Image work of an import, run on the blocking pool. The copy is decoded
+/// once; the storage policy, orientation fix, dimensions, perceptual hash and
+/// thumbnails all use that image. Files that can't be decoded are kept as is,
+/// apart from the metadata the privacy setting strips.
+fn process_imported_photo(
+    dest_path: PathBuf,
+    settings: &PhotoSettings,
+    originals_dir: Option<&Path>,
+    cache_dir: &Path,
+) -> ImportedImage {
+    // Capture date and orientation from EXIF (empty when the file has none).
+    // Read before re-encoding, which doesn't carry EXIF over.
+    let exif = read_exif(&dest_path);
+
+    // Privacy policy: strip the copy before anything else so files that can't
+    // be decoded are covered too, as is the original kept when re-encoding
+    if settings.strip_metadata {
+        if let Err(e) = sanitize_photo_file(&dest_path) {
+            eprintln!("Failed to remove photo metadata: {}", e);
+        }
+    }
+
+    let mut imported = ImportedImage {
+        file_path: dest_path,
+        original_path: None,
+        optimized: false,
+        taken_at: exif.taken_at,
+        orientation: exif.orientation,
+        width: None,
+        height: None,
+        perceptual_hash: None,
+        thumbnails: None,
+    };
+
+    let mut image = match open_oriented(&imported.file_path) {
+        Ok(img) => img,
+        Err(e) => {
+            eprintln!("Failed to decode photo: {}", e);
+            return imported;
+        }
+    };
+
+    // Apply the storage policy; re-encoded photos are stored upright
+    if settings.optimize_storage {
+        match optimize_decoded_photo(&imported.file_path, &image, settings, originals_dir) {
+            Ok(Some(result)) => {
+                imported.file_path = result.path;
+                imported.original_path = result.original_path.map(|p| p.to_string_lossy().to_string());
+                imported.orientation = imported.orientation.map(|_| 1);
+                imported.optimized = true;
+                image = result.image;
+            }
+            Ok(None) => imported.optimized = true,
+            Err(e) => eprintln!("Failed to optimize photo: {}", e),
+        }
+    }
+
+    // Optionally store the copy upright so other programs show it the same way
+    let rotated = imported.orientation.is_some_and(|o| o != 1);
+    if rotated && settings.normalize_orientation {
+        match write_image(&image, &imported.file_path) {
+            Ok(()) => imported.orientation = Some(1),
+            Err(e) => eprintln!("Failed to normalize photo orientation: {}", e),
+        }
+    }
+
+    // Dimensions as displayed, i.e. after orientation
+    let (width, height) = image.dimensions();
+    imported.width = Some(width as i32);
+    imported.height = Some(height as i32);
+    imported.perceptual_hash = Some(perceptual_hash(&image));
+
+    match render_thumbnails(&image, &imported.file_path, cache_dir) {
+        Ok(paths) => imported.thumbnails = Some(paths),
+        Err(e) => eprintln!("Failed to generate thumbnails: {}", e),
+    }
+
+    imported
+}
+
+/// Crop rectangle clamped to the image as (x, y, width, height), at least 1x1
+pub(crate) fn clamp_crop(image_width: u32, image_height: u32, crop: &CropRect) -> (u32, u32, u32, u32) {
+    let x = crop.x.min(image_width.saturating_sub(1));
+    let y = crop.y.min(image_height.saturating_sub(1));
+    let width = crop.width.min(image_width.saturating_sub(x)).max(1);
+    let height = crop.height.min(image_height.saturating_sub(y)).max(1);
+    (x, y, width, height)
+}
+
+pub(crate) async fn fetch_photo(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Photo, String> {
+    let row = sqlx::query(&format!("SELECT {} FROM photos WHERE id = ?", PHOTO_COLUMNS))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(photo_from_row!(row))
+}
+
+/// Files of a photo row, as read by `remove_unused_photo_files`
+pub(crate) const PHOTO_FILE_COLUMNS: &str = "file_path, thumbnail_path, preview_path, original_path";
+
+/// Remove the files of a deleted photo row (selected with `PHOTO_FILE_COLUMNS`)
+/// unless another row still uses them (duplicates share one stored file)
+pub(crate) async fn remove_unused_photo_files(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    row: &sqlx::sqlite::SqliteRow,
+) -> Result<(), String> {
+    let file_path: String = row.get("file_path");
+    let in_use: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM photos WHERE file_path = ?")
+        .bind(&file_path)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !in_use {
+        remove_photo_files(&file_path, row.get::<Option<String>, _>("thumbnail_path").as_deref());
+        for column in ["preview_path", "original_path"] {
+            if let Some(path) = row.get::<Option<String>, _>(column) {
+                remove_photo_files(&path, None);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove a photo file and its thumbnail from disk (best effort).
+/// Missing files are ignored, other errors are only logged.
+pub(crate) fn remove_photo_files(file_path: &str, thumbnail_path: Option<&str>) {
+    if let Err(e) = fs::remove_file(file_path) {
+        // Only log if error is not "file not found" (already deleted)
+        if e.kind() != std::io::ErrorKind::NotFound {
+            eprintln!("Warning: Failed to delete photo file {}: {}", file_path, e);
+        }
+    }
+
+    if let Some(thumb) = thumbnail_path {
+        if let Err(e) = fs::remove_file(thumb) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("Warning: Failed to delete thumbnail {}: {}", thumb, e);
+            }
+        }
+    }
+}
+
+/// Thumbnail path of a profile photo saved by `save_athlete_profile_photo`
+pub(crate) fn profile_thumbnail_path(photo_path: &str) -> Option<String> {
+    let path = PathBuf::from(photo_path);
+    let stem = path.file_stem()?.to_str()?;
+    let extension = path.extension()?.to_str()?;
+    Some(path.with_file_name(format!("{}_thumb.{}", stem, extension)).to_string_lossy().to_string())
+}
+
+/// Result of importing one file
+pub(crate) enum SavedPhoto {
+    New(Photo),
+    /// Already imported; the existing photo was tagged with the entity
+    Linked(Photo),
+    /// Already imported as this photo and rejected
+    Duplicate(i64),
+}
+
+/// Check the duplicate handling option: "reject" (default), "link" (tag the
+/// existing photo with the entity), or "allow" a second copy
+pub(crate) fn validate_on_duplicate(on_duplicate: Option<String>) -> Result<String, String> {
+    let on_duplicate = on_duplicate.unwrap_or_else(|| "reject".to_string());
+    if !["reject", "link", "allow"].contains(&on_duplicate.as_str()) {
+        return Err("Invalid duplicate handling".to_string());
+    }
+    Ok(on_duplicate)
+}
+
+/// Save a photo from a source path
+#[tauri::command]
+pub async fn save_photo(
+    app: AppHandle,
+    source_path: String,
+    entity_type: String,
+    entity_id: i64,
+    event_name: Option<String>,
+    on_duplicate: Option<String>,
+) -> Result<Photo, String> {
+    let pool = get_pool(&app).await?;
+
+    // Validate entity type
+    if !["athletes", "results", "competitions"].contains(&entity_type.as_str()) {
+        return Err("Invalid entity type".to_string());
+    }
+    let on_duplicate = validate_on_duplicate(on_duplicate)?;
+
+    let saved = import_photo(
+        &app,
+        &pool,
+        Path::new(&source_path),
+        &entity_type,
+        entity_id,
+        event_name.as_deref(),
+        &on_duplicate,
+    )
+    .await?;
+
+    match saved {
+        SavedPhoto::New(photo) | SavedPhoto::Linked(photo) => Ok(photo),
+        SavedPhoto::Duplicate(id) => Err(format!("Duplicate photo: already imported as photo {}", id)),
+    }
+}
+
+/// Copy a source file into the library and store it for an entity. The
+/// entity type and duplicate handling must already be validated.
+pub(crate) async fn import_photo(
+    app: &AppHandle,
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    source: &Path,
+    entity_type: &str,
+    entity_id: i64,
+    event_name: Option<&str>,
+    on_duplicate: &str,
+) -> Result<SavedPhoto, String> {
+    // Get the source file info
+    let source = source.to_path_buf();
+    if !source.exists() {
+        return Err("Source file does not exist".to_string());
+    }
+
+    let original_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("photo")
+        .to_string();
+
+    let extension = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("jpg")
+        .to_lowercase();
+
+    // Validate file type
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err("Unsupported image format".to_string());
+    }
+
+    // Exact duplicates are found by the hash of the source bytes
+    let hash_source = source.clone();
+    let source_hash = tokio::task::spawn_blocking(move || content_hash(&hash_source))
+        .await
+        .map_err(|e| e.to_string())??;
+    let duplicate_id: Option<i64> = sqlx::query_scalar("SELECT id FROM photos WHERE content_hash = ? ORDER BY id LIMIT 1")
+        .bind(&source_hash)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(duplicate_id) = duplicate_id {
+        match on_duplicate {
+            "reject" => return Ok(SavedPhoto::Duplicate(duplicate_id)),
+            "link" => {
+                add_photo_tag(pool, duplicate_id, entity_type, entity_id, None).await?;
+                return Ok(SavedPhoto::Linked(fetch_photo(pool, duplicate_id).await?));
+            }
+            _ => {}
+        }
+    }
+
+    // Generate unique filename
+    let unique_id = Uuid::new_v4();
+    let new_filename = format!("{}.{}", unique_id, extension);
+    let dest_path = get_photos_dir(app, entity_type, entity_id)?.join(&new_filename);
+
+    let settings = load_photo_settings(pool).await?;
+    let originals_dir = if settings.optimize_storage && settings.keep_original {
+        Some(get_originals_dir(app)?)
+    } else {
+        None
+    };
+    let cache_dir = get_thumbnail_cache_dir(app)?;
+
+    // Copying and all image work run on the blocking pool
+    let imported = tokio::task::spawn_blocking(move || -> Result<ImportedImage, String> {
+        fs::copy(&source, &dest_path).map_err(|e| format!("Failed to copy file: {}", e))?;
+        Ok(process_imported_photo(dest_path, &settings, originals_dir.as_deref(), &cache_dir))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let (thumbnail_path, preview_path) = match imported.thumbnails {
+        Some(paths) => (Some(paths.grid), Some(paths.preview)),
+        None => (None, None),
+    };
+    let size_bytes = fs::metadata(&imported.file_path).map_err(|e| e.to_string())?.len() as i64;
+    let dest_path_str = imported.file_path.to_string_lossy().to_string();
+
+    // Save to database
+    let result = sqlx::query(
+        r#"INSERT INTO photos (entity_type, entity_id, file_path, thumbnail_path, preview_path, original_name, width, height, size_bytes, event_name,
+                              taken_at, orientation, original_path, optimized_at, content_hash, perceptual_hash)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CASE WHEN ? THEN datetime('now') END, ?, ?)"#
+    )
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(&dest_path_str)
+    .bind(&thumbnail_path)
+    .bind(&preview_path)
+    .bind(&original_name)
+    .bind(imported.width)
+    .bind(imported.height)
+    .bind(size_bytes)
+    .bind(event_name)
+    .bind(&imported.taken_at)
+    .bind(imported.orientation)
+    .bind(&imported.original_path)
+    .bind(imported.optimized)
+    .bind(&source_hash)
+    .bind(&imported.perceptual_hash)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let id = result.last_insert_rowid();
+    add_photo_tag(pool, id, entity_type, entity_id, None).await?;
+
+    Ok(SavedPhoto::New(fetch_photo(pool, id).await?))
+}
+
+/// Get photos tagged with an entity
+#[tauri::command]
+pub async fn get_photos(
+    app: AppHandle,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<Photo>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(
+        &format!(
+            r#"SELECT {} FROM photos
+               WHERE id IN (SELECT photo_id FROM photo_tags WHERE entity_type = ? AND entity_id = ?)
+               ORDER BY COALESCE(taken_at, created_at) DESC"#,
+            PHOTO_COLUMNS
+        )
+    )
+    .bind(&entity_type)
+    .bind(entity_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|row| photo_from_row!(row))
+        .collect())
+}
+
+/// Get the number of photos tagged with an entity
+#[tauri::command]
+pub async fn get_photo_count(
+    app: AppHandle,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<i32, String> {
+    let pool = get_pool(&app).await?;
+
+    let count: i32 = sqlx::query_scalar(
+        "SELECT COUNT(*) as count FROM photo_tags WHERE entity_type = ? AND entity_id = ?"
+    )
+    .bind(&entity_type)
+    .bind(entity_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(count)
+}
+
+/// Delete a photo
+#[tauri::command]
+pub async fn delete_photo(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    // Get the photo paths first
+    let row = sqlx::query(
+        &format!("SELECT {} FROM photos WHERE id = ?", PHOTO_FILE_COLUMNS)
+    )
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some(row) = row {
+        // Delete from database first (can be rolled back if needed)
+        sqlx::query("DELETE FROM photos WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Delete the files (best effort - file may already be deleted or inaccessible)
+        // We delete DB record first so UI stays consistent even if file cleanup fails
+        remove_unused_photo_files(&pool, &row).await?;
+
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Convert a file path to a Tauri asset URL
+#[tauri::command]
+pub fn get_photo_url(file_path: String) -> String {
+    // Convert to asset protocol URL for Tauri
+    format!("asset://localhost/{}", file_path.replace('\\', "/"))
+}
+
+/// Photo with related entity details
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoWithDetails {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub file_path: String,
+    pub thumbnail_path: Option<String>,
+    pub preview_path: Option<String>,
+    pub original_name: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub size_bytes: i64,
+    pub event_name: Option<String>,
+    pub taken_at: Option<String>,
+    pub orientation: Option<i32>,
+    pub created_at: String,
+    // Related entity info
+    pub athlete_name: Option<String>,
+    pub competition_name: Option<String>,
+    pub tags: Vec<PhotoTag>,
+}
+
+/// Get all photos with optional filters
+#[tauri::command]
+pub async fn get_all_photos(
+    app: AppHandle,
+    athlete_id: Option<i64>,
+    competition_id: Option<i64>,
+    year: Option<i32>,
+) -> Result<Vec<PhotoWithDetails>, String> {
+    let pool = get_pool(&app).await?;
+
+    // Filters go through the tags; an athlete also sees photos of the competitions
+    // they took part in. Unset filters are bound as NULL and match everything.
+    let rows = sqlx::query(
+        r#"SELECT p.id, p.entity_type, p.entity_id, p.file_path, p.thumbnail_path, p.preview_path,
+                  p.original_name, p.width, p.height, p.size_bytes, p.event_name, p.taken_at, p.orientation, p.created_at,
+                  (SELECT a.first_name || ' ' || a.last_name FROM photo_tags t JOIN athletes a ON a.id = t.entity_id
+                   WHERE t.photo_id = p.id AND t.entity_type = 'athletes' ORDER BY t.id LIMIT 1) as athlete_name,
+                  (SELECT c.name FROM photo_tags t JOIN competitions c ON c.id = t.entity_id
+                   WHERE t.photo_id = p.id AND t.entity_type = 'competitions' ORDER BY t.id LIMIT 1) as competition_name
+           FROM photos p
+           WHERE (?1 IS NULL OR EXISTS (
+                     SELECT 1 FROM photo_tags t WHERE t.photo_id = p.id AND (
+                         (t.entity_type = 'athletes' AND t.entity_id = ?1)
+                      OR (t.entity_type = 'results' AND t.entity_id IN (SELECT id FROM results WHERE athlete_id = ?1))
+                      OR (t.entity_type = 'competitions' AND t.entity_id IN
+                          (SELECT competition_id FROM competition_participants WHERE athlete_id = ?1)))))
+             AND (?2 IS NULL OR EXISTS (
+                     SELECT 1 FROM photo_tags t WHERE t.photo_id = p.id AND t.entity_type = 'competitions' AND t.entity_id = ?2))
+             AND (?3 IS NULL OR CAST(strftime('%Y', COALESCE(p.taken_at, p.created_at)) AS INTEGER) = ?3)
+           ORDER BY COALESCE(p.taken_at, p.created_at) DESC"#
+    )
+    .bind(athlete_id)
+    .bind(competition_id)
+    .bind(year)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let ids: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+    let mut tags = load_photo_tags(&pool, &ids).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PhotoWithDetails {
+            id: row.get("id"),
+            entity_type: row.get("entity_type"),
+            entity_id: row.get("entity_id"),
+            file_path: row.get("file_path"),
+            thumbnail_path: row.get("thumbnail_path"),
+            preview_path: row.get("preview_path"),
+            original_name: row.get("original_name"),
+            width: row.get("width"),
+            height: row.get("height"),
+            size_bytes: row.get("size_bytes"),
+            event_name: row.get("event_name"),
+            taken_at: row.get("taken_at"),
+            orientation: row.get("orientation"),
+            created_at: row.get("created_at"),
+            athlete_name: row.get("athlete_name"),
+            competition_name: row.get("competition_name"),
+            tags: tags.remove(&row.get::<i64, _>("id")).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Get distinct years that have photos, by capture date when known
+#[tauri::command]
+pub async fn get_photo_years(app: AppHandle) -> Result<Vec<i32>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows: Vec<i32> = sqlx::query_scalar(
+        "SELECT DISTINCT CAST(strftime('%Y', COALESCE(taken_at, created_at)) AS INTEGER) as year FROM photos ORDER BY year DESC"
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+/// Delete multiple photos at once
+#[tauri::command]
+pub async fn delete_photos_bulk(app: AppHandle, ids: Vec<i64>) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    for id in ids {
+        // Get the photo paths first
+        let row = sqlx::query(&format!("SELECT {} FROM photos WHERE id = ?", PHOTO_FILE_COLUMNS))
+            .bind(id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(row) = row {
+            // Delete from database first (can be rolled back if needed)
+            sqlx::query("DELETE FROM photos WHERE id = ?")
+                .bind(id)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            // Delete the files (best effort - file may already be deleted or inaccessible)
+            remove_unused_photo_files(&pool, &row).await?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Tag multiple photos with a competition, keeping their other tags
+#[tauri::command(rename_all = "camelCase")]
+pub async fn link_photos_to_competition(
+    app: AppHandle,
+    photo_ids: Vec<i64>,
+    competition_id: i64,
+) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    for photo_id in photo_ids {
+        add_photo_tag(&pool, photo_id, "competitions", competition_id, None).await?;
+    }
+
+    Ok(true)
+}
+
+/// Suggest competitions for photos based on their capture date: a competition
+/// is suggested when the photo was taken on one of its days. Photos already
+/// tagged with a competition and photos without a capture date are skipped.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn suggest_photo_competitions(
+    app: AppHandle,
+    photo_ids: Vec<i64>,
+) -> Result<Vec<PhotoCompetitionSuggestion>, String> {
+    let pool = get_pool(&app).await?;
+
+    let mut suggestions = Vec::new();
+    for photo_id in photo_ids {
+        let rows = sqlx::query(
+            r#"SELECT c.id, c.name, c.date, c.end_date, c.location, c.address, c.level, c.custom_level_name, c.notes,
+                      c.reminder_enabled, c.reminder_days_before, c.created_at
+               FROM photos p
+               JOIN competitions c
+                 ON date(p.taken_at) BETWEEN c.date AND COALESCE(c.end_date, c.date)
+               WHERE p.id = ? AND p.taken_at IS NOT NULL
+                 AND NOT EXISTS (SELECT 1 FROM photo_tags t WHERE t.photo_id = p.id AND t.entity_type = 'competitions')
+               ORDER BY c.date, c.name"#
+        )
+        .bind(photo_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        suggestions.extend(rows.iter().map(|row| PhotoCompetitionSuggestion {
+            photo_id,
+            competition: competition_from_row!(row),
+        }));
+    }
+
+    Ok(suggestions)
+}
+
+/// Store an edited version of a photo's image: the upright image is passed
+/// through `edit`, the file rewritten, the thumbnail regenerated and the size
+/// columns updated
+async fn replace_photo_image<F>(app: &AppHandle, pool: &sqlx::Pool<sqlx::Sqlite>, id: i64, edit: F) -> Result<Photo, String>
+where
+    F: FnOnce(DynamicImage) -> DynamicImage + Send + 'static,
+{
+    let row = sqlx::query("SELECT file_path, thumbnail_path FROM photos WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Photo {} not found", id))?;
+
+    let file_path = PathBuf::from(row.get::<String, _>("file_path"));
+    let cache_dir = get_thumbnail_cache_dir(app)?;
+
+    let old_thumbnail: Option<String> = row.get("thumbnail_path");
+
+    // Decoding, editing and encoding run on the blocking pool
+    let edit_path = file_path.clone();
+    let edit_cache_dir = cache_dir.clone();
+    let (thumbnail_path, preview_path, width, height, size_bytes, hash) =
+        tokio::task::spawn_blocking(move || -> Result<_, String> {
+            let img = edit(open_oriented(&edit_path)?);
+            write_image(&img, &edit_path)?;
+            let (thumbnail_path, preview_path) = match render_thumbnails(&img, &edit_path, &edit_cache_dir) {
+                Ok(paths) => (Some(paths.grid), Some(paths.preview)),
+                Err(e) => {
+                    eprintln!("Failed to generate thumbnails: {}", e);
+                    (None, None)
+                }
+            };
+            let size_bytes = fs::metadata(&edit_path).map_err(|e| e.to_string())?.len() as i64;
+            let (width, height) = img.dimensions();
+            Ok((thumbnail_path, preview_path, width, height, size_bytes, perceptual_hash(&img)))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+    // Linked duplicates share the file, so they all get the new version
+    sqlx::query(
+        r#"UPDATE photos SET thumbnail_path = ?, preview_path = ?, width = ?, height = ?, size_bytes = ?, orientation = 1, perceptual_hash = ?
+           WHERE file_path = ?"#
+    )
+    .bind(&thumbnail_path)
+    .bind(&preview_path)
+    .bind(width as i32)
+    .bind(height as i32)
+    .bind(size_bytes)
+    .bind(hash)
+    .bind(file_path.to_string_lossy().to_string())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Thumbnails from before the cache lived next to the photos
+    if let Some(old) = old_thumbnail.filter(|old| thumbnail_path.is_some() && !Path::new(old).starts_with(&cache_dir)) {
+        remove_photo_files(&old, None);
+    }
+
+    fetch_photo(pool, id).await
+}
+
+/// Rotate a photo clockwise by 90, 180 or 270 degrees
+#[tauri::command]
+pub async fn rotate_photo(app: AppHandle, id: i64, degrees: i32) -> Result<Photo, String> {
+    let pool = get_pool(&app).await?;
+
+    let rotate: fn(&DynamicImage) -> DynamicImage = match degrees.rem_euclid(360) {
+        90 => DynamicImage::rotate90,
+        180 => DynamicImage::rotate180,
+        270 => DynamicImage::rotate270,
+        _ => return Err("Rotation must be 90, 180 or 270 degrees".to_string()),
+    };
+
+    replace_photo_image(&app, &pool, id, move |img| rotate(&img)).await
+}
+
+/// Crop a photo to a rectangle given in pixels of the upright image
+#[tauri::command]
+pub async fn crop_photo(app: AppHandle, id: i64, crop: CropRect) -> Result<Photo, String> {
+    let pool = get_pool(&app).await?;
+
+    replace_photo_image(&app, &pool, id, move |img| {
+        let (width, height) = img.dimensions();
+        let (x, y, crop_width, crop_height) = clamp_crop(width, height, &crop);
+        img.crop_imm(x, y, crop_width, crop_height)
+    })
+    .await
+}
+
+/// Tag multiple photos with an athlete, keeping their other tags
+#[tauri::command(rename_all = "camelCase")]
+pub async fn link_photos_to_athlete(
+    app: AppHandle,
+    photo_ids: Vec<i64>,
+    athlete_id: i64,
+) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    for photo_id in photo_ids {
+        add_photo_tag(&pool, photo_id, "athletes", athlete_id, None).await?;
+    }
+
+    Ok(true)
+}
+
+/// Get the profile photos directory
+fn get_profile_photos_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+
+    let profile_photos_dir = app_data.join("profile_photos");
+    fs::create_dir_all(&profile_photos_dir).map_err(|e| e.to_string())?;
+
+    Ok(profile_photos_dir)
+}
+
+/// Square area (x, y, side) to use for an avatar. The crop rectangle is clamped
+/// to the image and the largest centered square inside it is taken; without a
+/// crop the centered square of the whole image is used.
+pub(crate) fn square_crop(image_width: u32, image_height: u32, crop: Option<&CropRect>) -> (u32, u32, u32) {
+    let (x, y, width, height) = match crop {
+        Some(c) => clamp_crop(image_width, image_height, c),
+        None => (0, 0, image_width, image_height),
+    };
+
+    let side = width.min(height).max(1);
+    (x + (width.saturating_sub(side)) / 2, y + (height.saturating_sub(side)) / 2, side)
+}
+
+/// Remove all profile photo files of an athlete, including ones left behind
+/// by earlier versions that never deleted replaced photos
+pub(crate) fn remove_profile_photos(app: &AppHandle, athlete_id: i64) -> Result<(), String> {
+    let dir = get_profile_photos_dir(app)?;
+    let prefix = format!("athlete_{}_", athlete_id);
+
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with(&prefix) {
+            remove_photo_files(&entry.path().to_string_lossy(), None);
+        }
+    }
+
+    Ok(())
+}
+
+/// Save an athlete profile photo (separate from the photos gallery).
+/// The image is cropped to a square avatar with a thumbnail, the athlete's
+/// `photo_path` is updated and the previous avatar files are deleted.
+/// Returns the file path where the photo was saved
+#[tauri::command]
+pub async fn save_athlete_profile_photo(
+    app: AppHandle,
+    source_path: String,
+    athlete_id: i64,
+    crop: Option<CropRect>,
+) -> Result<String, String> {
+    let pool = get_pool(&app).await?;
+
+    // Get the source file info
+    let source = PathBuf::from(&source_path);
+    if !source.exists() {
+        return Err("Source file does not exist".to_string());
+    }
+
+    let extension = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("jpg")
+        .to_lowercase();
+
+    // Validate file type
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err("Unsupported image format".to_string());
+    }
+
+    let old_photo_path: Option<String> = sqlx::query_scalar("SELECT photo_path FROM athletes WHERE id = ?")
+        .bind(athlete_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Athlete {} not found", athlete_id))?;
+
+    // PNG keeps transparency, everything else is stored as JPEG
+    let extension = if extension == "png" { "png" } else { "jpg" };
+
+    let profile_photos_dir = get_profile_photos_dir(&app)?;
+
+    // Generate unique filename based on athlete ID
+    let unique_id = Uuid::new_v4();
+    let dest_path = profile_photos_dir.join(format!("athlete_{}_{}.{}", athlete_id, unique_id, extension));
+    let thumbnail_path = profile_photos_dir.join(format!("athlete_{}_{}_thumb.{}", athlete_id, unique_id, extension));
+
+    // Decoding, resizing and encoding run on the blocking pool
+    let avatar_path = dest_path.clone();
+    let avatar_thumbnail_path = thumbnail_path.clone();
+    tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let img = open_oriented(&source)?;
+        let (width, height) = img.dimensions();
+        let (x, y, side) = square_crop(width, height, crop.as_ref());
+        let mut avatar = img.crop_imm(x, y, side, side);
+        if side > AVATAR_SIZE {
+            avatar = avatar.resize_exact(AVATAR_SIZE, AVATAR_SIZE, FilterType::Lanczos3);
+        }
+        let thumbnail = avatar.resize_exact(AVATAR_THUMBNAIL_SIZE, AVATAR_THUMBNAIL_SIZE, FilterType::Lanczos3);
+
+        write_image(&avatar, &avatar_path)?;
+        if let Err(e) = write_image(&thumbnail, &avatar_thumbnail_path) {
+            remove_photo_files(&avatar_path.to_string_lossy(), None);
+            return Err(e);
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let dest = dest_path.to_string_lossy().to_string();
+    let updated = sqlx::query("UPDATE athletes SET photo_path = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(&dest)
+        .bind(athlete_id)
+        .execute(&pool)
+        .await;
+    if let Err(e) = updated {
+        remove_photo_files(&dest, Some(&thumbnail_path.to_string_lossy()));
+        return Err(e.to_string());
+    }
+
+    // Only files we own are deleted; a path elsewhere is left untouched
+    if let Some(old) = old_photo_path.filter(|old| PathBuf::from(old).starts_with(&profile_photos_dir)) {
+        remove_photo_files(&old, profile_thumbnail_path(&old).as_deref());
+    }
+
+    Ok(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> CropRect {
+        CropRect { x, y, width, height }
+    }
+
+    #[test]
+    fn test_undecodable_photo_is_stripped() {
+        let dir = std::env::temp_dir().join(format!("loikka-gallery-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // Little-endian TIFF with only an Artist field, stored at 26
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        tiff.extend_from_slice(&0x013bu16.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&11u32.to_le_bytes());
+        tiff.extend_from_slice(&26u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(b"Coach Anna\0");
+
+        // JPEG header and EXIF followed by image data that doesn't decode
+        let exif = [b"Exif\0\0".as_slice(), &tiff].concat();
+        let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe1];
+        jpeg.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(&exif);
+        jpeg.extend_from_slice(&[0xff, 0xda, 0, 2, 1, 2, 3]);
+        let path = dir.join("broken.jpg");
+        fs::write(&path, &jpeg).unwrap();
+
+        let settings = PhotoSettings {
+            normalize_orientation: false,
+            optimize_storage: false,
+            max_long_edge: 2048,
+            jpeg_quality: 85,
+            keep_original: false,
+            strip_metadata: true,
+        };
+        let imported = process_imported_photo(path.clone(), &settings, None, &dir);
+        assert_eq!(imported.width, None);
+
+        let stored = fs::read(&path).unwrap();
+        assert!(!stored.windows(10).any(|w| w == b"Coach Anna"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_clamp_crop() {
+        assert_eq!(clamp_crop(1000, 800, &rect(100, 50, 300, 200)), (100, 50, 300, 200));
+        assert_eq!(clamp_crop(1000, 800, &rect(900, 700, 400, 400)), (900, 700, 100, 100));
+        assert_eq!(clamp_crop(1000, 800, &rect(2000, 2000, 10, 10)), (999, 799, 1, 1));
+        assert_eq!(clamp_crop(1000, 800, &rect(0, 0, 0, 0)), (0, 0, 1, 1));
+    }
+
+    #[test]
+    fn test_square_crop_without_rect() {
+        assert_eq!(square_crop(400, 300, None), (50, 0, 300));
+        assert_eq!(square_crop(300, 400, None), (0, 50, 300));
+        assert_eq!(square_crop(200, 200, None), (0, 0, 200));
+    }
+
+    #[test]
+    fn test_square_crop_with_rect() {
+        assert_eq!(square_crop(1000, 800, Some(&rect(100, 100, 400, 400))), (100, 100, 400));
+        // Non-square rect: centered square inside it
+        assert_eq!(square_crop(1000, 800, Some(&rect(100, 100, 400, 200))), (200, 100, 200));
+    }
+
+    #[test]
+    fn test_square_crop_clamps_to_image() {
+        assert_eq!(square_crop(1000, 800, Some(&rect(900, 700, 400, 400))), (900, 700, 100));
+        assert_eq!(square_crop(100, 100, Some(&rect(500, 500, 50, 50))), (99, 99, 1));
+    }
+}
@@ -0,0 +1,245 @@
+use super::exif::read_exif;
+use super::gallery::{import_photo, validate_on_duplicate, SavedPhoto, SUPPORTED_EXTENSIONS};
+use super::tags::add_photo_tag;
+use super::workers::spawn_bounded;
+use crate::database::get_pool;
+use crate::types::{PhotoFolderImport, PhotoFolderImportSummary, PhotoImportProgress};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted after each file of `import_photo_folder`
+pub const PHOTO_IMPORT_PROGRESS_EVENT: &str = "photo-import-progress";
+
+/// Files imported at the same time. Each one copies, decodes and encodes on
+/// the blocking pool, so a few are enough to keep the cores busy.
+const IMPORT_WORKERS: usize = 4;
+
+static IMPORT_RUNNING: AtomicBool = AtomicBool::new(false);
+static IMPORT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Marks an import as running and clears the mark however the import ends
+struct ImportGuard;
+
+impl ImportGuard {
+    fn start() -> Result<Self, String> {
+        if IMPORT_RUNNING.swap(true, Ordering::SeqCst) {
+            return Err("A photo import is already running".to_string());
+        }
+        IMPORT_CANCELLED.store(false, Ordering::SeqCst);
+        Ok(ImportGuard)
+    }
+}
+
+impl Drop for ImportGuard {
+    fn drop(&mut self) {
+        IMPORT_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Supported image files in a folder, sorted by path. Hidden files and
+/// folders are skipped.
+pub(crate) fn collect_import_files(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read folder {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Competitions held on the day a file was taken, by EXIF date
+async fn competitions_on_capture_date(pool: &sqlx::Pool<sqlx::Sqlite>, file: &Path) -> Result<Vec<i64>, String> {
+    let source = file.to_path_buf();
+    let taken_at = tokio::task::spawn_blocking(move || read_exif(&source).taken_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(taken_at) = taken_at else {
+        return Ok(Vec::new());
+    };
+
+    sqlx::query_scalar(
+        "SELECT id FROM competitions WHERE date(?) BETWEEN date AND COALESCE(end_date, date) ORDER BY date, name"
+    )
+    .bind(taken_at)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Import one file: to the target entity if there is one, otherwise to the
+/// first competition held on its capture date. Returns the status and photo id.
+async fn import_folder_file(
+    app: &AppHandle,
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    import: &PhotoFolderImport,
+    on_duplicate: &str,
+    file: &Path,
+) -> Result<(&'static str, Option<i64>), String> {
+    let competitions = if import.auto_link {
+        competitions_on_capture_date(pool, file).await?
+    } else {
+        Vec::new()
+    };
+
+    let (entity_type, entity_id) = match (&import.entity_type, import.entity_id) {
+        (Some(entity_type), Some(entity_id)) => (entity_type.as_str(), entity_id),
+        _ => match competitions.first() {
+            Some(&competition_id) => ("competitions", competition_id),
+            None => return Ok(("unmatched", None)),
+        },
+    };
+
+    let saved = import_photo(app, pool, file, entity_type, entity_id, import.event_name.as_deref(), on_duplicate).await?;
+    let (status, photo) = match saved {
+        SavedPhoto::New(photo) => ("imported", photo),
+        SavedPhoto::Linked(photo) => ("linked", photo),
+        SavedPhoto::Duplicate(id) => return Ok(("duplicate", Some(id))),
+    };
+
+    for competition_id in competitions {
+        add_photo_tag(pool, photo.id, "competitions", competition_id, None).await?;
+    }
+
+    Ok((status, Some(photo.id)))
+}
+
+/// Import all supported photos of a folder, optionally including subfolders.
+/// Files are imported concurrently and `photo-import-progress` is emitted for
+/// each one. `cancel_photo_import` stops the files that haven't started yet.
+#[tauri::command]
+pub async fn import_photo_folder(app: AppHandle, import: PhotoFolderImport) -> Result<PhotoFolderImportSummary, String> {
+    let pool = get_pool(&app).await?;
+
+    match (&import.entity_type, import.entity_id) {
+        (Some(entity_type), Some(_)) => {
+            if !["athletes", "results", "competitions"].contains(&entity_type.as_str()) {
+                return Err("Invalid entity type".to_string());
+            }
+        }
+        (None, None) if import.auto_link => {}
+        (None, None) => return Err("Choose where to import the photos or link them by date".to_string()),
+        _ => return Err("Entity type and id must be given together".to_string()),
+    }
+    let on_duplicate = validate_on_duplicate(import.on_duplicate.clone())?;
+
+    let folder = PathBuf::from(&import.folder_path);
+    if !folder.is_dir() {
+        return Err("Folder does not exist".to_string());
+    }
+    let files = collect_import_files(&folder, import.recursive)?;
+
+    let _guard = ImportGuard::start()?;
+    let total = files.len() as i64;
+    let import = Arc::new(import);
+    let on_duplicate = Arc::new(on_duplicate);
+
+    let mut tasks = spawn_bounded(files, IMPORT_WORKERS, |file| {
+        let (app, pool, import, on_duplicate) = (app.clone(), pool.clone(), import.clone(), on_duplicate.clone());
+        async move {
+            // Files still waiting for a worker are skipped after a cancel
+            if IMPORT_CANCELLED.load(Ordering::SeqCst) {
+                return None;
+            }
+            let result = import_folder_file(&app, &pool, &import, &on_duplicate, &file).await;
+            Some((file, result))
+        }
+    });
+
+    let mut summary = PhotoFolderImportSummary { total, ..Default::default() };
+    let mut done = 0;
+
+    while let Some(joined) = tasks.join_next().await {
+        let Some((file, result)) = joined.map_err(|e| e.to_string())? else {
+            continue;
+        };
+        done += 1;
+
+        let (status, photo_id, error) = match result {
+            Ok((status, photo_id)) => (status, photo_id, None),
+            Err(e) => ("failed", None, Some(e)),
+        };
+        match status {
+            "imported" => summary.imported += 1,
+            "linked" => summary.linked += 1,
+            "duplicate" => summary.duplicates += 1,
+            "unmatched" => summary.unmatched += 1,
+            _ => summary.failed += 1,
+        }
+        if let (Some(id), "imported" | "linked") = (photo_id, status) {
+            summary.photo_ids.push(id);
+        }
+
+        let progress = PhotoImportProgress {
+            file_path: file.to_string_lossy().to_string(),
+            status: status.to_string(),
+            photo_id,
+            error,
+            done,
+            total,
+        };
+        if let Err(e) = app.emit(PHOTO_IMPORT_PROGRESS_EVENT, progress) {
+            eprintln!("Failed to emit photo import progress: {}", e);
+        }
+    }
+
+    summary.cancelled = done < total;
+    Ok(summary)
+}
+
+/// Stop a running folder import. Files already being imported are finished.
+#[tauri::command]
+pub fn cancel_photo_import() -> bool {
+    if !IMPORT_RUNNING.load(Ordering::SeqCst) {
+        return false;
+    }
+    IMPORT_CANCELLED.store(true, Ordering::SeqCst);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_import_files() {
+        let dir = std::env::temp_dir().join(format!("loikka-import-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("day2")).unwrap();
+        fs::create_dir_all(dir.join(".cache")).unwrap();
+        for file in ["b.JPG", "a.png", "notes.txt", ".hidden.jpg", "day2/c.webp", ".cache/d.jpg"] {
+            fs::write(dir.join(file), b"x").unwrap();
+        }
+
+        let top = collect_import_files(&dir, false).unwrap();
+        assert_eq!(top, vec![dir.join("a.png"), dir.join("b.JPG")]);
+
+        let all = collect_import_files(&dir, true).unwrap();
+        assert_eq!(all, vec![dir.join("a.png"), dir.join("b.JPG"), dir.join("day2/c.webp")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,497 @@
+use super::dedup::content_hash;
+use super::gallery::{profile_thumbnail_path, remove_unused_photo_files, PHOTO_FILE_COLUMNS};
+use super::thumbnails::{get_thumbnail_cache_dir, render_thumbnails_for_file, ThumbnailPaths};
+use crate::database::get_pool;
+use crate::types::{BrokenThumbnail, MissingPhotoFile, OrphanedPhotoFile, PhotoStorageRepairSummary, PhotoStorageReport};
+use image::GenericImageView;
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// A gallery photo row as far as its files are concerned
+pub(crate) struct StoredPhoto {
+    pub id: i64,
+    pub file_path: String,
+    pub thumbnail_path: Option<String>,
+    pub preview_path: Option<String>,
+    pub original_path: Option<String>,
+    pub content_hash: Option<String>,
+}
+
+/// An athlete's profile photo link
+pub(crate) struct StoredProfilePhoto {
+    pub athlete_id: i64,
+    pub photo_path: String,
+}
+
+/// All files and directories below `dir`. Hidden files (.DS_Store and the
+/// like) are left alone.
+fn walk(dir: &Path, files: &mut Vec<PathBuf>, dirs: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            dirs.push(path.clone());
+            walk(&path, files, dirs);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn is_missing(path: &str) -> bool {
+    !Path::new(path).is_file()
+}
+
+/// Compare the files below `roots` with the database. Directories in `kept_dirs`
+/// are never reported as empty.
+pub(crate) fn build_storage_report(
+    roots: &[PathBuf],
+    kept_dirs: &[PathBuf],
+    photos: &[StoredPhoto],
+    profiles: &[StoredProfilePhoto],
+) -> PhotoStorageReport {
+    let mut referenced: HashSet<PathBuf> = HashSet::new();
+    for photo in photos {
+        referenced.insert(PathBuf::from(&photo.file_path));
+        referenced.extend(photo.thumbnail_path.iter().map(PathBuf::from));
+        referenced.extend(photo.preview_path.iter().map(PathBuf::from));
+        referenced.extend(photo.original_path.iter().map(PathBuf::from));
+    }
+    for profile in profiles {
+        referenced.insert(PathBuf::from(&profile.photo_path));
+        referenced.extend(profile_thumbnail_path(&profile.photo_path).map(PathBuf::from));
+    }
+
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    for root in roots {
+        walk(root, &mut files, &mut dirs);
+    }
+    files.sort();
+    dirs.sort();
+
+    let orphans: Vec<PathBuf> = files.into_iter().filter(|path| !referenced.contains(path)).collect();
+
+    let mut report = PhotoStorageReport::default();
+    for path in &orphans {
+        let size_bytes = fs::metadata(path).map(|m| m.len() as i64).unwrap_or(0);
+        report.orphaned_bytes += size_bytes;
+        report.orphaned_files.push(OrphanedPhotoFile {
+            path: path.to_string_lossy().to_string(),
+            size_bytes,
+        });
+    }
+
+    report.empty_directories = dirs
+        .into_iter()
+        .filter(|dir| !kept_dirs.contains(dir))
+        .filter(|dir| fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none()))
+        .map(|dir| dir.to_string_lossy().to_string())
+        .collect();
+
+    // Orphans are hashed only when a missing photo has a hash to look for
+    let mut orphan_hashes: Option<HashMap<String, PathBuf>> = None;
+
+    for photo in photos {
+        if is_missing(&photo.file_path) {
+            let relink = photo
+                .content_hash
+                .as_ref()
+                .and_then(|hash| {
+                    orphan_hashes
+                        .get_or_insert_with(|| {
+                            orphans
+                                .iter()
+                                .filter_map(|path| content_hash(path).ok().map(|hash| (hash, path.clone())))
+                                .collect()
+                        })
+                        .get(hash)
+                        .cloned()
+                })
+                .or_else(|| same_stem_orphan(&orphans, Path::new(&photo.file_path)))
+                .or_else(|| {
+                    photo
+                        .original_path
+                        .as_ref()
+                        .filter(|original| !is_missing(original))
+                        .map(PathBuf::from)
+                });
+
+            report.missing_files.push(MissingPhotoFile {
+                photo_id: Some(photo.id),
+                athlete_id: None,
+                file_path: photo.file_path.clone(),
+                relink_path: relink.map(|p| p.to_string_lossy().to_string()),
+            });
+        } else if photo.thumbnail_path.as_deref().is_none_or(is_missing)
+            || photo.preview_path.as_deref().is_none_or(is_missing)
+        {
+            report.broken_thumbnails.push(BrokenThumbnail {
+                photo_id: photo.id,
+                thumbnail_path: photo.thumbnail_path.clone(),
+            });
+        }
+    }
+
+    for profile in profiles {
+        if is_missing(&profile.photo_path) {
+            report.missing_files.push(MissingPhotoFile {
+                photo_id: None,
+                athlete_id: Some(profile.athlete_id),
+                file_path: profile.photo_path.clone(),
+                relink_path: latest_profile_orphan(&orphans, profile.athlete_id)
+                    .map(|p| p.to_string_lossy().to_string()),
+            });
+        }
+    }
+
+    report
+}
+
+/// An orphan next to a missing file with the same name but another extension,
+/// as left by a re-encode that never reached the database
+fn same_stem_orphan(orphans: &[PathBuf], missing: &Path) -> Option<PathBuf> {
+    orphans
+        .iter()
+        .find(|path| path.parent() == missing.parent() && path.file_stem() == missing.file_stem())
+        .cloned()
+}
+
+/// The newest avatar file of an athlete that no longer has a database link
+fn latest_profile_orphan(orphans: &[PathBuf], athlete_id: i64) -> Option<PathBuf> {
+    let prefix = format!("athlete_{}_", athlete_id);
+    orphans
+        .iter()
+        .filter(|path| {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            stem.starts_with(&prefix) && !stem.ends_with("_thumb")
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .cloned()
+}
+
+async fn scan(app: &AppHandle, pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<PhotoStorageReport, String> {
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let photos_dir = app_data.join("photos");
+    let roots = vec![photos_dir.clone(), app_data.join("profile_photos"), get_thumbnail_cache_dir(app)?];
+    let kept_dirs = vec![photos_dir.join("thumbnails"), photos_dir.join("originals")];
+
+    let photos: Vec<StoredPhoto> = sqlx::query(
+        "SELECT id, file_path, thumbnail_path, preview_path, original_path, content_hash FROM photos ORDER BY id"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(|row| StoredPhoto {
+        id: row.get("id"),
+        file_path: row.get("file_path"),
+        thumbnail_path: row.get("thumbnail_path"),
+        preview_path: row.get("preview_path"),
+        original_path: row.get("original_path"),
+        content_hash: row.get("content_hash"),
+    })
+    .collect();
+
+    let profiles: Vec<StoredProfilePhoto> = sqlx::query(
+        "SELECT id, photo_path FROM athletes WHERE photo_path IS NOT NULL ORDER BY id"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(|row| StoredProfilePhoto {
+        athlete_id: row.get("id"),
+        photo_path: row.get("photo_path"),
+    })
+    .collect();
+
+    tokio::task::spawn_blocking(move || build_storage_report(&roots, &kept_dirs, &photos, &profiles))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Compare the photo folders with the database: files nothing refers to,
+/// photos and profile photos whose file is gone and photos without a
+/// working thumbnail. Nothing is changed.
+#[tauri::command]
+pub async fn scan_photo_storage(app: AppHandle) -> Result<PhotoStorageReport, String> {
+    let pool = get_pool(&app).await?;
+
+    scan(&app, &pool).await
+}
+
+/// Repair the problems of a report the user confirmed (a `scan_photo_storage`
+/// result, possibly with items left out). Storage is scanned again and only
+/// problems that still exist unchanged are fixed: missing files are relinked
+/// to their replacement or their rows removed, thumbnails regenerated, and
+/// orphaned files and empty folders deleted.
+#[tauri::command]
+pub async fn repair_photo_storage(app: AppHandle, report: PhotoStorageReport) -> Result<PhotoStorageRepairSummary, String> {
+    let pool = get_pool(&app).await?;
+
+    let current = scan(&app, &pool).await?;
+    let mut summary = PhotoStorageRepairSummary::default();
+
+    // Files taken into use here must survive the orphan cleanup below
+    let mut kept: HashSet<String> = HashSet::new();
+
+    for missing in report.missing_files.iter().filter(|m| current.missing_files.contains(m)) {
+        let outcome = match (&missing.relink_path, missing.photo_id, missing.athlete_id) {
+            (Some(relink), Some(photo_id), _) => relink_photo(&pool, photo_id, relink).await,
+            (Some(relink), None, Some(athlete_id)) => {
+                sqlx::query("UPDATE athletes SET photo_path = ? WHERE id = ?")
+                    .bind(relink)
+                    .bind(athlete_id)
+                    .execute(&pool)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+            (None, Some(photo_id), _) => remove_photo_row(&pool, photo_id).await,
+            (None, None, Some(athlete_id)) => {
+                sqlx::query("UPDATE athletes SET photo_path = NULL WHERE id = ?")
+                    .bind(athlete_id)
+                    .execute(&pool)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+            _ => Ok(()),
+        };
+
+        match outcome {
+            Ok(()) => match &missing.relink_path {
+                Some(relink) => {
+                    kept.insert(relink.clone());
+                    if let Some(thumb) = profile_thumbnail_path(relink).filter(|_| missing.athlete_id.is_some()) {
+                        kept.insert(thumb);
+                    }
+                    summary.files_relinked += 1;
+                }
+                None => summary.rows_removed += 1,
+            },
+            Err(e) => {
+                eprintln!("Failed to repair missing photo file {}: {}", missing.file_path, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    let cache_dir = get_thumbnail_cache_dir(&app)?;
+    for broken in report.broken_thumbnails.iter().filter(|b| current.broken_thumbnails.contains(b)) {
+        match regenerate_thumbnail(&pool, &cache_dir, broken.photo_id).await {
+            Ok(paths) => {
+                kept.insert(paths.grid);
+                kept.insert(paths.preview);
+                summary.thumbnails_regenerated += 1;
+            }
+            Err(e) => {
+                eprintln!("Failed to regenerate thumbnail of photo {}: {}", broken.photo_id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    let mut touched_dirs: HashSet<PathBuf> = HashSet::new();
+    for orphan in report.orphaned_files.iter().filter(|o| current.orphaned_files.contains(o)) {
+        if kept.contains(&orphan.path) {
+            continue;
+        }
+
+        match fs::remove_file(&orphan.path) {
+            Ok(()) => {
+                summary.files_deleted += 1;
+                summary.bytes_freed += orphan.size_bytes;
+                touched_dirs.extend(Path::new(&orphan.path).parent().map(Path::to_path_buf));
+            }
+            Err(e) => {
+                eprintln!("Failed to delete orphaned photo file {}: {}", orphan.path, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    // Confirmed empty folders, and entity folders emptied above. remove_dir
+    // only removes empty directories, so anything still in use stays.
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let photos_dir = app_data.join("photos");
+    let kept_dirs = [photos_dir.clone(), photos_dir.join("thumbnails"), photos_dir.join("originals"), app_data.join("profile_photos")];
+
+    let mut dirs: Vec<PathBuf> = report
+        .empty_directories
+        .iter()
+        .filter(|d| current.empty_directories.contains(d))
+        .map(PathBuf::from)
+        .collect();
+    dirs.extend(touched_dirs.into_iter().filter(|d| d.starts_with(&photos_dir)));
+    // Deepest first so a parent can go once its children are gone
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.components().count()));
+    dirs.dedup();
+
+    for dir in dirs {
+        if !kept_dirs.contains(&dir) && fs::remove_dir(&dir).is_ok() {
+            summary.directories_removed += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Point a photo (and linked duplicates sharing its file) to a replacement file
+async fn relink_photo(pool: &sqlx::Pool<sqlx::Sqlite>, photo_id: i64, relink: &str) -> Result<(), String> {
+    let size_bytes = fs::metadata(relink).map_err(|e| e.to_string())?.len() as i64;
+    let dimensions = image::open(relink).ok().map(|img| img.dimensions());
+
+    sqlx::query(
+        r#"UPDATE photos SET file_path = ?, size_bytes = ?,
+               width = COALESCE(?, width), height = COALESCE(?, height),
+               original_path = CASE WHEN original_path = ? THEN NULL ELSE original_path END
+           WHERE file_path = (SELECT file_path FROM photos WHERE id = ?)"#
+    )
+    .bind(relink)
+    .bind(size_bytes)
+    .bind(dimensions.map(|(w, _)| w as i32))
+    .bind(dimensions.map(|(_, h)| h as i32))
+    .bind(relink)
+    .bind(photo_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Delete a photo row whose file is gone, with its leftover thumbnail
+async fn remove_photo_row(pool: &sqlx::Pool<sqlx::Sqlite>, photo_id: i64) -> Result<(), String> {
+    let row = sqlx::query(&format!("SELECT {} FROM photos WHERE id = ?", PHOTO_FILE_COLUMNS))
+        .bind(photo_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let Some(row) = row else {
+        return Ok(());
+    };
+
+    sqlx::query("DELETE FROM photos WHERE id = ?")
+        .bind(photo_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    remove_unused_photo_files(pool, &row).await
+}
+
+/// Render a photo's thumbnails again into the cache
+async fn regenerate_thumbnail(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    cache_dir: &Path,
+    photo_id: i64,
+) -> Result<ThumbnailPaths, String> {
+    let file_path: String = sqlx::query_scalar("SELECT file_path FROM photos WHERE id = ?")
+        .bind(photo_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (source, target) = (PathBuf::from(&file_path), cache_dir.to_path_buf());
+    let paths = tokio::task::spawn_blocking(move || render_thumbnails_for_file(&source, &target))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    sqlx::query("UPDATE photos SET thumbnail_path = ?, preview_path = ? WHERE file_path = ?")
+        .bind(&paths.grid)
+        .bind(&paths.preview)
+        .bind(&file_path)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(id: i64, file_path: &Path, thumbnail_path: Option<&Path>) -> StoredPhoto {
+        StoredPhoto {
+            id,
+            file_path: file_path.to_string_lossy().to_string(),
+            thumbnail_path: thumbnail_path.map(|p| p.to_string_lossy().to_string()),
+            preview_path: thumbnail_path.map(|p| p.to_string_lossy().to_string()),
+            original_path: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_build_storage_report() {
+        let root = std::env::temp_dir().join(format!("loikka-storage-{}", uuid::Uuid::new_v4()));
+        let photos = root.join("photos");
+        let thumbnails = photos.join("thumbnails");
+        let profiles = root.join("profile_photos");
+        fs::create_dir_all(photos.join("athletes").join("1")).unwrap();
+        fs::create_dir_all(photos.join("athletes").join("2")).unwrap(); // left by a purge
+        fs::create_dir_all(&thumbnails).unwrap();
+        fs::create_dir_all(&profiles).unwrap();
+
+        let ok = photos.join("athletes").join("1").join("a.jpg");
+        let ok_thumb = thumbnails.join("a_thumb.jpg");
+        let no_thumb = photos.join("athletes").join("1").join("b.jpg");
+        let reencoded = photos.join("athletes").join("1").join("c.jpg"); // row still says c.png
+        let stray = thumbnails.join("old_thumb.jpg");
+        let avatar = profiles.join("athlete_7_new.jpg"); // row points to a deleted avatar
+        for path in [&ok, &ok_thumb, &no_thumb, &reencoded, &stray, &avatar] {
+            fs::write(path, b"data").unwrap();
+        }
+        fs::write(photos.join(".DS_Store"), b"").unwrap();
+
+        let rows = vec![
+            stored(1, &ok, Some(&ok_thumb)),
+            stored(2, &no_thumb, Some(&thumbnails.join("b_thumb.jpg"))),
+            stored(3, &photos.join("athletes").join("1").join("c.png"), None),
+            stored(4, &photos.join("competitions").join("9").join("gone.jpg"), None),
+        ];
+        let profile_rows = vec![StoredProfilePhoto {
+            athlete_id: 7,
+            photo_path: profiles.join("athlete_7_old.jpg").to_string_lossy().to_string(),
+        }];
+
+        let report = build_storage_report(&[photos.clone(), profiles.clone()], std::slice::from_ref(&thumbnails), &rows, &profile_rows);
+
+        let orphans: Vec<&str> = report.orphaned_files.iter().map(|o| o.path.as_str()).collect();
+        assert_eq!(orphans.len(), 3);
+        for path in [&reencoded, &stray, &avatar] {
+            assert!(orphans.contains(&path.to_string_lossy().as_ref()));
+        }
+        assert_eq!(report.orphaned_bytes, 12);
+
+        assert_eq!(report.broken_thumbnails, vec![BrokenThumbnail {
+            photo_id: 2,
+            thumbnail_path: Some(thumbnails.join("b_thumb.jpg").to_string_lossy().to_string()),
+        }]);
+
+        assert_eq!(report.missing_files.len(), 3);
+        assert_eq!(report.missing_files[0].photo_id, Some(3));
+        assert_eq!(report.missing_files[0].relink_path.as_deref(), Some(reencoded.to_string_lossy().as_ref()));
+        assert_eq!(report.missing_files[1].photo_id, Some(4));
+        assert_eq!(report.missing_files[1].relink_path, None);
+        assert_eq!(report.missing_files[2].athlete_id, Some(7));
+        assert_eq!(report.missing_files[2].relink_path.as_deref(), Some(avatar.to_string_lossy().as_ref()));
+
+        assert_eq!(report.empty_directories, vec![photos.join("athletes").join("2").to_string_lossy().to_string()]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+// Re-export everything for generate_handler! macro to access __cmd__ items
+pub mod albums;
+pub(crate) mod archive;
+pub mod consent;
+pub mod dedup;
+pub(crate) mod exif;
+pub mod export;
+pub mod gallery;
+pub mod import;
+pub mod maintenance;
+pub mod privacy;
+pub mod storage;
+pub mod tags;
+pub mod thumbnails;
+pub(crate) mod workers;
+
+pub use albums::*;
+pub use consent::*;
+pub use dedup::*;
+pub use export::*;
+pub use gallery::*;
+pub use import::*;
+pub use maintenance::*;
+pub use privacy::*;
+pub use storage::*;
+pub use tags::*;
+pub use thumbnails::*;
@@ -0,0 +1,294 @@
+use super::exif::strip_exif_private;
+use crate::database::get_pool;
+use crate::types::PhotoSanitizeSummary;
+use sqlx::Row;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Remove location and identifying metadata from an image file's bytes:
+/// EXIF keeps only what isn't private (see `strip_exif_private`), and XMP,
+/// IPTC, comments and text chunks are dropped. Returns `None` when there was
+/// nothing to remove or the format isn't recognized.
+pub(crate) fn sanitize_image_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.starts_with(&[0xff, 0xd8]) {
+        sanitize_jpeg(bytes)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        sanitize_png(bytes)
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        sanitize_webp(bytes)
+    } else {
+        None
+    }
+}
+
+fn sanitize_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+    let mut changed = false;
+    let mut pos = 2;
+
+    // Metadata segments come before the image data (SOS); the rest is copied as is
+    while pos + 4 <= bytes.len() && bytes[pos] == 0xff && !matches!(bytes[pos + 1], 0xda | 0xd9) {
+        let marker = bytes[pos + 1];
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let Some(segment) = bytes.get(pos..pos + 2 + len) else {
+            break;
+        };
+        let data = &segment[4..];
+
+        let is_xmp = marker == 0xe1 && (data.starts_with(b"http://ns.adobe.com/xap/1.0/\0")
+            || data.starts_with(b"http://ns.adobe.com/xmp/extension/\0"));
+        // APP13 holds IPTC (names, places, captions), COM free text
+        if is_xmp || marker == 0xed || marker == 0xfe {
+            changed = true;
+        } else if marker == 0xe1 && data.starts_with(b"Exif\0\0") {
+            let start = out.len();
+            out.extend_from_slice(segment);
+            changed |= strip_exif_private(&mut out[start + 4..]);
+        } else {
+            out.extend_from_slice(segment);
+        }
+        pos += 2 + len;
+    }
+
+    if !changed {
+        return None;
+    }
+    out.extend_from_slice(&bytes[pos..]);
+    Some(out)
+}
+
+fn sanitize_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(PNG_SIGNATURE);
+    let mut changed = false;
+    let mut pos = PNG_SIGNATURE.len();
+
+    while pos + 12 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+        let chunk = bytes.get(pos..pos.checked_add(12 + len)?)?;
+        let kind = &chunk[4..8];
+        pos += chunk.len();
+
+        match kind {
+            // Text chunks carry authors, comments and XMP
+            b"tEXt" | b"zTXt" | b"iTXt" => changed = true,
+            b"eXIf" => {
+                let start = out.len();
+                out.extend_from_slice(chunk);
+                if strip_exif_private(&mut out[start + 8..start + 8 + len]) {
+                    let crc = crc32fast::hash(&out[start + 4..start + 8 + len]);
+                    out[start + 8 + len..].copy_from_slice(&crc.to_be_bytes());
+                    changed = true;
+                }
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+    }
+
+    if !changed {
+        return None;
+    }
+    out.extend_from_slice(&bytes[pos..]);
+    Some(out)
+}
+
+fn sanitize_webp(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = bytes[..12].to_vec();
+    let mut changed = false;
+    let mut pos = 12;
+
+    while pos + 8 <= bytes.len() {
+        let len = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let padded = len + len % 2;
+        let chunk = bytes.get(pos..(pos + 8 + padded).min(bytes.len()))?;
+        pos += 8 + padded;
+
+        match &chunk[..4] {
+            b"XMP " => changed = true,
+            b"EXIF" => {
+                let start = out.len();
+                out.extend_from_slice(chunk);
+                let end = (start + 8 + len).min(out.len());
+                changed |= strip_exif_private(&mut out[start + 8..end]);
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+    }
+
+    if !changed {
+        return None;
+    }
+
+    // The extended header announces XMP; it's gone now
+    if out.get(12..16) == Some(b"VP8X".as_slice()) && out.len() > 20 {
+        out[20] &= !0x04;
+    }
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+/// Sanitize a stored file in place. Returns the new size when it changed.
+pub(crate) fn sanitize_photo_file(path: &Path) -> Result<Option<u64>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read photo: {}", e))?;
+    let Some(sanitized) = sanitize_image_metadata(&bytes) else {
+        return Ok(None);
+    };
+
+    // Write next to the photo and swap, so a crash never leaves half a file
+    let tmp_path = path.with_extension("sanitize.tmp");
+    fs::write(&tmp_path, &sanitized).map_err(|e| format!("Failed to write photo: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace photo: {}", e))?;
+
+    Ok(Some(sanitized.len() as u64))
+}
+
+/// Remove location and identifying metadata from every stored photo and kept
+/// original, whatever the privacy setting. Rows sharing a file are updated
+/// together.
+#[tauri::command]
+pub async fn sanitize_photo_library(app: AppHandle) -> Result<PhotoSanitizeSummary, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query("SELECT DISTINCT file_path, original_path FROM photos ORDER BY file_path")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut summary = PhotoSanitizeSummary::default();
+
+    for row in rows {
+        let file_path: String = row.get("file_path");
+        let original_path: Option<String> = row.get("original_path");
+
+        let path = PathBuf::from(&file_path);
+        let original = original_path.map(PathBuf::from);
+        let outcome = tokio::task::spawn_blocking(move || -> Result<(Option<u64>, bool), String> {
+            let size = sanitize_photo_file(&path)?;
+            let original_changed = match original.filter(|p| p.exists()) {
+                Some(original) => sanitize_photo_file(&original)?.is_some(),
+                None => false,
+            };
+            Ok((size, original_changed))
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match outcome {
+            Ok((size, original_changed)) => {
+                if let Some(size) = size {
+                    sqlx::query("UPDATE photos SET size_bytes = ? WHERE file_path = ?")
+                        .bind(size as i64)
+                        .bind(&file_path)
+                        .execute(&pool)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+
+                summary.processed += 1;
+                if size.is_some() || original_changed {
+                    summary.sanitized += 1;
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to sanitize photo {}: {}", file_path, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use std::io::Cursor;
+
+    /// Little-endian TIFF with a camera serial number and an empty GPS IFD
+    fn private_exif() -> Vec<u8> {
+        let mut d = b"II*\0".to_vec();
+        d.extend_from_slice(&8u32.to_le_bytes());
+        d.extend_from_slice(&2u16.to_le_bytes());
+        // Artist "Coach Anna" stored at 38
+        d.extend_from_slice(&0x013bu16.to_le_bytes());
+        d.extend_from_slice(&2u16.to_le_bytes());
+        d.extend_from_slice(&11u32.to_le_bytes());
+        d.extend_from_slice(&38u32.to_le_bytes());
+        // GPS IFD at 49
+        d.extend_from_slice(&0x8825u16.to_le_bytes());
+        d.extend_from_slice(&4u16.to_le_bytes());
+        d.extend_from_slice(&1u32.to_le_bytes());
+        d.extend_from_slice(&49u32.to_le_bytes());
+        d.extend_from_slice(&0u32.to_le_bytes());
+        d.extend_from_slice(b"Coach Anna\0");
+        d.extend_from_slice(&0u16.to_le_bytes());
+        d.extend_from_slice(&0u32.to_le_bytes());
+        d
+    }
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(4, 4)).write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+        bytes
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn test_sanitize_jpeg() {
+        let plain = encode(ImageFormat::Jpeg);
+        assert_eq!(sanitize_image_metadata(&plain), None);
+
+        let mut jpeg = plain[..2].to_vec();
+        let exif = [b"Exif\0\0".as_slice(), &private_exif()].concat();
+        for (marker, data) in [(0xe1, exif.as_slice()), (0xe1, b"http://ns.adobe.com/xap/1.0/\0<x:xmpmeta/>"), (0xfe, b"Kalevan kisat")] {
+            jpeg.extend_from_slice(&[0xff, marker]);
+            jpeg.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+            jpeg.extend_from_slice(data);
+        }
+        jpeg.extend_from_slice(&plain[2..]);
+
+        let clean = sanitize_image_metadata(&jpeg).unwrap();
+        assert!(!contains(&clean, b"Coach Anna"));
+        assert!(!contains(&clean, b"xmpmeta"));
+        assert!(!contains(&clean, b"Kalevan"));
+        assert!(contains(&clean, b"Exif\0\0"));
+        assert!(image::load_from_memory(&clean).is_ok());
+        assert_eq!(sanitize_image_metadata(&clean), None);
+    }
+
+    #[test]
+    fn test_sanitize_png_keeps_valid_chunks() {
+        let plain = encode(ImageFormat::Png);
+        let ihdr_end = PNG_SIGNATURE.len() + 25;
+
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut c = (data.len() as u32).to_be_bytes().to_vec();
+            c.extend_from_slice(kind);
+            c.extend_from_slice(data);
+            c.extend_from_slice(&crc32fast::hash(&[kind, data].concat()).to_be_bytes());
+            c
+        };
+        let png = [
+            &plain[..ihdr_end],
+            &chunk(b"eXIf", &private_exif()),
+            &chunk(b"tEXt", b"Author\0Coach Anna"),
+            &plain[ihdr_end..],
+        ]
+        .concat();
+
+        let clean = sanitize_image_metadata(&png).unwrap();
+        assert!(!contains(&clean, b"Coach Anna"));
+        assert!(contains(&clean, b"eXIf"));
+        // Decoding checks the recomputed CRC
+        assert!(image::load_from_memory(&clean).is_ok());
+    }
+}
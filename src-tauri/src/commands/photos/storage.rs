@@ -0,0 +1,304 @@
+use super::gallery::open_oriented;
+use crate::commands::settings::load_photo_settings;
+use crate::database::get_pool;
+use crate::types::{PhotoOptimizationSummary, PhotoSettings};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use sqlx::Row;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// A photo file rewritten according to the storage policy
+#[derive(Debug)]
+pub(crate) struct OptimizedPhoto {
+    pub path: PathBuf,
+    pub original_path: Option<PathBuf>,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub width: u32,
+    pub height: u32,
+    pub image: DynamicImage, // Upright image as stored
+}
+
+/// Get the directory for originals kept by the storage policy
+pub(crate) fn get_originals_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+
+    let originals_dir = app_data.join("photos").join("originals");
+    fs::create_dir_all(&originals_dir).map_err(|e| e.to_string())?;
+
+    Ok(originals_dir)
+}
+
+/// Encode an upright image according to the policy. Returns the encoded bytes
+/// and the file extension to store them with.
+///
+/// PNG stays PNG (it may be transparent) and is only scaled down. Everything
+/// else becomes JPEG; the image crate can only write lossless WebP, which
+/// would grow lossy WebP photos.
+pub(crate) fn encode_optimized(
+    img: &DynamicImage,
+    extension: &str,
+    settings: &PhotoSettings,
+) -> Result<(Vec<u8>, &'static str, DynamicImage), String> {
+    let (width, height) = img.dimensions();
+    let img = if width.max(height) > settings.max_long_edge {
+        img.resize(settings.max_long_edge, settings.max_long_edge, FilterType::Lanczos3)
+    } else {
+        img.clone()
+    };
+
+    let mut bytes = Vec::new();
+    if extension == "png" {
+        img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+        return Ok((bytes, "png", img));
+    }
+
+    let quality = settings.jpeg_quality.clamp(1, 100);
+    JpegEncoder::new_with_quality(&mut bytes, quality)
+        .encode_image(&DynamicImage::ImageRgb8(img.to_rgb8()))
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    Ok((bytes, "jpg", img))
+}
+
+/// Rewrite a stored photo according to the storage policy. Returns `None` when
+/// re-encoding would not make the file smaller or the format can't be
+/// re-encoded (animated GIFs would lose their animation). With `originals_dir`
+/// the untouched file is moved there instead of being deleted.
+pub(crate) fn optimize_photo_file(
+    path: &Path,
+    settings: &PhotoSettings,
+    originals_dir: Option<&Path>,
+) -> Result<Option<OptimizedPhoto>, String> {
+    if is_gif(path) {
+        return Ok(None);
+    }
+
+    let img = open_oriented(path)?;
+    optimize_decoded_photo(path, &img, settings, originals_dir)
+}
+
+fn is_gif(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("gif"))
+}
+
+/// `optimize_photo_file` for a photo that is already decoded (upright)
+pub(crate) fn optimize_decoded_photo(
+    path: &Path,
+    img: &DynamicImage,
+    settings: &PhotoSettings,
+    originals_dir: Option<&Path>,
+) -> Result<Option<OptimizedPhoto>, String> {
+    if is_gif(path) {
+        return Ok(None);
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("jpg")
+        .to_lowercase();
+
+    let bytes_before = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    let (bytes, new_extension, encoded) = encode_optimized(img, &extension, settings)?;
+
+    if bytes.len() as u64 >= bytes_before {
+        return Ok(None);
+    }
+
+    let new_path = path.with_extension(new_extension);
+    let tmp_path = path.with_extension(format!("{}.tmp", new_extension));
+    fs::write(&tmp_path, &bytes).map_err(|e| format!("Failed to write photo: {}", e))?;
+
+    let original_path = match originals_dir {
+        Some(dir) => {
+            let file_name = path.file_name().ok_or("Invalid photo path")?;
+            let original = dir.join(file_name);
+            if fs::rename(path, &original).is_err() {
+                // Different file system: copy and remove instead
+                fs::copy(path, &original).map_err(|e| format!("Failed to keep original: {}", e))?;
+                fs::remove_file(path).map_err(|e| e.to_string())?;
+            }
+            Some(original)
+        }
+        None => {
+            if new_path != path {
+                fs::remove_file(path).map_err(|e| e.to_string())?;
+            }
+            None
+        }
+    };
+
+    fs::rename(&tmp_path, &new_path).map_err(|e| format!("Failed to write photo: {}", e))?;
+
+    let (width, height) = encoded.dimensions();
+    Ok(Some(OptimizedPhoto {
+        path: new_path,
+        original_path,
+        bytes_before,
+        bytes_after: bytes.len() as u64,
+        width,
+        height,
+        image: encoded,
+    }))
+}
+
+/// Apply the storage policy to photos that haven't been optimized yet.
+/// Runs the image work off the async runtime and reports the bytes saved.
+/// Rows sharing a file (linked duplicates) are updated together.
+#[tauri::command]
+pub async fn optimize_photo_library(app: AppHandle) -> Result<PhotoOptimizationSummary, String> {
+    let pool = get_pool(&app).await?;
+
+    let settings = load_photo_settings(&pool).await?;
+    let originals_dir = if settings.keep_original {
+        Some(get_originals_dir(&app)?)
+    } else {
+        None
+    };
+
+    let rows = sqlx::query("SELECT id, file_path FROM photos WHERE optimized_at IS NULL ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut summary = PhotoOptimizationSummary::default();
+
+    for row in rows {
+        let id: i64 = row.get("id");
+        let file_path: String = row.get("file_path");
+        let path = PathBuf::from(&file_path);
+
+        let task_settings = settings.clone();
+        let task_originals_dir = originals_dir.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            optimize_photo_file(&path, &task_settings, task_originals_dir.as_deref())
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match outcome {
+            Ok(Some(optimized)) => {
+                sqlx::query(
+                    r#"UPDATE photos SET file_path = ?, original_path = COALESCE(original_path, ?),
+                           size_bytes = ?, width = ?, height = ?, orientation = 1, optimized_at = datetime('now')
+                       WHERE file_path = ?"#
+                )
+                .bind(optimized.path.to_string_lossy().to_string())
+                .bind(optimized.original_path.map(|p| p.to_string_lossy().to_string()))
+                .bind(optimized.bytes_after as i64)
+                .bind(optimized.width as i32)
+                .bind(optimized.height as i32)
+                .bind(&file_path)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+
+                summary.processed += 1;
+                summary.bytes_before += optimized.bytes_before as i64;
+                summary.bytes_after += optimized.bytes_after as i64;
+            }
+            Ok(None) => {
+                // Nothing to gain; don't try again on the next run
+                sqlx::query("UPDATE photos SET optimized_at = datetime('now') WHERE file_path = ?")
+                    .bind(&file_path)
+                    .execute(&pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                summary.skipped += 1;
+            }
+            Err(e) => {
+                eprintln!("Failed to optimize photo {}: {}", id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    summary.bytes_saved = summary.bytes_before - summary.bytes_after;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn settings(max_long_edge: u32) -> PhotoSettings {
+        PhotoSettings {
+            normalize_orientation: false,
+            optimize_storage: true,
+            max_long_edge,
+            jpeg_quality: 80,
+            keep_original: false,
+            strip_metadata: false,
+        }
+    }
+
+    fn noisy_image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x * 7 % 256) as u8, (y * 13 % 256) as u8, ((x ^ y) % 256) as u8])
+        }))
+    }
+
+    #[test]
+    fn test_encode_optimized_scales_down_long_edge() {
+        let (bytes, extension, encoded) = encode_optimized(&noisy_image(800, 400), "jpg", &settings(400)).unwrap();
+        assert_eq!(extension, "jpg");
+        assert_eq!(encoded.dimensions(), (400, 200));
+
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(decoded.dimensions(), (400, 200));
+    }
+
+    #[test]
+    fn test_encode_optimized_keeps_small_images() {
+        let (_, _, encoded) = encode_optimized(&noisy_image(300, 500), "jpeg", &settings(2560)).unwrap();
+        assert_eq!(encoded.dimensions(), (300, 500));
+    }
+
+    #[test]
+    fn test_encode_optimized_formats() {
+        let transparent = DynamicImage::ImageRgba8(RgbaImage::from_pixel(50, 50, Rgba([0, 0, 0, 0])));
+        let (bytes, extension, _) = encode_optimized(&transparent, "png", &settings(2560)).unwrap();
+        assert_eq!(extension, "png");
+        assert!(image::load_from_memory(&bytes).unwrap().color().has_alpha());
+
+        // WebP is stored as JPEG
+        let (_, extension, _) = encode_optimized(&noisy_image(50, 50), "webp", &settings(2560)).unwrap();
+        assert_eq!(extension, "jpg");
+    }
+
+    #[test]
+    fn test_optimize_photo_file_replaces_and_keeps_original() {
+        let dir = std::env::temp_dir().join(format!("loikka-optimize-{}", uuid::Uuid::new_v4()));
+        let originals = dir.join("originals");
+        fs::create_dir_all(&originals).unwrap();
+
+        // A lossless PNG of a photo-like image shrinks a lot as JPEG
+        let path = dir.join("photo.webp");
+        noisy_image(600, 300).save_with_format(&path, ImageFormat::Png).unwrap();
+
+        let optimized = optimize_photo_file(&path, &settings(300), Some(&originals)).unwrap().unwrap();
+        assert_eq!(optimized.path, dir.join("photo.jpg"));
+        assert_eq!((optimized.width, optimized.height), (300, 150));
+        assert!(optimized.bytes_after < optimized.bytes_before);
+        assert!(optimized.path.exists());
+        assert!(!path.exists());
+        assert_eq!(optimized.original_path, Some(originals.join("photo.webp")));
+        assert!(originals.join("photo.webp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+use crate::database::get_pool;
+use crate::types::PhotoTag;
+use sqlx::Row;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// Tag columns with the tagged entity's display name
+const PHOTO_TAG_SELECT: &str = r#"SELECT t.id, t.photo_id, t.entity_type, t.entity_id, t.caption, t.created_at,
+       CASE t.entity_type
+           WHEN 'athletes' THEN (SELECT a.first_name || ' ' || a.last_name FROM athletes a WHERE a.id = t.entity_id)
+           WHEN 'competitions' THEN (SELECT c.name FROM competitions c WHERE c.id = t.entity_id)
+           WHEN 'results' THEN (SELECT a.first_name || ' ' || a.last_name FROM results r
+                                JOIN athletes a ON a.id = r.athlete_id WHERE r.id = t.entity_id)
+       END AS entity_name
+FROM photo_tags t"#;
+
+fn photo_tag_from_row(row: &sqlx::sqlite::SqliteRow) -> PhotoTag {
+    PhotoTag {
+        id: row.get("id"),
+        photo_id: row.get("photo_id"),
+        entity_type: row.get("entity_type"),
+        entity_id: row.get("entity_id"),
+        caption: row.get("caption"),
+        entity_name: row.get("entity_name"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn validate_entity_type(entity_type: &str) -> Result<(), String> {
+    if ["athletes", "competitions", "results"].contains(&entity_type) {
+        Ok(())
+    } else {
+        Err("Invalid entity type".to_string())
+    }
+}
+
+/// Tag a photo with an entity. An existing tag is kept; a given caption
+/// replaces its caption.
+pub(crate) async fn add_photo_tag(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    photo_id: i64,
+    entity_type: &str,
+    entity_id: i64,
+    caption: Option<&str>,
+) -> Result<(), String> {
+    validate_entity_type(entity_type)?;
+
+    sqlx::query(
+        r#"INSERT INTO photo_tags (photo_id, entity_type, entity_id, caption) VALUES (?, ?, ?, ?)
+           ON CONFLICT (photo_id, entity_type, entity_id) DO UPDATE SET caption = COALESCE(excluded.caption, caption)"#
+    )
+    .bind(photo_id)
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(caption)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Remove all tags pointing to a deleted entity
+pub(crate) async fn remove_entity_tags(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    entity_type: &str,
+    entity_id: i64,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM photo_tags WHERE entity_type = ? AND entity_id = ?")
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Tags of the given photos, grouped by photo id
+pub(crate) async fn load_photo_tags(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    photo_ids: &[i64],
+) -> Result<HashMap<i64, Vec<PhotoTag>>, String> {
+    let ids = serde_json::to_string(photo_ids).map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE t.photo_id IN (SELECT value FROM json_each(?)) ORDER BY t.id",
+        PHOTO_TAG_SELECT
+    ))
+    .bind(ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut tags: HashMap<i64, Vec<PhotoTag>> = HashMap::new();
+    for row in &rows {
+        let tag = photo_tag_from_row(row);
+        tags.entry(tag.photo_id).or_default().push(tag);
+    }
+
+    Ok(tags)
+}
+
+async fn fetch_photo_tag(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    photo_id: i64,
+    entity_type: &str,
+    entity_id: i64,
+) -> Result<PhotoTag, String> {
+    let row = sqlx::query(&format!(
+        "{} WHERE t.photo_id = ? AND t.entity_type = ? AND t.entity_id = ?",
+        PHOTO_TAG_SELECT
+    ))
+    .bind(photo_id)
+    .bind(entity_type)
+    .bind(entity_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(photo_tag_from_row(&row))
+}
+
+/// Get the athletes, competitions and results tagged on a photo
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_photo_tags(app: AppHandle, photo_id: i64) -> Result<Vec<PhotoTag>, String> {
+    let pool = get_pool(&app).await?;
+
+    let mut tags = load_photo_tags(&pool, &[photo_id]).await?;
+    Ok(tags.remove(&photo_id).unwrap_or_default())
+}
+
+/// Tag a photo with an athlete, competition or result
+#[tauri::command(rename_all = "camelCase")]
+pub async fn tag_photo(
+    app: AppHandle,
+    photo_id: i64,
+    entity_type: String,
+    entity_id: i64,
+    caption: Option<String>,
+) -> Result<PhotoTag, String> {
+    let pool = get_pool(&app).await?;
+
+    add_photo_tag(&pool, photo_id, &entity_type, entity_id, caption.as_deref()).await?;
+    fetch_photo_tag(&pool, photo_id, &entity_type, entity_id).await
+}
+
+/// Remove a tag from a photo. The photo itself is kept.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn untag_photo(
+    app: AppHandle,
+    photo_id: i64,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM photo_tags WHERE photo_id = ? AND entity_type = ? AND entity_id = ?")
+        .bind(photo_id)
+        .bind(&entity_type)
+        .bind(entity_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Set or clear the caption of a tag
+#[tauri::command]
+pub async fn update_photo_tag_caption(app: AppHandle, id: i64, caption: Option<String>) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("UPDATE photo_tags SET caption = ? WHERE id = ?")
+        .bind(caption.filter(|c| !c.trim().is_empty()))
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
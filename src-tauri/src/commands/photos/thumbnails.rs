@@ -0,0 +1,218 @@
+use super::gallery::{open_oriented, remove_photo_files};
+use super::workers::spawn_bounded;
+use crate::database::get_pool;
+use crate::types::{ThumbnailProgress, ThumbnailRegenerationSummary};
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use sqlx::Row;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Long edge of the thumbnails shown in photo grids
+pub const GRID_SIZE: u32 = 320;
+/// Long edge of the image shown in the photo viewer
+pub const PREVIEW_SIZE: u32 = 1280;
+
+/// Event emitted while `regenerate_thumbnails` runs
+pub const THUMBNAIL_PROGRESS_EVENT: &str = "photo-thumbnails-progress";
+
+/// Grid and preview images of one stored photo
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ThumbnailPaths {
+    pub grid: String,
+    pub preview: String,
+}
+
+/// Get the thumbnail cache directory. Everything in it can be rebuilt from
+/// the photos with `regenerate_thumbnails`, so it isn't synced or backed up.
+pub(crate) fn get_thumbnail_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("thumbnails");
+    fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+
+    Ok(cache_dir)
+}
+
+/// Cache paths for a stored photo, named after the photo file so rows sharing
+/// one file share the thumbnails too
+pub(crate) fn thumbnail_paths(cache_dir: &Path, file_path: &Path) -> ThumbnailPaths {
+    let stem = file_path.file_stem().and_then(|s| s.to_str()).unwrap_or("photo");
+    ThumbnailPaths {
+        grid: cache_dir.join(format!("{}_grid.webp", stem)).to_string_lossy().to_string(),
+        preview: cache_dir.join(format!("{}_preview.webp", stem)).to_string_lossy().to_string(),
+    }
+}
+
+/// Scale down so the long edge is at most `size`; smaller images are kept
+fn fit(img: &DynamicImage, size: u32) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    if width.max(height) <= size {
+        img.clone()
+    } else {
+        img.resize(size, size, FilterType::Triangle)
+    }
+}
+
+/// Write an image as WebP. The image crate only encodes lossless WebP, which
+/// is still small at these sizes.
+fn write_webp(img: &DynamicImage, path: &Path) -> Result<(), String> {
+    let img = if img.color().has_alpha() {
+        DynamicImage::ImageRgba8(img.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+    };
+
+    let mut bytes = Vec::new();
+    img.write_with_encoder(WebPEncoder::new_lossless(&mut bytes))
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    fs::write(path, bytes).map_err(|e| format!("Failed to save thumbnail: {}", e))
+}
+
+/// Render the grid and preview images of an upright photo. The grid image is
+/// scaled from the preview, so the full image is only resampled once.
+pub(crate) fn render_thumbnails(img: &DynamicImage, file_path: &Path, cache_dir: &Path) -> Result<ThumbnailPaths, String> {
+    let paths = thumbnail_paths(cache_dir, file_path);
+
+    let preview = fit(img, PREVIEW_SIZE);
+    write_webp(&preview, Path::new(&paths.preview))?;
+    write_webp(&fit(&preview, GRID_SIZE), Path::new(&paths.grid))?;
+
+    Ok(paths)
+}
+
+/// Decode a stored photo and render its thumbnails
+pub(crate) fn render_thumbnails_for_file(file_path: &Path, cache_dir: &Path) -> Result<ThumbnailPaths, String> {
+    let img = open_oriented(file_path)?;
+    render_thumbnails(&img, file_path, cache_dir)
+}
+
+/// Rebuild the thumbnails of every photo, for example after a restore or when
+/// the cache was cleared. Photos are decoded in parallel on the blocking pool
+/// and `photo-thumbnails-progress` is emitted after each one. Thumbnails of
+/// older versions and cache files of deleted photos are removed.
+#[tauri::command]
+pub async fn regenerate_thumbnails(app: AppHandle) -> Result<ThumbnailRegenerationSummary, String> {
+    let pool = get_pool(&app).await?;
+    let cache_dir = get_thumbnail_cache_dir(&app)?;
+
+    // Rows sharing a file (linked duplicates) are rendered once
+    let rows = sqlx::query("SELECT file_path, MIN(thumbnail_path) as thumbnail_path FROM photos GROUP BY file_path ORDER BY MIN(id)")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let total = rows.len() as i64;
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2);
+
+    let mut tasks = spawn_bounded(&rows, workers, |row| {
+        let file_path: String = row.get("file_path");
+        let old_thumbnail: Option<String> = row.get("thumbnail_path");
+        let cache_dir = cache_dir.clone();
+        // Rendering starts only once the task holds a worker permit
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let result = render_thumbnails_for_file(Path::new(&file_path), &cache_dir);
+                (file_path, old_thumbnail, result)
+            })
+            .await
+        }
+    });
+
+    let mut summary = ThumbnailRegenerationSummary { total, ..Default::default() };
+    let mut current: HashSet<PathBuf> = HashSet::new();
+
+    while let Some(joined) = tasks.join_next().await {
+        let (file_path, old_thumbnail, result) = joined.and_then(|rendered| rendered).map_err(|e| e.to_string())?;
+
+        match result {
+            Ok(paths) => {
+                sqlx::query("UPDATE photos SET thumbnail_path = ?, preview_path = ? WHERE file_path = ?")
+                    .bind(&paths.grid)
+                    .bind(&paths.preview)
+                    .bind(&file_path)
+                    .execute(&pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                // Thumbnails from before the cache lived next to the photos
+                if let Some(old) = old_thumbnail.filter(|old| !Path::new(old).starts_with(&cache_dir)) {
+                    remove_photo_files(&old, None);
+                }
+
+                current.insert(PathBuf::from(paths.grid));
+                current.insert(PathBuf::from(paths.preview));
+                summary.generated += 1;
+            }
+            Err(e) => {
+                eprintln!("Failed to generate thumbnails for {}: {}", file_path, e);
+                summary.failed += 1;
+            }
+        }
+
+        let progress = ThumbnailProgress {
+            done: summary.generated + summary.failed,
+            total,
+            failed: summary.failed,
+        };
+        if let Err(e) = app.emit(THUMBNAIL_PROGRESS_EVENT, progress) {
+            eprintln!("Failed to emit thumbnail progress: {}", e);
+        }
+    }
+
+    // Drop cache files no photo uses any more, unless a render failed and the
+    // old files are all that is left
+    let referenced: HashSet<PathBuf> = sqlx::query("SELECT thumbnail_path, preview_path FROM photos")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .flat_map(|row| [row.get::<Option<String>, _>("thumbnail_path"), row.get::<Option<String>, _>("preview_path")])
+        .flatten()
+        .map(PathBuf::from)
+        .collect();
+
+    for entry in fs::read_dir(&cache_dir).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if !current.contains(&path) && !referenced.contains(&path) {
+            remove_photo_files(&path.to_string_lossy(), None);
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_render_thumbnails_sizes() {
+        let cache_dir = std::env::temp_dir().join(format!("loikka-thumbs-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&cache_dir).unwrap();
+
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(2000, 1000, Rgb([200, 100, 50])));
+        let paths = render_thumbnails(&img, Path::new("/photos/athletes/1/abc.jpg"), &cache_dir).unwrap();
+        assert_eq!(paths, thumbnail_paths(&cache_dir, Path::new("/photos/athletes/1/abc.jpg")));
+        assert!(paths.grid.ends_with("abc_grid.webp"));
+
+        let preview = image::open(&paths.preview).unwrap();
+        assert_eq!(preview.dimensions(), (PREVIEW_SIZE, PREVIEW_SIZE / 2));
+        let grid = image::open(&paths.grid).unwrap();
+        assert_eq!(grid.dimensions(), (GRID_SIZE, GRID_SIZE / 2));
+
+        // Small images are not scaled up
+        let small = DynamicImage::ImageRgb8(RgbImage::new(200, 100));
+        let paths = render_thumbnails(&small, Path::new("small.png"), &cache_dir).unwrap();
+        assert_eq!(image::open(&paths.preview).unwrap().dimensions(), (200, 100));
+        assert_eq!(image::open(&paths.grid).unwrap().dimensions(), (200, 100));
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Spawn `job` for every item with at most `workers` of them running at once.
+///
+/// Every task is spawned up front and waits for a permit before it starts, so
+/// the caller can handle each outcome with `join_next` as soon as it finishes
+/// instead of after the last item has been queued.
+pub(crate) fn spawn_bounded<T, F, Fut>(items: impl IntoIterator<Item = T>, workers: usize, job: F) -> JoinSet<Fut::Output>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(workers.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for item in items {
+        let semaphore = semaphore.clone();
+        let work = job(item);
+        tasks.spawn(async move {
+            // The semaphore is never closed, so acquiring only waits
+            let _permit = semaphore.acquire().await;
+            work.await
+        });
+    }
+
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_spawn_bounded_limits_running_jobs() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = spawn_bounded(0..20, 3, |i| {
+            let (running, peak) = (running.clone(), peak.clone());
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        });
+
+        // Results arrive while later jobs are still waiting
+        let first = tasks.join_next().await.unwrap().unwrap();
+        assert!(first < 20);
+        assert!(!tasks.is_empty());
+
+        let mut done = 1;
+        while let Some(joined) = tasks.join_next().await {
+            joined.unwrap();
+            done += 1;
+        }
+        assert_eq!(done, 20);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+}
@@ -0,0 +1,593 @@
+use crate::commands::goals::evaluation::{pb_candidates, personal_best_results, season_best_results};
+use crate::commands::results::medals::{medal_from_row, MEDAL_COLUMNS};
+use crate::database::get_pool;
+use crate::types::{
+    CompetitionReport, CompetitionReportAthlete, CompetitionReportDiscipline,
+    CompetitionReportResult, Medal,
+};
+use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use tauri::AppHandle;
+
+/// Matches results that belong to competition `c` (same name, dated within the competition days)
+const COMPETITION_RESULTS_FILTER: &str = r#"
+    r.type = 'competition'
+    AND LOWER(TRIM(r.competition_name)) = LOWER(TRIM(c.name))
+    AND r.date BETWEEN c.date AND COALESCE(c.end_date, c.date)
+"#;
+
+/// Build a post-competition report: results, PB/SB, medals and planned events without a result
+pub async fn build_competition_report(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    competition_id: i64,
+) -> Result<CompetitionReport, String> {
+    let row = sqlx::query(
+        r#"SELECT id, name, date, end_date, location, address, level, custom_level_name, notes, reminder_enabled, reminder_days_before, created_at
+        FROM competitions WHERE id = ?"#
+    )
+    .bind(competition_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Competition {} not found", competition_id))?;
+
+    let competition = competition_from_row!(row);
+
+    // Discipline lookup for both results and planned events
+    let disciplines: HashMap<i64, CompetitionReportDiscipline> = sqlx::query(
+        "SELECT id, name, full_name, category, unit FROM disciplines"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(|row| {
+        let discipline = CompetitionReportDiscipline {
+            id: row.get("id"),
+            name: row.get("name"),
+            full_name: row.get("full_name"),
+            category: row.get("category"),
+            unit: row.get("unit"),
+        };
+        (discipline.id, discipline)
+    })
+    .collect();
+
+    // Athletes that either were registered as participants or have a result in this competition
+    let athlete_query = format!(
+        r#"SELECT a.id, a.first_name, a.last_name FROM athletes a
+        WHERE a.id IN (SELECT athlete_id FROM competition_participants WHERE competition_id = ?)
+           OR a.id IN (SELECT r.athlete_id FROM results r, competitions c WHERE c.id = ? AND {})
+        ORDER BY a.last_name, a.first_name"#,
+        COMPETITION_RESULTS_FILTER
+    );
+    let athlete_rows = sqlx::query(&athlete_query)
+        .bind(competition_id)
+        .bind(competition_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result_query = format!(
+        r#"SELECT r.id, r.athlete_id, r.discipline_id, r.date, r.value, r.type, r.competition_name, r.competition_level, r.custom_level_name, r.location, r.placement, r.notes, r.is_personal_best, r.is_season_best, r.is_national_record, r.wind, r.status, r.equipment_weight, r.hurdle_height, r.hurdle_spacing, r.sub_results, r.combined_event_id, r.heat, r.created_at
+        FROM results r, competitions c
+        WHERE c.id = ? AND {}
+        ORDER BY r.date, r.discipline_id"#,
+        COMPETITION_RESULTS_FILTER
+    );
+    let result_rows = sqlx::query(&result_query)
+        .bind(competition_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let medal_query = format!(
+        r#"SELECT {} FROM medals
+           WHERE competition_id = ?
+              OR (competition_id IS NULL
+                  AND LOWER(TRIM(competition_name)) = LOWER(TRIM(?))
+                  AND date BETWEEN ? AND COALESCE(?, ?))
+           ORDER BY CASE type WHEN 'gold' THEN 1 WHEN 'silver' THEN 2 ELSE 3 END"#,
+        MEDAL_COLUMNS
+    );
+    let medals: Vec<Medal> = sqlx::query(&medal_query)
+        .bind(competition_id)
+        .bind(&competition.name)
+        .bind(&competition.date)
+        .bind(&competition.end_date)
+        .bind(&competition.date)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(medal_from_row)
+        .collect();
+
+    let participant_rows = sqlx::query(
+        "SELECT athlete_id, disciplines_planned FROM competition_participants WHERE competition_id = ?"
+    )
+    .bind(competition_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let planned: HashMap<i64, Vec<i64>> = participant_rows
+        .iter()
+        .map(|row| {
+            let disciplines_json: Option<String> = row.get("disciplines_planned");
+            let planned: Vec<i64> = disciplines_json
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            (row.get("athlete_id"), planned)
+        })
+        .collect();
+
+    let mut athletes = Vec::with_capacity(athlete_rows.len());
+    for row in &athlete_rows {
+        let athlete_id: i64 = row.get("id");
+
+        // The stored flags only mark the current bests, so PB and SB are
+        // worked out from what came before each result
+        let candidates = pb_candidates(pool, athlete_id, None).await?;
+        let personal_bests: HashSet<i64> = personal_best_results(&candidates).into_iter().map(|(id, _)| id).collect();
+        let season_bests: HashSet<i64> = season_best_results(&candidates).into_iter().map(|(id, _)| id).collect();
+
+        let results: Vec<CompetitionReportResult> = result_rows
+            .iter()
+            .filter(|r| r.get::<i64, _>("athlete_id") == athlete_id)
+            .filter_map(|r| {
+                let mut result = result_from_row!(r);
+                result.is_personal_best = personal_bests.contains(&result.id);
+                result.is_season_best = season_bests.contains(&result.id);
+                disciplines.get(&result.discipline_id).map(|d| CompetitionReportResult {
+                    discipline: d.clone(),
+                    result,
+                })
+            })
+            .collect();
+
+        let athlete_medals: Vec<Medal> = medals.iter().filter(|m| m.athlete_id == athlete_id).cloned().collect();
+
+        let planned_without_result = planned
+            .get(&athlete_id)
+            .map(|ids| {
+                ids.iter()
+                    .filter(|id| !results.iter().any(|r| r.result.discipline_id == **id))
+                    .filter_map(|id| disciplines.get(id).cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        athletes.push(CompetitionReportAthlete {
+            athlete_id,
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            personal_best_count: results.iter().filter(|r| r.result.is_personal_best).count() as i32,
+            season_best_count: results.iter().filter(|r| r.result.is_season_best).count() as i32,
+            results,
+            medals: athlete_medals,
+            planned_without_result,
+        });
+    }
+
+    Ok(CompetitionReport {
+        competition,
+        athletes,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_competition_report(app: AppHandle, competition_id: i64) -> Result<CompetitionReport, String> {
+    let pool = get_pool(&app).await?;
+    build_competition_report(&pool, competition_id).await
+}
+
+/// Render the competition report as shareable text. `format` is "markdown" or "html".
+#[tauri::command]
+pub async fn export_competition_report(
+    app: AppHandle,
+    competition_id: i64,
+    format: String,
+) -> Result<String, String> {
+    let pool = get_pool(&app).await?;
+    let report = build_competition_report(&pool, competition_id).await?;
+
+    match format.as_str() {
+        "markdown" | "md" => Ok(render_markdown(&report)),
+        "html" => Ok(render_html(&report)),
+        _ => Err(format!("Unsupported report format: {}", format)),
+    }
+}
+
+/// Format a result value the same way the frontend does (time, distance, cm or points)
+pub(crate) fn format_result_value(value: f64, discipline: &CompetitionReportDiscipline) -> String {
+    if discipline.category == "combined" {
+        return format!("{} p", value.round() as i64);
+    }
+
+    if discipline.unit == "time" {
+        if !value.is_finite() || value < 0.0 {
+            return "0.00".to_string();
+        }
+        if value < 60.0 {
+            return format!("{:.2}", value);
+        }
+        let secs = format!("{:05.2}", value % 60.0);
+        if value < 3600.0 {
+            return format!("{}:{}", (value / 60.0).floor() as i64, secs);
+        }
+        let hours = (value / 3600.0).floor() as i64;
+        let mins = ((value % 3600.0) / 60.0).floor() as i64;
+        return format!("{}:{:02}:{}", hours, mins, secs);
+    }
+
+    match discipline.name.as_str() {
+        "Korkeus" | "Seiväs" => format!("{} cm", (value * 100.0).round() as i64),
+        "Cooper" => format!("{} m", value.round() as i64),
+        _ => format!("{:.2} m", value),
+    }
+}
+
+/// "2025-06-14" -> "14.6.2025"
+fn format_date_fi(date: &str) -> String {
+    match chrono::NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d") {
+        Ok(d) => d.format("%-d.%-m.%Y").to_string(),
+        Err(_) => date.to_string(),
+    }
+}
+
+fn format_competition_dates(report: &CompetitionReport) -> String {
+    match &report.competition.end_date {
+        Some(end) if end != &report.competition.date => format!(
+            "{} – {}",
+            format_date_fi(&report.competition.date),
+            format_date_fi(end)
+        ),
+        _ => format_date_fi(&report.competition.date),
+    }
+}
+
+fn medal_label(medal_type: &str) -> &'static str {
+    match medal_type {
+        "gold" => "Kulta",
+        "silver" => "Hopea",
+        _ => "Pronssi",
+    }
+}
+
+/// Result text with status, wind and record markers, e.g. "12.34 (+1.2) OE"
+fn result_summary(entry: &CompetitionReportResult) -> String {
+    let result = &entry.result;
+    let mut text = match result.status.as_deref() {
+        Some("nm") => "NM".to_string(),
+        Some("dns") => "DNS".to_string(),
+        Some("dnf") => "DNF".to_string(),
+        Some("dq") => "DQ".to_string(),
+        _ => format_result_value(result.value, &entry.discipline),
+    };
+
+    if let Some(wind) = result.wind {
+        text.push_str(&format!(" ({:+.1})", wind));
+    }
+
+    let mut markers = Vec::new();
+    if result.is_national_record {
+        markers.push("SE");
+    }
+    if result.is_personal_best {
+        markers.push("OE");
+    } else if result.is_season_best {
+        markers.push("KE");
+    }
+    if !markers.is_empty() {
+        text.push(' ');
+        text.push_str(&markers.join(", "));
+    }
+
+    text
+}
+
+/// Backslash-escape characters that markdown would treat as formatting, links,
+/// HTML or (inside a table) a new column
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '~' | '[' | ']' | '<' | '>' | '#' | '|') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+pub(crate) fn render_markdown(report: &CompetitionReport) -> String {
+    let competition = &report.competition;
+    let mut out = String::new();
+
+    out.push_str(&format!("# {}\n\n", escape_markdown(&competition.name)));
+    out.push_str(&format!("**Päivämäärä:** {}  \n", format_competition_dates(report)));
+    if let Some(location) = &competition.location {
+        out.push_str(&format!("**Paikka:** {}  \n", escape_markdown(location)));
+    }
+    out.push('\n');
+
+    if report.athletes.is_empty() {
+        out.push_str("Ei urheilijoita tai tuloksia tästä kilpailusta.\n");
+        return out;
+    }
+
+    for athlete in &report.athletes {
+        out.push_str(&format!(
+            "## {} {}\n\n",
+            escape_markdown(&athlete.first_name),
+            escape_markdown(&athlete.last_name)
+        ));
+
+        if athlete.results.is_empty() {
+            out.push_str("Ei tuloksia.\n\n");
+        } else {
+            out.push_str("| Laji | Tulos | Sijoitus |\n|------|-------|----------|\n");
+            for entry in &athlete.results {
+                let placement = entry
+                    .result
+                    .placement
+                    .map(|p| format!("{}.", p))
+                    .unwrap_or_else(|| "–".to_string());
+                out.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    escape_markdown(&entry.discipline.full_name),
+                    escape_markdown(&result_summary(entry)),
+                    placement
+                ));
+            }
+            out.push('\n');
+        }
+
+        if athlete.personal_best_count > 0 || athlete.season_best_count > 0 {
+            out.push_str(&format!(
+                "Omia ennätyksiä: {}, kauden ennätyksiä: {}\n\n",
+                athlete.personal_best_count, athlete.season_best_count
+            ));
+        }
+
+        if !athlete.medals.is_empty() {
+            out.push_str("**Mitalit:**\n\n");
+            for medal in &athlete.medals {
+                out.push_str(&format!(
+                    "- {}{}\n",
+                    medal_label(&medal.medal_type),
+                    medal
+                        .discipline_name
+                        .as_ref()
+                        .map(|d| format!(" – {}", escape_markdown(d)))
+                        .unwrap_or_default()
+                ));
+            }
+            out.push('\n');
+        }
+
+        if !athlete.planned_without_result.is_empty() {
+            let names: Vec<String> = athlete
+                .planned_without_result
+                .iter()
+                .map(|d| escape_markdown(&d.full_name))
+                .collect();
+            out.push_str(&format!("Suunnitellut lajit ilman tulosta: {}\n\n", names.join(", ")));
+        }
+    }
+
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn render_html(report: &CompetitionReport) -> String {
+    let competition = &report.competition;
+    let mut out = String::new();
+
+    out.push_str("<!DOCTYPE html>\n<html lang=\"fi\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n", escape_html(&competition.name)));
+    out.push_str("<style>body{font-family:sans-serif;max-width:720px;margin:2em auto;}table{border-collapse:collapse;width:100%;}th,td{border-bottom:1px solid #ddd;padding:4px 8px;text-align:left;}</style>\n");
+    out.push_str("</head>\n<body>\n");
+
+    out.push_str(&format!("<h1>{}</h1>\n", escape_html(&competition.name)));
+    out.push_str(&format!("<p><strong>Päivämäärä:</strong> {}", format_competition_dates(report)));
+    if let Some(location) = &competition.location {
+        out.push_str(&format!("<br><strong>Paikka:</strong> {}", escape_html(location)));
+    }
+    out.push_str("</p>\n");
+
+    if report.athletes.is_empty() {
+        out.push_str("<p>Ei urheilijoita tai tuloksia tästä kilpailusta.</p>\n");
+    }
+
+    for athlete in &report.athletes {
+        out.push_str(&format!(
+            "<h2>{} {}</h2>\n",
+            escape_html(&athlete.first_name),
+            escape_html(&athlete.last_name)
+        ));
+
+        if athlete.results.is_empty() {
+            out.push_str("<p>Ei tuloksia.</p>\n");
+        } else {
+            out.push_str("<table>\n<tr><th>Laji</th><th>Tulos</th><th>Sijoitus</th></tr>\n");
+            for entry in &athlete.results {
+                let placement = entry
+                    .result
+                    .placement
+                    .map(|p| format!("{}.", p))
+                    .unwrap_or_else(|| "–".to_string());
+                out.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&entry.discipline.full_name),
+                    escape_html(&result_summary(entry)),
+                    placement
+                ));
+            }
+            out.push_str("</table>\n");
+        }
+
+        if athlete.personal_best_count > 0 || athlete.season_best_count > 0 {
+            out.push_str(&format!(
+                "<p>Omia ennätyksiä: {}, kauden ennätyksiä: {}</p>\n",
+                athlete.personal_best_count, athlete.season_best_count
+            ));
+        }
+
+        if !athlete.medals.is_empty() {
+            out.push_str("<p><strong>Mitalit:</strong></p>\n<ul>\n");
+            for medal in &athlete.medals {
+                out.push_str(&format!(
+                    "<li>{}{}</li>\n",
+                    medal_label(&medal.medal_type),
+                    medal
+                        .discipline_name
+                        .as_ref()
+                        .map(|d| format!(" – {}", escape_html(d)))
+                        .unwrap_or_default()
+                ));
+            }
+            out.push_str("</ul>\n");
+        }
+
+        if !athlete.planned_without_result.is_empty() {
+            let names: Vec<String> = athlete
+                .planned_without_result
+                .iter()
+                .map(|d| escape_html(&d.full_name))
+                .collect();
+            out.push_str(&format!("<p>Suunnitellut lajit ilman tulosta: {}</p>\n", names.join(", ")));
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discipline(name: &str, category: &str, unit: &str) -> CompetitionReportDiscipline {
+        CompetitionReportDiscipline {
+            id: 1,
+            name: name.to_string(),
+            full_name: name.to_string(),
+            category: category.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_result_value_time() {
+        let sprint = discipline("60 m", "sprints", "time");
+        assert_eq!(format_result_value(9.5, &sprint), "9.50");
+        assert_eq!(format_result_value(125.4, &sprint), "2:05.40");
+        assert_eq!(format_result_value(3725.0, &sprint), "1:02:05.00");
+    }
+
+    #[test]
+    fn test_format_result_value_distance_and_points() {
+        assert_eq!(format_result_value(4.5, &discipline("Pituus", "jumps", "distance")), "4.50 m");
+        assert_eq!(format_result_value(1.06, &discipline("Korkeus", "jumps", "distance")), "106 cm");
+        assert_eq!(format_result_value(2450.4, &discipline("Cooper", "other", "distance")), "2450 m");
+        assert_eq!(format_result_value(1234.0, &discipline("3-ottelu", "combined", "distance")), "1234 p");
+    }
+
+    #[test]
+    fn test_format_date_fi() {
+        assert_eq!(format_date_fi("2025-06-14"), "14.6.2025");
+        assert_eq!(format_date_fi("huomenna"), "huomenna");
+    }
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("60 m | aj"), "60 m \\| aj");
+        assert_eq!(escape_markdown("9.50 OE"), "9.50 OE");
+        assert_eq!(escape_markdown("*Kisat* [2025]"), "\\*Kisat\\* \\[2025\\]");
+        assert_eq!(escape_markdown("<b>_a_</b>"), "\\<b\\>\\_a\\_\\</b\\>");
+    }
+
+    async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        crate::database::run_migrations(&pool).await.expect("Failed to run migrations");
+        pool
+    }
+
+    /// Competition result; the stored PB/SB flags are left off on purpose
+    async fn insert_result(pool: &sqlx::Pool<sqlx::Sqlite>, discipline_id: i64, date: &str, value: f64, competition: &str) -> i64 {
+        sqlx::query(
+            "INSERT INTO results (athlete_id, discipline_id, date, value, type, competition_name) VALUES (1, ?, ?, ?, 'competition', ?)"
+        )
+        .bind(discipline_id)
+        .bind(date)
+        .bind(value)
+        .bind(competition)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid()
+    }
+
+    #[tokio::test]
+    async fn test_report_marks_bests_as_they_stood_at_the_time() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO athletes (id, first_name, last_name, birth_year) VALUES (1, 'Aino', 'Virtanen', 2012)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO competitions (id, name, date, location) VALUES (1, 'Kevätkisat', '2025-05-10', 'Turku')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        insert_result(&pool, 22, "2024-08-01", 4.20, "Syyskisat").await;
+        let long_jump = insert_result(&pool, 22, "2025-05-10", 4.00, "Kevätkisat").await;
+        let sprint = insert_result(&pool, 2, "2025-05-10", 9.50, "Kevätkisat").await;
+        // Later results beat both, so neither is a current best any more
+        insert_result(&pool, 22, "2025-06-01", 4.50, "Kesäkisat").await;
+        insert_result(&pool, 2, "2025-06-01", 9.20, "Kesäkisat").await;
+
+        sqlx::query(
+            "INSERT INTO medals (athlete_id, result_id, type, competition_name, competition_id, discipline_id, discipline_name, date) VALUES (1, ?, 'gold', 'Kevätkisat', 1, 2, '60 m', '2025-05-10')"
+        )
+        .bind(sprint)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = build_competition_report(&pool, 1).await.unwrap();
+        assert_eq!(report.athletes.len(), 1);
+        let athlete = &report.athletes[0];
+
+        let flags: Vec<(i64, bool, bool)> = athlete
+            .results
+            .iter()
+            .map(|r| (r.result.id, r.result.is_personal_best, r.result.is_season_best))
+            .collect();
+        assert!(flags.contains(&(long_jump, false, true)));
+        assert!(flags.contains(&(sprint, true, true)));
+        assert_eq!(athlete.personal_best_count, 1);
+        assert_eq!(athlete.season_best_count, 2);
+
+        assert_eq!(athlete.medals.len(), 1);
+        assert_eq!(athlete.medals[0].discipline_name.as_deref(), Some("60 m"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<b>A & B</b>"), "&lt;b&gt;A &amp; B&lt;/b&gt;");
+    }
+}
@@ -1,8 +1,11 @@
+use crate::commands::goals::evaluation::evaluate_goals;
+use crate::commands::photos::remove_entity_tags;
 use crate::database::get_pool;
 use crate::types::{CreateResult, Discipline, Result as AthleteResult, UpdateResult};
 use sqlx::Row;
 use tauri::AppHandle;
 
+use super::medals::{remove_result_medal, sync_result_medal};
 use super::records::{
     check_personal_best_extended, check_season_best_extended, recalculate_records,
 };
@@ -13,7 +16,7 @@ pub async fn get_all_results(app: AppHandle) -> Result<Vec<AthleteResult>, Strin
     let pool = get_pool(&app).await?;
 
     let rows = sqlx::query(
-        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at
+        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at
         FROM results ORDER BY date DESC"#
     )
     .fetch_all(&pool)
@@ -43,6 +46,7 @@ pub async fn get_all_results(app: AppHandle) -> Result<Vec<AthleteResult>, Strin
         hurdle_spacing: row.get("hurdle_spacing"),
         sub_results: row.get("sub_results"),
         combined_event_id: row.get("combined_event_id"),
+        heat: row.get("heat"),
         created_at: row.get("created_at"),
     }).collect())
 }
@@ -52,7 +56,7 @@ pub async fn get_results_by_athlete(app: AppHandle, athlete_id: i64) -> Result<V
     let pool = get_pool(&app).await?;
 
     let rows = sqlx::query(
-        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at
+        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at
         FROM results WHERE athlete_id = ? ORDER BY date DESC"#
     )
     .bind(athlete_id)
@@ -83,6 +87,7 @@ pub async fn get_results_by_athlete(app: AppHandle, athlete_id: i64) -> Result<V
         hurdle_spacing: row.get("hurdle_spacing"),
         sub_results: row.get("sub_results"),
         combined_event_id: row.get("combined_event_id"),
+        heat: row.get("heat"),
         created_at: row.get("created_at"),
     }).collect())
 }
@@ -112,7 +117,14 @@ pub async fn get_disciplines(app: AppHandle) -> Result<Vec<Discipline>, String>
 #[tauri::command]
 pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<AthleteResult, String> {
     let pool = get_pool(&app).await?;
+    insert_result(&pool, &result).await
+}
 
+/// Insert a result and update PB/SB flags. Shared by create_result and file imports.
+pub(crate) async fn insert_result(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    result: &CreateResult,
+) -> Result<AthleteResult, String> {
     // Get current year for fallback (if date parsing fails)
     let current_year = chrono::Utc::now().format("%Y").to_string().parse::<i32>().unwrap_or(2024);
     let year: i32 = result.date.split('-').next().and_then(|y| y.parse().ok()).unwrap_or(current_year);
@@ -126,11 +138,12 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
             wind: result.wind,
             equipment_weight: result.equipment_weight,
             hurdle_height: result.hurdle_height,
+            date: Some(result.date.clone()),
         };
 
         // Check if this is a personal best (with wind and equipment consideration)
         let pb = check_personal_best_extended(
-            &pool,
+            pool,
             result.athlete_id,
             result.discipline_id,
             result.value,
@@ -139,7 +152,7 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
 
         // Check if this is a season best
         let sb = check_season_best_extended(
-            &pool,
+            pool,
             result.athlete_id,
             result.discipline_id,
             result.value,
@@ -228,8 +241,8 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
     }
 
     let query_result = sqlx::query(
-        r#"INSERT INTO results (athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
+        r#"INSERT INTO results (athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
     )
     .bind(result.athlete_id)
     .bind(result.discipline_id)
@@ -252,6 +265,7 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
     .bind(result.hurdle_spacing)
     .bind(&result.sub_results)
     .bind(result.combined_event_id)
+    .bind(&result.heat)
     .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
@@ -259,7 +273,7 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
     let id = query_result.last_insert_rowid();
 
     let row = sqlx::query(
-        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at
+        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at
         FROM results WHERE id = ?"#
     )
     .bind(id)
@@ -270,6 +284,12 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
     // Commit transaction
     tx.commit().await.map_err(|e| e.to_string())?;
 
+    // Placement 1-3 in a competition creates a medal
+    sync_result_medal(pool, id).await?;
+
+    // The new result may meet an active goal
+    evaluate_goals(pool, result.athlete_id, result.discipline_id).await?;
+
     Ok(AthleteResult {
         id: row.get("id"),
         athlete_id: row.get("athlete_id"),
@@ -293,6 +313,7 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
         hurdle_spacing: row.get("hurdle_spacing"),
         sub_results: row.get("sub_results"),
         combined_event_id: row.get("combined_event_id"),
+        heat: row.get("heat"),
         created_at: row.get("created_at"),
     })
 }
@@ -301,6 +322,14 @@ pub async fn create_result(app: AppHandle, result: CreateResult) -> Result<Athle
 pub async fn update_result(app: AppHandle, id: i64, result: UpdateResult) -> Result<AthleteResult, String> {
     let pool = get_pool(&app).await?;
 
+    // Remember the previous athlete/discipline so their goals can be re-evaluated
+    let previous: Option<(i64, i64)> = sqlx::query("SELECT athlete_id, discipline_id FROM results WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| (row.get("athlete_id"), row.get("discipline_id")));
+
     sqlx::query(
         r#"UPDATE results SET
             athlete_id = COALESCE(?, athlete_id),
@@ -321,7 +350,8 @@ pub async fn update_result(app: AppHandle, id: i64, result: UpdateResult) -> Res
             hurdle_spacing = ?,
             is_national_record = COALESCE(?, is_national_record),
             sub_results = COALESCE(?, sub_results),
-            combined_event_id = COALESCE(?, combined_event_id)
+            combined_event_id = COALESCE(?, combined_event_id),
+            heat = NULLIF(COALESCE(?, heat), '')
         WHERE id = ?"#
     )
     .bind(result.athlete_id)
@@ -343,6 +373,7 @@ pub async fn update_result(app: AppHandle, id: i64, result: UpdateResult) -> Res
     .bind(result.is_national_record.map(|v| if v { 1i32 } else { 0i32 }))
     .bind(&result.sub_results)
     .bind(result.combined_event_id)
+    .bind(&result.heat)
     .bind(id)
     .execute(&pool)
     .await
@@ -350,7 +381,7 @@ pub async fn update_result(app: AppHandle, id: i64, result: UpdateResult) -> Res
 
     // Get the updated result to extract athlete_id and discipline_id for recalculation
     let row = sqlx::query(
-        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at
+        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at
         FROM results WHERE id = ?"#
     )
     .bind(id)
@@ -366,9 +397,20 @@ pub async fn update_result(app: AppHandle, id: i64, result: UpdateResult) -> Res
     // Recalculate PB/SB flags for this athlete/discipline combination
     recalculate_records(&pool, athlete_id, discipline_id, equipment_weight, hurdle_height).await?;
 
+    // Keep the derived medal in sync with placement and level
+    sync_result_medal(&pool, id).await?;
+
+    // Achieve or reopen goals affected by the edit
+    evaluate_goals(&pool, athlete_id, discipline_id).await?;
+    if let Some((prev_athlete_id, prev_discipline_id)) = previous {
+        if (prev_athlete_id, prev_discipline_id) != (athlete_id, discipline_id) {
+            evaluate_goals(&pool, prev_athlete_id, prev_discipline_id).await?;
+        }
+    }
+
     // Re-fetch the result after recalculation to get updated PB/SB flags
     let row = sqlx::query(
-        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at
+        r#"SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at
         FROM results WHERE id = ?"#
     )
     .bind(id)
@@ -399,6 +441,7 @@ pub async fn update_result(app: AppHandle, id: i64, result: UpdateResult) -> Res
         hurdle_spacing: row.get("hurdle_spacing"),
         sub_results: row.get("sub_results"),
         combined_event_id: row.get("combined_event_id"),
+        heat: row.get("heat"),
         created_at: row.get("created_at"),
     })
 }
@@ -416,12 +459,19 @@ pub async fn delete_result(app: AppHandle, id: i64) -> Result<bool, String> {
     .await
     .map_err(|e| e.to_string())?;
 
+    // Medals derived from this result go with it
+    remove_result_medal(&pool, id).await?;
+
     let result = sqlx::query("DELETE FROM results WHERE id = ?")
         .bind(id)
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
 
+    if result.rows_affected() > 0 {
+        remove_entity_tags(&pool, "results", id).await?;
+    }
+
     // If we deleted a result, recalculate PB/SB for that athlete/discipline
     if result.rows_affected() > 0 {
         if let Some(row) = row {
@@ -431,6 +481,9 @@ pub async fn delete_result(app: AppHandle, id: i64) -> Result<bool, String> {
             let hurdle_height: Option<i32> = row.get("hurdle_height");
 
             recalculate_records(&pool, athlete_id, discipline_id, equipment_weight, hurdle_height).await?;
+
+            // Goals achieved by the deleted result go back to active
+            evaluate_goals(&pool, athlete_id, discipline_id).await?;
         }
     }
 
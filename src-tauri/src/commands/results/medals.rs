@@ -1,42 +1,273 @@
+use crate::commands::settings::load_medal_settings;
 use crate::database::get_pool;
-use crate::types::Medal;
+use crate::types::{Medal, MedalTableRow, UpdateMedal};
 use sqlx::Row;
 use tauri::AppHandle;
 
-#[tauri::command]
-pub async fn get_athlete_medals(app: AppHandle, athlete_id: i64) -> Result<Vec<Medal>, String> {
-    let pool = get_pool(&app).await?;
+pub(crate) const MEDAL_COLUMNS: &str =
+    "id, athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date, derived, created_at";
 
-    let rows = sqlx::query(
-        r#"SELECT m.id, m.athlete_id, m.result_id, m.type, m.competition_name,
-                  r.location, r.discipline_id, d.full_name as discipline_name, m.date, m.created_at
-           FROM medals m
-           LEFT JOIN results r ON m.result_id = r.id
-           LEFT JOIN disciplines d ON r.discipline_id = d.id
-           WHERE m.athlete_id = ?
-           ORDER BY m.date DESC"#
-    )
-    .bind(athlete_id)
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(rows.iter().map(|row| Medal {
+pub(crate) fn medal_from_row(row: &sqlx::sqlite::SqliteRow) -> Medal {
+    Medal {
         id: row.get("id"),
         athlete_id: row.get("athlete_id"),
         result_id: row.get("result_id"),
         medal_type: row.get("type"),
         competition_name: row.get("competition_name"),
-        competition_id: None,
+        competition_id: row.get("competition_id"),
         location: row.get("location"),
         discipline_id: row.get("discipline_id"),
         discipline_name: row.get("discipline_name"),
         date: row.get("date"),
+        derived: row.get("derived"),
         created_at: row.get("created_at"),
-    }).collect())
+    }
+}
+
+async fn fetch_medal(pool: &sqlx::Pool<sqlx::Sqlite>, id: i64) -> Result<Medal, String> {
+    let row = sqlx::query(&format!("SELECT {} FROM medals WHERE id = ?", MEDAL_COLUMNS))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(medal_from_row(&row))
+}
+
+/// Medal type earned by a placement (1-3)
+pub(crate) fn medal_type_for_placement(placement: Option<i32>) -> Option<&'static str> {
+    match placement {
+        Some(1) => Some("gold"),
+        Some(2) => Some("silver"),
+        Some(3) => Some("bronze"),
+        _ => None,
+    }
+}
+
+/// A result can earn a medal if it is a valid competition result
+/// and its competition level is not excluded in medal settings
+pub(crate) fn is_medal_eligible(
+    result_type: &str,
+    status: Option<&str>,
+    competition_level: Option<&str>,
+    excluded_levels: &[String],
+) -> bool {
+    result_type == "competition"
+        && status.unwrap_or("valid") == "valid"
+        && !competition_level.is_some_and(|level| excluded_levels.iter().any(|l| l == level))
+}
+
+/// Find the competition a result belongs to (same name, date within the competition days)
+async fn find_competition_id(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    competition_name: Option<&str>,
+    date: &str,
+) -> Result<Option<i64>, String> {
+    let Some(name) = competition_name else {
+        return Ok(None);
+    };
+
+    sqlx::query_scalar(
+        r#"SELECT id FROM competitions
+        WHERE LOWER(TRIM(name)) = LOWER(TRIM(?))
+          AND ? BETWEEN date AND COALESCE(end_date, date)
+        ORDER BY date DESC LIMIT 1"#
+    )
+    .bind(name)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Remove the medal derived from a result
+async fn remove_derived_medal(pool: &sqlx::Pool<sqlx::Sqlite>, result_id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM medals WHERE result_id = ? AND derived = 1")
+        .bind(result_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Detach medals from a result that is being deleted. The derived medal goes
+/// with the result; manually added medals are kept without the link.
+pub(crate) async fn remove_result_medal(pool: &sqlx::Pool<sqlx::Sqlite>, result_id: i64) -> Result<(), String> {
+    remove_derived_medal(pool, result_id).await?;
+
+    sqlx::query("UPDATE medals SET result_id = NULL WHERE result_id = ?")
+        .bind(result_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Create, update or remove the medal derived from a result's placement.
+///
+/// Placement 1-3 on an eligible result upserts the derived medal by result_id with
+/// competition, location and discipline filled in. Any other placement, including
+/// none, removes it. Manually added medals are never touched; while one is linked
+/// to the result no derived medal is created next to it.
+pub(crate) async fn sync_result_medal(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    result_id: i64,
+) -> Result<Option<Medal>, String> {
+    let row = sqlx::query(
+        r#"SELECT r.athlete_id, r.discipline_id, r.date, r.type, r.status, r.competition_name, r.competition_level,
+                  r.location, r.placement, d.full_name as discipline_name
+           FROM results r
+           LEFT JOIN disciplines d ON r.discipline_id = d.id
+           WHERE r.id = ?"#
+    )
+    .bind(result_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let Some(row) = row else {
+        remove_result_medal(pool, result_id).await?;
+        return Ok(None);
+    };
+
+    let placement: Option<i32> = row.get("placement");
+    let settings = load_medal_settings(pool).await?;
+    let result_type: String = row.get("type");
+    let status: Option<String> = row.get("status");
+    let competition_level: Option<String> = row.get("competition_level");
+
+    let medal_type = medal_type_for_placement(placement).filter(|_| {
+        is_medal_eligible(
+            &result_type,
+            status.as_deref(),
+            competition_level.as_deref(),
+            &settings.excluded_levels,
+        )
+    });
+
+    let Some(medal_type) = medal_type else {
+        remove_derived_medal(pool, result_id).await?;
+        return Ok(None);
+    };
+
+    let has_manual_medal: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM medals WHERE result_id = ? AND derived = 0"
+    )
+    .bind(result_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if has_manual_medal {
+        remove_derived_medal(pool, result_id).await?;
+        return Ok(None);
+    }
+
+    let athlete_id: i64 = row.get("athlete_id");
+    let discipline_id: i64 = row.get("discipline_id");
+    let discipline_name: Option<String> = row.get("discipline_name");
+    let date: String = row.get("date");
+    let competition_name: Option<String> = row.get("competition_name");
+    let location: Option<String> = row.get("location");
+    let competition_id = find_competition_id(pool, competition_name.as_deref(), &date).await?;
+
+    let existing_id: Option<i64> = sqlx::query_scalar("SELECT id FROM medals WHERE result_id = ? AND derived = 1 ORDER BY id LIMIT 1")
+        .bind(result_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let id = if let Some(id) = existing_id {
+        sqlx::query(
+            r#"UPDATE medals SET athlete_id = ?, type = ?, competition_name = ?, competition_id = ?, location = ?,
+                discipline_id = ?, discipline_name = ?, date = ?
+            WHERE id = ?"#
+        )
+        .bind(athlete_id)
+        .bind(medal_type)
+        .bind(competition_name.as_deref().unwrap_or_default())
+        .bind(competition_id)
+        .bind(&location)
+        .bind(discipline_id)
+        .bind(&discipline_name)
+        .bind(&date)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        // Older data may have several medals for the same result
+        sqlx::query("DELETE FROM medals WHERE result_id = ? AND derived = 1 AND id != ?")
+            .bind(result_id)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        id
+    } else {
+        sqlx::query(
+            r#"INSERT INTO medals (athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date, derived)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 1)"#
+        )
+        .bind(athlete_id)
+        .bind(result_id)
+        .bind(medal_type)
+        .bind(competition_name.as_deref().unwrap_or_default())
+        .bind(competition_id)
+        .bind(&location)
+        .bind(discipline_id)
+        .bind(&discipline_name)
+        .bind(&date)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid()
+    };
+
+    Ok(Some(fetch_medal(pool, id).await?))
+}
+
+/// Re-run medal derivation for every placed competition result and every
+/// derived medal (after settings change)
+pub(crate) async fn resync_all_result_medals(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<(), String> {
+    let result_ids: Vec<i64> = sqlx::query_scalar(
+        r#"SELECT id FROM results WHERE type = 'competition' AND placement IS NOT NULL
+        UNION
+        SELECT result_id FROM medals WHERE derived = 1 AND result_id IS NOT NULL"#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for result_id in result_ids {
+        sync_result_medal(pool, result_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Medals of an athlete, newest first
+#[tauri::command]
+pub async fn get_athlete_medals(app: AppHandle, athlete_id: i64) -> Result<Vec<Medal>, String> {
+    let pool = get_pool(&app).await?;
+
+    let rows = sqlx::query(&format!("SELECT {} FROM medals WHERE athlete_id = ? ORDER BY date DESC", MEDAL_COLUMNS))
+        .bind(athlete_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(medal_from_row).collect())
 }
 
+/// Create a medal, or update the existing one when a medal is already linked to `result_id`.
+/// Competition, location and discipline default to the linked result's values.
+/// Medals saved here are manual, so placement changes no longer overwrite them.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_medal(
     app: AppHandle,
     athlete_id: i64,
@@ -44,34 +275,208 @@ pub async fn create_medal(
     medal_type: String,
     competition_name: String,
     date: String,
+    competition_id: Option<i64>,
+    location: Option<String>,
+    discipline_id: Option<i64>,
 ) -> Result<Medal, String> {
     let pool = get_pool(&app).await?;
 
+    if !["gold", "silver", "bronze"].contains(&medal_type.as_str()) {
+        return Err(format!("Invalid medal type: {}", medal_type));
+    }
+
+    // Fill in missing details from the linked result
+    let result_row = match result_id {
+        Some(id) => sqlx::query(
+            r#"SELECT r.location, r.discipline_id, d.full_name as discipline_name
+               FROM results r
+               LEFT JOIN disciplines d ON r.discipline_id = d.id
+               WHERE r.id = ?"#
+        )
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let location = location.or_else(|| result_row.as_ref().and_then(|r| r.get("location")));
+    let discipline_id = discipline_id.or_else(|| result_row.as_ref().and_then(|r| r.get("discipline_id")));
+    let discipline_name: Option<String> = match discipline_id {
+        Some(id) => sqlx::query_scalar("SELECT full_name FROM disciplines WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+    let competition_id = match competition_id {
+        Some(id) => Some(id),
+        None => find_competition_id(&pool, Some(&competition_name), &date).await?,
+    };
+
+    let existing_id: Option<i64> = match result_id {
+        Some(id) => sqlx::query_scalar("SELECT id FROM medals WHERE result_id = ? ORDER BY id LIMIT 1")
+            .bind(id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let id = if let Some(id) = existing_id {
+        sqlx::query(
+            r#"UPDATE medals SET athlete_id = ?, type = ?, competition_name = ?, competition_id = ?, location = ?,
+                discipline_id = ?, discipline_name = ?, date = ?, derived = 0
+            WHERE id = ?"#
+        )
+        .bind(athlete_id)
+        .bind(&medal_type)
+        .bind(&competition_name)
+        .bind(competition_id)
+        .bind(&location)
+        .bind(discipline_id)
+        .bind(&discipline_name)
+        .bind(&date)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        id
+    } else {
+        sqlx::query(
+            r#"INSERT INTO medals (athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#
+        )
+        .bind(athlete_id)
+        .bind(result_id)
+        .bind(&medal_type)
+        .bind(&competition_name)
+        .bind(competition_id)
+        .bind(&location)
+        .bind(discipline_id)
+        .bind(&discipline_name)
+        .bind(&date)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .last_insert_rowid()
+    };
+
+    fetch_medal(&pool, id).await
+}
+
+/// Update a medal. Nullable details (competition, location, discipline) are
+/// replaced as given so they can be cleared; leaving one out clears it.
+/// An edited medal is no longer derived, so result sync won't overwrite it.
+#[tauri::command]
+pub async fn update_medal(app: AppHandle, id: i64, medal: UpdateMedal) -> Result<Medal, String> {
+    let pool = get_pool(&app).await?;
+
+    if let Some(medal_type) = &medal.medal_type {
+        if !["gold", "silver", "bronze"].contains(&medal_type.as_str()) {
+            return Err(format!("Invalid medal type: {}", medal_type));
+        }
+    }
+
+    let discipline_name: Option<String> = match medal.discipline_id {
+        Some(discipline_id) => sqlx::query_scalar("SELECT full_name FROM disciplines WHERE id = ?")
+            .bind(discipline_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
     let result = sqlx::query(
-        r#"INSERT INTO medals (athlete_id, result_id, type, competition_name, date)
-           VALUES (?, ?, ?, ?, ?)
-           RETURNING id, athlete_id, result_id, type, competition_name, date, created_at"#
+        r#"UPDATE medals SET
+            type = COALESCE(?, type),
+            competition_name = COALESCE(?, competition_name),
+            competition_id = ?,
+            location = ?,
+            discipline_id = ?,
+            discipline_name = ?,
+            date = COALESCE(?, date),
+            derived = 0
+        WHERE id = ?"#
+    )
+    .bind(&medal.medal_type)
+    .bind(&medal.competition_name)
+    .bind(medal.competition_id)
+    .bind(&medal.location)
+    .bind(medal.discipline_id)
+    .bind(&discipline_name)
+    .bind(&medal.date)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Medal {} not found", id));
+    }
+
+    fetch_medal(&pool, id).await
+}
+
+#[tauri::command]
+pub async fn delete_medal(app: AppHandle, id: i64) -> Result<bool, String> {
+    let pool = get_pool(&app).await?;
+
+    let result = sqlx::query("DELETE FROM medals WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Medal counts grouped by season, competition level and discipline.
+/// `athlete_id` limits the table to one athlete; without it the whole club is counted.
+pub(crate) async fn build_medal_table(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    athlete_id: Option<i64>,
+) -> Result<Vec<MedalTableRow>, String> {
+    let rows = sqlx::query(
+        r#"SELECT CAST(strftime('%Y', m.date) AS INTEGER) as season,
+                  COALESCE(c.level, r.competition_level) as competition_level,
+                  m.discipline_id,
+                  m.discipline_name,
+                  SUM(CASE WHEN m.type = 'gold' THEN 1 ELSE 0 END) as gold,
+                  SUM(CASE WHEN m.type = 'silver' THEN 1 ELSE 0 END) as silver,
+                  SUM(CASE WHEN m.type = 'bronze' THEN 1 ELSE 0 END) as bronze,
+                  COUNT(*) as total
+           FROM medals m
+           LEFT JOIN results r ON m.result_id = r.id
+           LEFT JOIN competitions c ON m.competition_id = c.id
+           WHERE (? IS NULL OR m.athlete_id = ?)
+           GROUP BY season, competition_level, m.discipline_id
+           ORDER BY season DESC, gold DESC, silver DESC, bronze DESC, discipline_name"#
     )
     .bind(athlete_id)
-    .bind(result_id)
-    .bind(&medal_type)
-    .bind(&competition_name)
-    .bind(&date)
-    .fetch_one(&pool)
+    .bind(athlete_id)
+    .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(Medal {
-        id: result.get("id"),
-        athlete_id: result.get("athlete_id"),
-        result_id: result.get("result_id"),
-        medal_type: result.get("type"),
-        competition_name: result.get("competition_name"),
-        competition_id: None,
-        location: None,
-        discipline_id: None,
-        discipline_name: None,
-        date: result.get("date"),
-        created_at: result.get("created_at"),
-    })
+    Ok(rows
+        .iter()
+        .map(|row| MedalTableRow {
+            season: row.get("season"),
+            competition_level: row.get("competition_level"),
+            discipline_id: row.get("discipline_id"),
+            discipline_name: row.get("discipline_name"),
+            gold: row.get("gold"),
+            silver: row.get("silver"),
+            bronze: row.get("bronze"),
+            total: row.get("total"),
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_medal_table(app: AppHandle, athlete_id: Option<i64>) -> Result<Vec<MedalTableRow>, String> {
+    let pool = get_pool(&app).await?;
+    build_medal_table(&pool, athlete_id).await
 }
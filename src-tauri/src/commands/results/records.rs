@@ -1,7 +1,26 @@
+use chrono::{Datelike, NaiveDate};
 use sqlx::Row;
 
 use super::types::{RecordCheckParams, WIND_AFFECTED_DISCIPLINES, WIND_LIMIT, WIND_RULE_AGE_THRESHOLD};
 
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d").ok()
+}
+
+/// Age of an athlete on a date. Uses the exact birth date when known,
+/// otherwise the age turned during that calendar year.
+pub fn age_on_date(birth_year: i32, birth_date: Option<&str>, date: &str) -> i32 {
+    let on = parse_date(date);
+
+    if let (Some(born), Some(on)) = (birth_date.and_then(parse_date), on) {
+        let had_birthday = (on.month(), on.day()) >= (born.month(), born.day());
+        return on.year() - born.year() - if had_birthday { 0 } else { 1 };
+    }
+
+    let year = on.map(|d| d.year()).unwrap_or_else(|| chrono::Local::now().year());
+    year - birth_year
+}
+
 /// Check if wind makes the result ineligible for records
 pub fn is_wind_assisted(
     wind: Option<f64>,
@@ -9,13 +28,28 @@ pub fn is_wind_assisted(
     athlete_birth_year: i32,
     result_year: i32,
 ) -> bool {
+    is_wind_assisted_at_age(wind, discipline_name, result_year - athlete_birth_year)
+}
+
+/// Wind check using the athlete's exact age on the result date when the birth date is known
+pub fn is_wind_assisted_on_date(
+    wind: Option<f64>,
+    discipline_name: &str,
+    athlete_birth_year: i32,
+    athlete_birth_date: Option<&str>,
+    result_date: &str,
+) -> bool {
+    let athlete_age = age_on_date(athlete_birth_year, athlete_birth_date, result_date);
+    is_wind_assisted_at_age(wind, discipline_name, athlete_age)
+}
+
+fn is_wind_assisted_at_age(wind: Option<f64>, discipline_name: &str, athlete_age: i32) -> bool {
     // Check if discipline is wind-affected
     if !WIND_AFFECTED_DISCIPLINES.contains(&discipline_name) {
         return false;
     }
 
     // Check athlete age at time of result
-    let athlete_age = result_year - athlete_birth_year;
     if athlete_age < WIND_RULE_AGE_THRESHOLD {
         // Wind rules don't apply to younger athletes
         return false;
@@ -35,7 +69,7 @@ pub async fn check_personal_best_extended(
 ) -> Result<bool, String> {
     // Get discipline info
     let row = sqlx::query(
-        "SELECT d.name, d.lower_is_better, d.category, a.birth_year
+        "SELECT d.name, d.lower_is_better, d.category, a.birth_year, a.birth_date
          FROM disciplines d, athletes a
          WHERE d.id = ? AND a.id = ?"
     )
@@ -57,12 +91,16 @@ pub async fn check_personal_best_extended(
     let lower_is_better: i32 = row.get("lower_is_better");
     let category: String = row.get("category");
     let birth_year: i32 = row.get("birth_year");
+    let birth_date: Option<String> = row.get("birth_date");
 
-    // Get current year from result date isn't available here, use current year
-    let current_year = chrono::Utc::now().format("%Y").to_string().parse::<i32>().unwrap_or(2024);
+    // Without a result date, use today
+    let result_date = params
+        .date
+        .clone()
+        .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
 
     // Check if wind-assisted (ineligible for record)
-    if is_wind_assisted(params.wind, &discipline_name, birth_year, current_year) {
+    if is_wind_assisted_on_date(params.wind, &discipline_name, birth_year, birth_date.as_deref(), &result_date) {
         return Ok(false);
     }
 
@@ -169,7 +207,7 @@ pub async fn check_season_best_extended(
 ) -> Result<bool, String> {
     // Get discipline info
     let row = sqlx::query(
-        "SELECT d.name, d.lower_is_better, d.category, a.birth_year
+        "SELECT d.name, d.lower_is_better, d.category, a.birth_year, a.birth_date
          FROM disciplines d, athletes a
          WHERE d.id = ? AND a.id = ?"
     )
@@ -191,9 +229,14 @@ pub async fn check_season_best_extended(
     let lower_is_better: i32 = row.get("lower_is_better");
     let category: String = row.get("category");
     let birth_year: i32 = row.get("birth_year");
+    let birth_date: Option<String> = row.get("birth_date");
 
     // Check if wind-assisted (ineligible for record)
-    if is_wind_assisted(params.wind, &discipline_name, birth_year, year) {
+    let wind_assisted = match &params.date {
+        Some(date) => is_wind_assisted_on_date(params.wind, &discipline_name, birth_year, birth_date.as_deref(), date),
+        None => is_wind_assisted(params.wind, &discipline_name, birth_year, year),
+    };
+    if wind_assisted {
         return Ok(false);
     }
 
@@ -302,7 +345,7 @@ pub async fn recalculate_records(
 ) -> Result<(), String> {
     // Get discipline info
     let row = sqlx::query(
-        "SELECT d.name, d.lower_is_better, d.category, a.birth_year
+        "SELECT d.name, d.lower_is_better, d.category, a.birth_year, a.birth_date
          FROM disciplines d, athletes a
          WHERE d.id = ? AND a.id = ?"
     )
@@ -324,7 +367,7 @@ pub async fn recalculate_records(
     let lower_is_better: i32 = row.get("lower_is_better");
     let category: String = row.get("category");
     let birth_year: i32 = row.get("birth_year");
-    let current_year = chrono::Utc::now().format("%Y").to_string().parse::<i32>().unwrap_or(2024);
+    let birth_date: Option<String> = row.get("birth_date");
 
     // First, clear all PB/SB flags for this athlete/discipline (respecting equipment)
     if category == "throws" && equipment_weight.is_some() {
@@ -412,9 +455,8 @@ pub async fn recalculate_records(
         let result_id: i64 = row.get("id");
         let wind: Option<f64> = row.get("wind");
         let date: String = row.get("date");
-        let result_year: i32 = date.split('-').next().and_then(|y| y.parse().ok()).unwrap_or(current_year);
 
-        if !is_wind_assisted(wind, &discipline_name, birth_year, result_year) {
+        if !is_wind_assisted_on_date(wind, &discipline_name, birth_year, birth_date.as_deref(), &date) {
             // This is the new PB
             sqlx::query("UPDATE results SET is_personal_best = 1 WHERE id = ?")
                 .bind(result_id)
@@ -461,7 +503,7 @@ pub async fn recalculate_records(
         let sb_query = if category == "throws" && equipment_weight.is_some() {
             if lower_is_better == 1 {
                 sqlx::query(
-                    "SELECT id, wind FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND equipment_weight = ? AND (status IS NULL OR status = 'valid') ORDER BY value ASC"
+                    "SELECT id, wind, date FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND equipment_weight = ? AND (status IS NULL OR status = 'valid') ORDER BY value ASC"
                 )
                 .bind(athlete_id)
                 .bind(discipline_id)
@@ -469,7 +511,7 @@ pub async fn recalculate_records(
                 .bind(equipment_weight)
             } else {
                 sqlx::query(
-                    "SELECT id, wind FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND equipment_weight = ? AND (status IS NULL OR status = 'valid') ORDER BY value DESC"
+                    "SELECT id, wind, date FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND equipment_weight = ? AND (status IS NULL OR status = 'valid') ORDER BY value DESC"
                 )
                 .bind(athlete_id)
                 .bind(discipline_id)
@@ -479,7 +521,7 @@ pub async fn recalculate_records(
         } else if category == "hurdles" && hurdle_height.is_some() {
             if lower_is_better == 1 {
                 sqlx::query(
-                    "SELECT id, wind FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND hurdle_height = ? AND (status IS NULL OR status = 'valid') ORDER BY value ASC"
+                    "SELECT id, wind, date FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND hurdle_height = ? AND (status IS NULL OR status = 'valid') ORDER BY value ASC"
                 )
                 .bind(athlete_id)
                 .bind(discipline_id)
@@ -487,7 +529,7 @@ pub async fn recalculate_records(
                 .bind(hurdle_height)
             } else {
                 sqlx::query(
-                    "SELECT id, wind FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND hurdle_height = ? AND (status IS NULL OR status = 'valid') ORDER BY value DESC"
+                    "SELECT id, wind, date FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND hurdle_height = ? AND (status IS NULL OR status = 'valid') ORDER BY value DESC"
                 )
                 .bind(athlete_id)
                 .bind(discipline_id)
@@ -496,14 +538,14 @@ pub async fn recalculate_records(
             }
         } else if lower_is_better == 1 {
             sqlx::query(
-                "SELECT id, wind FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND (status IS NULL OR status = 'valid') ORDER BY value ASC"
+                "SELECT id, wind, date FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND (status IS NULL OR status = 'valid') ORDER BY value ASC"
             )
             .bind(athlete_id)
             .bind(discipline_id)
             .bind(&year)
         } else {
             sqlx::query(
-                "SELECT id, wind FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND (status IS NULL OR status = 'valid') ORDER BY value DESC"
+                "SELECT id, wind, date FROM results WHERE athlete_id = ? AND discipline_id = ? AND strftime('%Y', date) = ? AND (status IS NULL OR status = 'valid') ORDER BY value DESC"
             )
             .bind(athlete_id)
             .bind(discipline_id)
@@ -511,14 +553,14 @@ pub async fn recalculate_records(
         };
 
         let year_results = sb_query.fetch_all(pool).await.map_err(|e| e.to_string())?;
-        let year_int: i32 = year.parse().unwrap_or(current_year);
 
         // Find the best result that isn't wind-assisted
         for row in &year_results {
             let result_id: i64 = row.get("id");
             let wind: Option<f64> = row.get("wind");
+            let date: String = row.get("date");
 
-            if !is_wind_assisted(wind, &discipline_name, birth_year, year_int) {
+            if !is_wind_assisted_on_date(wind, &discipline_name, birth_year, birth_date.as_deref(), &date) {
                 // This is the SB for this year
                 sqlx::query("UPDATE results SET is_season_best = 1 WHERE id = ?")
                     .bind(result_id)
@@ -1,6 +1,7 @@
+use super::medals::{build_medal_table, is_medal_eligible, medal_type_for_placement, remove_result_medal, sync_result_medal};
 use super::records::{
-    check_personal_best_extended, check_personal_best_internal, check_season_best_extended,
-    check_season_best_internal, is_wind_assisted,
+    age_on_date, check_personal_best_extended, check_personal_best_internal, check_season_best_extended,
+    check_season_best_internal, is_wind_assisted, is_wind_assisted_on_date,
 };
 use super::types::{RecordCheckParams, WIND_AFFECTED_DISCIPLINES, WIND_LIMIT, WIND_RULE_AGE_THRESHOLD};
 
@@ -21,6 +22,7 @@ async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
             first_name TEXT NOT NULL,
             last_name TEXT NOT NULL,
             birth_year INTEGER NOT NULL,
+            birth_date TEXT,
             club_name TEXT,
             photo_path TEXT,
             created_at TEXT NOT NULL DEFAULT (datetime('now')),
@@ -59,6 +61,35 @@ async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
             hurdle_spacing REAL,
             created_at TEXT NOT NULL DEFAULT (datetime('now'))
         );
+
+        CREATE TABLE competitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            date TEXT NOT NULL,
+            end_date TEXT,
+            level TEXT
+        );
+
+        CREATE TABLE medals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            athlete_id INTEGER NOT NULL,
+            result_id INTEGER,
+            type TEXT NOT NULL,
+            competition_name TEXT NOT NULL,
+            competition_id INTEGER,
+            location TEXT,
+            discipline_id INTEGER,
+            discipline_name TEXT,
+            date TEXT NOT NULL,
+            derived INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
         "#,
     )
     .execute(&pool)
@@ -220,6 +251,33 @@ fn test_wind_assisted_zero_wind() {
     assert!(!is_wind_assisted(Some(0.0), "Pituus", 2010, 2025));
 }
 
+#[test]
+fn test_age_on_date_with_birth_date() {
+    // Exact age: birthday not yet reached on the result date
+    assert_eq!(age_on_date(2011, Some("2011-09-15"), "2025-06-01"), 13);
+    assert_eq!(age_on_date(2011, Some("2011-09-15"), "2025-09-15"), 14);
+    assert_eq!(age_on_date(2011, Some("2011-09-15"), "2025-12-31"), 14);
+}
+
+#[test]
+fn test_age_on_date_without_birth_date() {
+    // Only the birth year known: age turned during the year
+    assert_eq!(age_on_date(2011, None, "2025-06-01"), 14);
+    assert_eq!(age_on_date(2011, None, "2025-01-01"), 14);
+    // Unparseable birth date falls back to the birth year
+    assert_eq!(age_on_date(2011, Some("unknown"), "2025-06-01"), 14);
+}
+
+#[test]
+fn test_wind_assisted_on_date_exact_age() {
+    // Turns 14 in September: still 13 in June, so wind is not considered
+    assert!(!is_wind_assisted_on_date(Some(3.0), "100m", 2011, Some("2011-09-15"), "2025-06-01"));
+    assert!(is_wind_assisted_on_date(Some(3.0), "100m", 2011, Some("2011-09-15"), "2025-09-20"));
+
+    // Without a birth date the calendar-year rule applies
+    assert!(is_wind_assisted_on_date(Some(3.0), "100m", 2011, None, "2025-06-01"));
+}
+
 #[test]
 fn test_wind_affected_disciplines_list() {
     // Verify the constant contains expected disciplines
@@ -377,6 +435,7 @@ async fn test_pb_wind_assisted_not_eligible() {
         wind: Some(2.5),
         equipment_weight: None,
         hurdle_height: None,
+        date: None,
     };
     let is_pb = check_personal_best_extended(&pool, athlete_id, 1, 12.00, &params)
         .await
@@ -409,6 +468,7 @@ async fn test_pb_wind_ok_for_young_athlete() {
         wind: Some(2.5),
         equipment_weight: None,
         hurdle_height: None,
+        date: None,
     };
     let is_pb = check_personal_best_extended(&pool, athlete_id, 1, 12.00, &params)
         .await
@@ -443,6 +503,7 @@ async fn test_pb_equipment_weight_separate() {
         wind: None,
         equipment_weight: Some(4.0),
         hurdle_height: None,
+        date: None,
     };
     let is_pb = check_personal_best_extended(&pool, athlete_id, 3, 7.50, &params)
         .await
@@ -494,6 +555,7 @@ async fn test_pb_hurdle_height_separate() {
         wind: None,
         equipment_weight: None,
         hurdle_height: Some(84),
+        date: None,
     };
     let is_pb = check_personal_best_extended(&pool, athlete_id, 4, 11.00, &params)
         .await
@@ -603,6 +665,7 @@ async fn test_sb_wind_assisted_not_eligible() {
         wind: Some(2.5),
         equipment_weight: None,
         hurdle_height: None,
+        date: None,
     };
     let is_sb = check_season_best_extended(&pool, athlete_id, 1, 12.00, 2025, &params)
         .await
@@ -634,9 +697,217 @@ async fn test_sb_non_wind_affected_discipline() {
         wind: Some(5.0),
         equipment_weight: None,
         hurdle_height: None,
+        date: None,
     };
     let is_sb = check_season_best_extended(&pool, athlete_id, 5, 145.0, 2025, &params)
         .await
         .unwrap();
     assert!(is_sb, "Wind should not affect 800m SB eligibility");
 }
+
+// ==================== Medal derivation tests ====================
+
+async fn place_result(pool: &sqlx::Pool<sqlx::Sqlite>, result_id: i64, placement: Option<i32>, level: &str) {
+    sqlx::query(
+        "UPDATE results SET placement = ?, competition_level = ?, competition_name = 'Kevätkisat', location = 'Tampere' WHERE id = ?",
+    )
+    .bind(placement)
+    .bind(level)
+    .bind(result_id)
+    .execute(pool)
+    .await
+    .expect("Failed to update placement");
+}
+
+async fn medal_count(pool: &sqlx::Pool<sqlx::Sqlite>, result_id: i64) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM medals WHERE result_id = ?")
+        .bind(result_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[test]
+fn test_medal_type_for_placement() {
+    assert_eq!(medal_type_for_placement(Some(1)), Some("gold"));
+    assert_eq!(medal_type_for_placement(Some(2)), Some("silver"));
+    assert_eq!(medal_type_for_placement(Some(3)), Some("bronze"));
+    assert_eq!(medal_type_for_placement(Some(4)), None);
+    assert_eq!(medal_type_for_placement(None), None);
+}
+
+#[test]
+fn test_medal_eligibility() {
+    let excluded = vec!["seuran_sisaiset".to_string()];
+    assert!(is_medal_eligible("competition", None, Some("pm"), &excluded));
+    assert!(is_medal_eligible("competition", Some("valid"), None, &excluded));
+    assert!(!is_medal_eligible("training", None, None, &excluded));
+    assert!(!is_medal_eligible("competition", Some("dq"), Some("pm"), &excluded));
+    assert!(!is_medal_eligible("competition", None, Some("seuran_sisaiset"), &excluded));
+}
+
+#[tokio::test]
+async fn test_placement_creates_medal_with_links() {
+    let pool = setup_test_db().await;
+    let athlete_id = create_test_athlete(&pool, 2012).await;
+    let competition_id = sqlx::query("INSERT INTO competitions (name, date, end_date) VALUES ('Kevätkisat', '2025-05-10', '2025-05-11')")
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+    let result_id = insert_result(&pool, athlete_id, 2, "2025-05-11", 4.10, None, None, None, "valid").await;
+    place_result(&pool, result_id, Some(2), "pm").await;
+
+    let medal = sync_result_medal(&pool, result_id).await.unwrap().expect("Medal should be created");
+    assert_eq!(medal.medal_type, "silver");
+    assert!(medal.derived);
+    assert_eq!(medal.competition_id, Some(competition_id));
+    assert_eq!(medal.discipline_id, Some(2));
+    assert_eq!(medal.discipline_name.as_deref(), Some("Pituushyppy"));
+    assert_eq!(medal.location.as_deref(), Some("Tampere"));
+
+    // Improving the placement updates the same medal
+    place_result(&pool, result_id, Some(1), "pm").await;
+    let updated = sync_result_medal(&pool, result_id).await.unwrap().unwrap();
+    assert_eq!(updated.id, medal.id);
+    assert_eq!(updated.medal_type, "gold");
+    assert_eq!(medal_count(&pool, result_id).await, 1);
+
+    // Dropping out of the top three removes it
+    place_result(&pool, result_id, Some(4), "pm").await;
+    assert!(sync_result_medal(&pool, result_id).await.unwrap().is_none());
+    assert_eq!(medal_count(&pool, result_id).await, 0);
+
+    // So does clearing the placement
+    place_result(&pool, result_id, Some(1), "pm").await;
+    sync_result_medal(&pool, result_id).await.unwrap();
+    place_result(&pool, result_id, None, "pm").await;
+    assert!(sync_result_medal(&pool, result_id).await.unwrap().is_none());
+    assert_eq!(medal_count(&pool, result_id).await, 0);
+}
+
+#[tokio::test]
+async fn test_excluded_level_creates_no_medal() {
+    let pool = setup_test_db().await;
+    let athlete_id = create_test_athlete(&pool, 2012).await;
+
+    let result_id = insert_result(&pool, athlete_id, 1, "2025-06-01", 14.2, None, None, None, "valid").await;
+    place_result(&pool, result_id, Some(1), "seuran_sisaiset").await;
+    assert!(sync_result_medal(&pool, result_id).await.unwrap().is_none());
+
+    // Levels come from settings, so clearing the exclusion list allows the medal
+    sqlx::query("INSERT INTO app_settings (key, value) VALUES ('medal_excluded_levels', '[]')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(sync_result_medal(&pool, result_id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_manual_medal_is_never_derived_over() {
+    let pool = setup_test_db().await;
+    let athlete_id = create_test_athlete(&pool, 2012).await;
+
+    let result_id = insert_result(&pool, athlete_id, 3, "2025-06-01", 9.5, None, None, None, "valid").await;
+    sqlx::query("INSERT INTO medals (athlete_id, result_id, type, competition_name, date) VALUES (?, ?, 'bronze', 'Kisat', '2025-06-01')")
+        .bind(athlete_id)
+        .bind(result_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(sync_result_medal(&pool, result_id).await.unwrap().is_none());
+    assert_eq!(medal_count(&pool, result_id).await, 1);
+
+    // A placement does not add a derived medal next to the manual one
+    place_result(&pool, result_id, Some(1), "pm").await;
+    assert!(sync_result_medal(&pool, result_id).await.unwrap().is_none());
+    let medal_type: String = sqlx::query_scalar("SELECT type FROM medals WHERE result_id = ?")
+        .bind(result_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(medal_type, "bronze");
+
+    // Deleting the result keeps the manual medal without the link
+    remove_result_medal(&pool, result_id).await.unwrap();
+    assert_eq!(medal_count(&pool, result_id).await, 0);
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM medals WHERE athlete_id = ? AND result_id IS NULL")
+        .bind(athlete_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 1);
+}
+
+#[tokio::test]
+async fn test_removing_result_drops_derived_medal() {
+    let pool = setup_test_db().await;
+    let athlete_id = create_test_athlete(&pool, 2012).await;
+
+    let result_id = insert_result(&pool, athlete_id, 2, "2025-06-01", 4.2, None, None, None, "valid").await;
+    place_result(&pool, result_id, Some(2), "pm").await;
+    sync_result_medal(&pool, result_id).await.unwrap();
+
+    remove_result_medal(&pool, result_id).await.unwrap();
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM medals WHERE athlete_id = ?")
+        .bind(athlete_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_medal_table_groups_by_season_level_and_discipline() {
+    let pool = setup_test_db().await;
+    let first = create_test_athlete(&pool, 2012).await;
+    let second = create_test_athlete(&pool, 2013).await;
+
+    for (athlete_id, discipline_id, date, placement) in [
+        (first, 2, "2025-05-11", 1),
+        (first, 2, "2025-06-01", 3),
+        (first, 1, "2025-06-01", 2),
+        (second, 2, "2025-06-01", 1),
+        (first, 2, "2024-08-01", 1),
+    ] {
+        let result_id = insert_result(&pool, athlete_id, discipline_id, date, 4.0, None, None, None, "valid").await;
+        place_result(&pool, result_id, Some(placement), "pm").await;
+        sync_result_medal(&pool, result_id).await.unwrap();
+    }
+
+    let athlete_table = build_medal_table(&pool, Some(first)).await.unwrap();
+    assert_eq!(athlete_table.len(), 3);
+    let long_jump_2025 = &athlete_table[0];
+    assert_eq!((long_jump_2025.season, long_jump_2025.discipline_id), (2025, Some(2)));
+    assert_eq!(long_jump_2025.competition_level.as_deref(), Some("pm"));
+    assert_eq!((long_jump_2025.gold, long_jump_2025.silver, long_jump_2025.bronze), (1, 0, 1));
+    assert_eq!(athlete_table[2].season, 2024);
+
+    // Club table counts every athlete
+    let club_table = build_medal_table(&pool, None).await.unwrap();
+    assert_eq!(club_table[0].gold, 2);
+    assert_eq!(club_table.iter().map(|r| r.total).sum::<i32>(), 5);
+}
+
+#[tokio::test]
+async fn test_medal_table_uses_stored_discipline() {
+    let pool = setup_test_db().await;
+    let athlete_id = create_test_athlete(&pool, 2012).await;
+    let result_id = insert_result(&pool, athlete_id, 2, "2025-06-01", 4.0, None, None, None, "valid").await;
+    place_result(&pool, result_id, Some(1), "pm").await;
+    sync_result_medal(&pool, result_id).await.unwrap();
+
+    // A cleared discipline stays cleared even though the result still has one
+    sqlx::query("UPDATE medals SET discipline_id = NULL, discipline_name = NULL, derived = 0 WHERE result_id = ?")
+        .bind(result_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let table = build_medal_table(&pool, Some(athlete_id)).await.unwrap();
+    assert_eq!(table.len(), 1);
+    assert_eq!(table[0].discipline_id, None);
+    assert_eq!(table[0].discipline_name, None);
+}
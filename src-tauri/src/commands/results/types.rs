@@ -18,4 +18,5 @@ pub struct RecordCheckParams {
     pub wind: Option<f64>,
     pub equipment_weight: Option<f64>,
     pub hurdle_height: Option<i32>,
+    pub date: Option<String>, // Result date; exact age for wind rules needs it (defaults to today)
 }
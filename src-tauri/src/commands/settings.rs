@@ -0,0 +1,134 @@
+use crate::commands::results::medals::resync_all_result_medals;
+use crate::database::get_pool;
+use crate::types::{MedalSettings, PhotoSettings};
+use tauri::AppHandle;
+
+/// Setting key for competition levels that never produce medals automatically
+pub const MEDAL_EXCLUDED_LEVELS_KEY: &str = "medal_excluded_levels";
+
+/// Club-internal competitions don't award real medals by default
+pub const DEFAULT_MEDAL_EXCLUDED_LEVELS: &[&str] = &["seuran_sisaiset"];
+
+/// Setting key for rewriting imported photos upright
+pub const PHOTO_NORMALIZE_ORIENTATION_KEY: &str = "photo_normalize_orientation";
+
+/// Setting keys for the photo storage policy
+pub const PHOTO_OPTIMIZE_STORAGE_KEY: &str = "photo_optimize_storage";
+pub const PHOTO_MAX_LONG_EDGE_KEY: &str = "photo_max_long_edge";
+pub const PHOTO_JPEG_QUALITY_KEY: &str = "photo_jpeg_quality";
+pub const PHOTO_KEEP_ORIGINAL_KEY: &str = "photo_keep_original";
+
+/// Setting key for removing location and identifying EXIF from photos
+pub const PHOTO_STRIP_METADATA_KEY: &str = "photo_strip_metadata";
+
+/// Enough for full-screen viewing on most displays
+pub const DEFAULT_PHOTO_MAX_LONG_EDGE: u32 = 2560;
+pub const DEFAULT_PHOTO_JPEG_QUALITY: u8 = 85;
+
+/// Read a raw setting value
+pub async fn get_setting(pool: &sqlx::Pool<sqlx::Sqlite>, key: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Insert or replace a raw setting value
+pub async fn set_setting(pool: &sqlx::Pool<sqlx::Sqlite>, key: &str, value: &str) -> Result<(), String> {
+    sqlx::query(
+        r#"INSERT INTO app_settings (key, value, updated_at) VALUES (?, ?, datetime('now'))
+        ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at"#
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Read a JSON encoded setting, falling back to `default` when missing or unreadable
+pub async fn get_json_setting<T: serde::de::DeserializeOwned>(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    key: &str,
+    default: T,
+) -> Result<T, String> {
+    Ok(get_setting(pool, key)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or(default))
+}
+
+pub async fn load_medal_settings(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<MedalSettings, String> {
+    let default_levels = DEFAULT_MEDAL_EXCLUDED_LEVELS.iter().map(|l| l.to_string()).collect();
+    Ok(MedalSettings {
+        excluded_levels: get_json_setting(pool, MEDAL_EXCLUDED_LEVELS_KEY, default_levels).await?,
+    })
+}
+
+#[tauri::command]
+pub async fn get_medal_settings(app: AppHandle) -> Result<MedalSettings, String> {
+    let pool = get_pool(&app).await?;
+    load_medal_settings(&pool).await
+}
+
+/// Update medal rules and re-sync medals of all placed competition results
+#[tauri::command]
+pub async fn update_medal_settings(app: AppHandle, settings: MedalSettings) -> Result<MedalSettings, String> {
+    let pool = get_pool(&app).await?;
+
+    let value = serde_json::to_string(&settings.excluded_levels).map_err(|e| e.to_string())?;
+    set_setting(&pool, MEDAL_EXCLUDED_LEVELS_KEY, &value).await?;
+
+    resync_all_result_medals(&pool).await?;
+
+    Ok(settings)
+}
+
+pub async fn load_photo_settings(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<PhotoSettings, String> {
+    Ok(PhotoSettings {
+        normalize_orientation: get_json_setting(pool, PHOTO_NORMALIZE_ORIENTATION_KEY, false).await?,
+        optimize_storage: get_json_setting(pool, PHOTO_OPTIMIZE_STORAGE_KEY, false).await?,
+        max_long_edge: get_json_setting(pool, PHOTO_MAX_LONG_EDGE_KEY, DEFAULT_PHOTO_MAX_LONG_EDGE).await?,
+        jpeg_quality: get_json_setting(pool, PHOTO_JPEG_QUALITY_KEY, DEFAULT_PHOTO_JPEG_QUALITY).await?,
+        keep_original: get_json_setting(pool, PHOTO_KEEP_ORIGINAL_KEY, false).await?,
+        strip_metadata: get_json_setting(pool, PHOTO_STRIP_METADATA_KEY, false).await?,
+    })
+}
+
+#[tauri::command]
+pub async fn get_photo_settings(app: AppHandle) -> Result<PhotoSettings, String> {
+    let pool = get_pool(&app).await?;
+    load_photo_settings(&pool).await
+}
+
+/// Update photo handling. Only affects photos imported after the change;
+/// use `optimize_photo_library` to apply the storage policy to existing photos.
+#[tauri::command]
+pub async fn update_photo_settings(app: AppHandle, settings: PhotoSettings) -> Result<PhotoSettings, String> {
+    let pool = get_pool(&app).await?;
+
+    if !(1..=100).contains(&settings.jpeg_quality) {
+        return Err("JPEG quality must be between 1 and 100".to_string());
+    }
+    if settings.max_long_edge < 320 {
+        return Err("Maximum long edge must be at least 320 pixels".to_string());
+    }
+
+    let values = [
+        (PHOTO_NORMALIZE_ORIENTATION_KEY, serde_json::to_string(&settings.normalize_orientation)),
+        (PHOTO_OPTIMIZE_STORAGE_KEY, serde_json::to_string(&settings.optimize_storage)),
+        (PHOTO_MAX_LONG_EDGE_KEY, serde_json::to_string(&settings.max_long_edge)),
+        (PHOTO_JPEG_QUALITY_KEY, serde_json::to_string(&settings.jpeg_quality)),
+        (PHOTO_KEEP_ORIGINAL_KEY, serde_json::to_string(&settings.keep_original)),
+        (PHOTO_STRIP_METADATA_KEY, serde_json::to_string(&settings.strip_metadata)),
+    ];
+    for (key, value) in values {
+        let value = value.map_err(|e| e.to_string())?;
+        set_setting(&pool, key, &value).await?;
+    }
+
+    Ok(settings)
+}
@@ -1,15 +1,22 @@
+use crate::commands::goals::evaluation::evaluate_all_goals;
+use crate::commands::photos::album_from_row;
 use crate::database::get_pool;
-use crate::types::{Athlete, Competition, ExportData, Goal, Medal, Result as AthleteResult};
+use crate::commands::clubs::RESULT_CLUB_SQL;
+use crate::types::{
+    Album, AlbumPhoto, Athlete, AthleteGuardian, Club, ClubMembership, Competition, ExportData, Family, Goal, Guardian, Medal,
+    Result as AthleteResult, ResultClub,
+};
 use sqlx::Row;
+use std::collections::HashMap;
 use tauri::AppHandle;
 
 #[tauri::command]
-pub async fn export_data(app: AppHandle) -> Result<String, String> {
+pub async fn export_data(app: AppHandle, exclude_contacts: Option<bool>) -> Result<String, String> {
     let pool = get_pool(&app).await?;
 
     // Fetch all athletes
     let athlete_rows = sqlx::query(
-        "SELECT id, first_name, last_name, birth_year, gender, club_name, photo_path, created_at, updated_at FROM athletes"
+        "SELECT id, first_name, last_name, birth_year, birth_date, gender, club_name, photo_path, archived_at, family_id, photo_consent, photo_consent_date, photo_consent_note, created_at, updated_at FROM athletes"
     )
     .fetch_all(&pool)
     .await
@@ -20,16 +27,22 @@ pub async fn export_data(app: AppHandle) -> Result<String, String> {
         first_name: row.get("first_name"),
         last_name: row.get("last_name"),
         birth_year: row.get("birth_year"),
+        birth_date: row.get("birth_date"),
         gender: row.get("gender"),
         club_name: row.get("club_name"),
         photo_path: row.get("photo_path"),
+        archived_at: row.get("archived_at"),
+        family_id: row.get("family_id"),
+        photo_consent: row.get("photo_consent"),
+        photo_consent_date: row.get("photo_consent_date"),
+        photo_consent_note: row.get("photo_consent_note"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     }).collect();
 
     // Fetch all results
     let result_rows = sqlx::query(
-        "SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at FROM results"
+        "SELECT id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at FROM results"
     )
     .fetch_all(&pool)
     .await
@@ -58,6 +71,7 @@ pub async fn export_data(app: AppHandle) -> Result<String, String> {
         hurdle_spacing: row.get("hurdle_spacing"),
         sub_results: row.get("sub_results"),
         combined_event_id: row.get("combined_event_id"),
+        heat: row.get("heat"),
         created_at: row.get("created_at"),
     }).collect();
 
@@ -86,7 +100,7 @@ pub async fn export_data(app: AppHandle) -> Result<String, String> {
 
     // Fetch all goals
     let goal_rows = sqlx::query(
-        "SELECT id, athlete_id, discipline_id, target_value, target_date, status, achieved_at, created_at FROM goals"
+        "SELECT id, athlete_id, discipline_id, goal_type, target_value, target_date, status, achieved_at, achieved_result_id, skill_mark, season, created_at FROM goals"
     )
     .fetch_all(&pool)
     .await
@@ -96,16 +110,20 @@ pub async fn export_data(app: AppHandle) -> Result<String, String> {
         id: row.get("id"),
         athlete_id: row.get("athlete_id"),
         discipline_id: row.get("discipline_id"),
+        goal_type: row.get("goal_type"),
         target_value: row.get("target_value"),
+        skill_mark: row.get("skill_mark"),
+        season: row.get("season"),
         target_date: row.get("target_date"),
         status: row.get("status"),
         achieved_at: row.get("achieved_at"),
+        achieved_result_id: row.get("achieved_result_id"),
         created_at: row.get("created_at"),
     }).collect();
 
     // Fetch all medals
     let medal_rows = sqlx::query(
-        "SELECT id, athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date, created_at FROM medals"
+        "SELECT id, athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date, derived, created_at FROM medals"
     )
     .fetch_all(&pool)
     .await
@@ -122,9 +140,129 @@ pub async fn export_data(app: AppHandle) -> Result<String, String> {
         discipline_id: row.get("discipline_id"),
         discipline_name: row.get("discipline_name"),
         date: row.get("date"),
+        derived: row.get("derived"),
         created_at: row.get("created_at"),
     }).collect();
 
+    // Fetch families and guardians
+    let families: Vec<Family> = sqlx::query("SELECT id, name, notes, created_at FROM families")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| Family {
+            id: row.get("id"),
+            name: row.get("name"),
+            notes: row.get("notes"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+
+    // Guardian phone numbers and email addresses can be left out of an export
+    // that is shared; everything else is kept so the file still imports
+    let exclude_contacts = exclude_contacts.unwrap_or(false);
+    let guardians: Vec<Guardian> = sqlx::query(
+        "SELECT id, name, phone, email, relationship, family_id, created_at, updated_at FROM guardians"
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(|row| Guardian {
+        id: row.get("id"),
+        name: row.get("name"),
+        phone: if exclude_contacts { None } else { row.get("phone") },
+        email: if exclude_contacts { None } else { row.get("email") },
+        relationship: row.get("relationship"),
+        family_id: row.get("family_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+    .collect();
+
+    let athlete_guardians: Vec<AthleteGuardian> = sqlx::query("SELECT athlete_id, guardian_id FROM athlete_guardians")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| AthleteGuardian {
+            athlete_id: row.get("athlete_id"),
+            guardian_id: row.get("guardian_id"),
+        })
+        .collect();
+
+    // Fetch clubs, memberships and the club each result is credited to
+    let clubs: Vec<Club> = sqlx::query("SELECT id, name, abbreviation, created_at FROM clubs")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| Club {
+            id: row.get("id"),
+            name: row.get("name"),
+            abbreviation: row.get("abbreviation"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+
+    let club_memberships: Vec<ClubMembership> = sqlx::query(
+        r#"SELECT m.id, m.athlete_id, m.club_id, c.name as club_name, m.start_date, m.end_date
+        FROM athlete_club_memberships m JOIN clubs c ON c.id = m.club_id"#
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(|row| ClubMembership {
+        id: row.get("id"),
+        athlete_id: row.get("athlete_id"),
+        club_id: row.get("club_id"),
+        club_name: row.get("club_name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+    })
+    .collect();
+
+    let result_clubs: Vec<ResultClub> = sqlx::query(&format!(
+        "SELECT result_id, club_id FROM (SELECT r.id as result_id, {} as club_id FROM results r) WHERE club_id IS NOT NULL",
+        RESULT_CLUB_SQL
+    ))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(|row| ResultClub {
+        result_id: row.get("result_id"),
+        club_id: row.get("club_id"),
+    })
+    .collect();
+
+    // Albums refer to photos by id; the photo files travel with Drive sync
+    let albums: Vec<Album> = sqlx::query(
+        r#"SELECT id, name, description, cover_photo_id, created_at, updated_at,
+                  (SELECT COUNT(*) FROM album_photos WHERE album_id = albums.id) as photo_count,
+                  NULL as cover_thumbnail_path
+           FROM albums"#
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .iter()
+    .map(album_from_row)
+    .collect();
+
+    let album_photos: Vec<AlbumPhoto> = sqlx::query("SELECT album_id, photo_id, position FROM album_photos ORDER BY album_id, position")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|row| AlbumPhoto {
+            album_id: row.get("album_id"),
+            photo_id: row.get("photo_id"),
+            position: row.get("position"),
+        })
+        .collect();
+
     let export = ExportData {
         version: "1.0.0".to_string(),
         exported_at: chrono::Utc::now().to_rfc3339(),
@@ -133,29 +271,109 @@ pub async fn export_data(app: AppHandle) -> Result<String, String> {
         competitions,
         goals,
         medals,
+        families,
+        guardians,
+        athlete_guardians,
+        clubs,
+        club_memberships,
+        result_clubs,
+        albums,
+        album_photos,
     };
 
     serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
 }
 
+/// Import clubs and memberships. Club names are unique regardless of case, so an
+/// imported club whose name already belongs to a local club under another id is
+/// merged into the local club instead of replacing it (which would cascade away its
+/// memberships). Result clubs follow from the memberships and need no import.
+async fn import_clubs(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    clubs: &[Club],
+    memberships: &[ClubMembership],
+) -> Result<(), String> {
+    let mut club_ids: HashMap<i64, i64> = HashMap::new();
+
+    for club in clubs {
+        let local_id: Option<i64> = sqlx::query_scalar("SELECT id FROM clubs WHERE name = ? COLLATE NOCASE AND id != ?")
+            .bind(&club.name)
+            .bind(club.id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(local_id) = local_id {
+            club_ids.insert(club.id, local_id);
+            continue;
+        }
+
+        sqlx::query(
+            r#"INSERT INTO clubs (id, name, abbreviation, created_at) VALUES (?, ?, ?, ?)
+               ON CONFLICT(id) DO UPDATE SET name = excluded.name, abbreviation = excluded.abbreviation"#
+        )
+        .bind(club.id)
+        .bind(&club.name)
+        .bind(&club.abbreviation)
+        .bind(&club.created_at)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    for membership in memberships {
+        sqlx::query(
+            "INSERT OR REPLACE INTO athlete_club_memberships (id, athlete_id, club_id, start_date, end_date) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(membership.id)
+        .bind(membership.athlete_id)
+        .bind(club_ids.get(&membership.club_id).copied().unwrap_or(membership.club_id))
+        .bind(&membership.start_date)
+        .bind(&membership.end_date)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn import_data(app: AppHandle, json: String) -> Result<bool, String> {
     let pool = get_pool(&app).await?;
 
     let data: ExportData = serde_json::from_str(&json).map_err(|e| format!("Invalid JSON: {}", e))?;
 
+    // Import families (athletes and guardians refer to them)
+    for family in data.families {
+        sqlx::query("INSERT OR REPLACE INTO families (id, name, notes, created_at) VALUES (?, ?, ?, ?)")
+            .bind(family.id)
+            .bind(&family.name)
+            .bind(&family.notes)
+            .bind(&family.created_at)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
     // Import athletes
     for athlete in data.athletes {
         sqlx::query(
-            "INSERT OR REPLACE INTO athletes (id, first_name, last_name, birth_year, gender, club_name, photo_path, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            "INSERT OR REPLACE INTO athletes (id, first_name, last_name, birth_year, birth_date, gender, club_name, photo_path, archived_at, family_id, photo_consent, photo_consent_date, photo_consent_note, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(athlete.id)
         .bind(&athlete.first_name)
         .bind(&athlete.last_name)
         .bind(athlete.birth_year)
+        .bind(&athlete.birth_date)
         .bind(&athlete.gender)
         .bind(&athlete.club_name)
         .bind(&athlete.photo_path)
+        .bind(&athlete.archived_at)
+        .bind(athlete.family_id)
+        .bind(&athlete.photo_consent)
+        .bind(&athlete.photo_consent_date)
+        .bind(&athlete.photo_consent_note)
         .bind(&athlete.created_at)
         .bind(&athlete.updated_at)
         .execute(&pool)
@@ -166,7 +384,7 @@ pub async fn import_data(app: AppHandle, json: String) -> Result<bool, String> {
     // Import results
     for result in data.results {
         sqlx::query(
-            "INSERT OR REPLACE INTO results (id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            "INSERT OR REPLACE INTO results (id, athlete_id, discipline_id, date, value, type, competition_name, competition_level, custom_level_name, location, placement, notes, is_personal_best, is_season_best, is_national_record, wind, status, equipment_weight, hurdle_height, hurdle_spacing, sub_results, combined_event_id, heat, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(result.id)
         .bind(result.athlete_id)
@@ -190,6 +408,7 @@ pub async fn import_data(app: AppHandle, json: String) -> Result<bool, String> {
         .bind(result.hurdle_spacing)
         .bind(&result.sub_results)
         .bind(result.combined_event_id)
+        .bind(&result.heat)
         .bind(&result.created_at)
         .execute(&pool)
         .await
@@ -221,15 +440,19 @@ pub async fn import_data(app: AppHandle, json: String) -> Result<bool, String> {
     // Import goals
     for goal in data.goals {
         sqlx::query(
-            "INSERT OR REPLACE INTO goals (id, athlete_id, discipline_id, target_value, target_date, status, achieved_at, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            "INSERT OR REPLACE INTO goals (id, athlete_id, discipline_id, goal_type, target_value, target_date, status, achieved_at, achieved_result_id, skill_mark, season, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(goal.id)
         .bind(goal.athlete_id)
         .bind(goal.discipline_id)
+        .bind(&goal.goal_type)
         .bind(goal.target_value)
         .bind(&goal.target_date)
         .bind(&goal.status)
         .bind(&goal.achieved_at)
+        .bind(goal.achieved_result_id)
+        .bind(&goal.skill_mark)
+        .bind(goal.season)
         .bind(&goal.created_at)
         .execute(&pool)
         .await
@@ -239,7 +462,7 @@ pub async fn import_data(app: AppHandle, json: String) -> Result<bool, String> {
     // Import medals
     for medal in data.medals {
         sqlx::query(
-            "INSERT OR REPLACE INTO medals (id, athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            "INSERT OR REPLACE INTO medals (id, athlete_id, result_id, type, competition_name, competition_id, location, discipline_id, discipline_name, date, derived, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(medal.id)
         .bind(medal.athlete_id)
@@ -251,11 +474,151 @@ pub async fn import_data(app: AppHandle, json: String) -> Result<bool, String> {
         .bind(medal.discipline_id)
         .bind(&medal.discipline_name)
         .bind(&medal.date)
+        .bind(medal.derived)
         .bind(&medal.created_at)
         .execute(&pool)
         .await
         .map_err(|e| e.to_string())?;
     }
 
+    // Import clubs and membership history
+    import_clubs(&pool, &data.clubs, &data.club_memberships).await?;
+
+    // Import guardians
+    for guardian in data.guardians {
+        sqlx::query(
+            "INSERT OR REPLACE INTO guardians (id, name, phone, email, relationship, family_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(guardian.id)
+        .bind(&guardian.name)
+        .bind(&guardian.phone)
+        .bind(&guardian.email)
+        .bind(&guardian.relationship)
+        .bind(guardian.family_id)
+        .bind(&guardian.created_at)
+        .bind(&guardian.updated_at)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    for link in data.athlete_guardians {
+        sqlx::query("INSERT OR IGNORE INTO athlete_guardians (athlete_id, guardian_id) VALUES (?, ?)")
+            .bind(link.athlete_id)
+            .bind(link.guardian_id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Import albums. Photos missing from this database are left out.
+    for album in data.albums {
+        sqlx::query(
+            r#"INSERT OR REPLACE INTO albums (id, name, description, cover_photo_id, created_at, updated_at)
+               VALUES (?, ?, ?, (SELECT id FROM photos WHERE id = ?), ?, ?)"#
+        )
+        .bind(album.id)
+        .bind(&album.name)
+        .bind(&album.description)
+        .bind(album.cover_photo_id)
+        .bind(&album.created_at)
+        .bind(&album.updated_at)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    for entry in data.album_photos {
+        sqlx::query(
+            r#"INSERT OR REPLACE INTO album_photos (album_id, photo_id, position)
+               SELECT ?, ?, ? WHERE EXISTS (SELECT 1 FROM photos WHERE id = ?)"#
+        )
+        .bind(entry.album_id)
+        .bind(entry.photo_id)
+        .bind(entry.position)
+        .bind(entry.photo_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Imported results may meet (or no longer meet) goal targets
+    evaluate_all_goals(&pool).await?;
+
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::run_migrations;
+
+    async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("Failed to create in-memory database");
+
+        run_migrations(&pool).await.expect("Failed to run migrations");
+        pool
+    }
+
+    fn club(id: i64, name: &str) -> Club {
+        Club {
+            id,
+            name: name.to_string(),
+            abbreviation: None,
+            created_at: "2025-01-01 00:00:00".to_string(),
+        }
+    }
+
+    fn membership(id: i64, athlete_id: i64, club_id: i64) -> ClubMembership {
+        ClubMembership {
+            id,
+            athlete_id,
+            club_id,
+            club_name: String::new(),
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_clubs_merges_by_name() {
+        let pool = setup_test_db().await;
+        for (id, name) in [(1, "Aino"), (2, "Eetu")] {
+            sqlx::query("INSERT INTO athletes (id, first_name, last_name, birth_year) VALUES (?, ?, 'Virtanen', 2013)")
+                .bind(id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO clubs (id, name) VALUES (5, 'Turun Urheiluliitto')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO athlete_club_memberships (id, athlete_id, club_id) VALUES (1, 1, 5)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Same club under another id and case, plus a new club
+        let clubs = [club(9, "turun urheiluliitto"), club(10, "Kaarinan Pojat")];
+        let memberships = [membership(2, 2, 9), membership(3, 2, 10)];
+        import_clubs(&pool, &clubs, &memberships).await.unwrap();
+
+        let clubs: Vec<(i64, String)> = sqlx::query_as("SELECT id, name FROM clubs ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(clubs, vec![(5, "Turun Urheiluliitto".to_string()), (10, "Kaarinan Pojat".to_string())]);
+
+        let memberships: Vec<(i64, i64)> = sqlx::query_as("SELECT id, club_id FROM athlete_club_memberships ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(memberships, vec![(1, 5), (2, 5), (3, 10)]);
+    }
+}
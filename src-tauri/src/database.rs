@@ -38,7 +38,7 @@ pub async fn init_database(app: &AppHandle) -> Result<DbPool, String> {
     Ok(pool)
 }
 
-async fn run_migrations(pool: &DbPool) -> Result<(), String> {
+pub(crate) async fn run_migrations(pool: &DbPool) -> Result<(), String> {
     // Check if migrations table exists
     sqlx::query(
         r#"CREATE TABLE IF NOT EXISTS _migrations (
@@ -138,6 +138,74 @@ async fn run_migrations(pool: &DbPool) -> Result<(), String> {
         run_migration_v20(pool).await?;
     }
 
+    if current_version < 21 {
+        run_migration_v21(pool).await?;
+    }
+
+    if current_version < 22 {
+        run_migration_v22(pool).await?;
+    }
+
+    if current_version < 23 {
+        run_migration_v23(pool).await?;
+    }
+
+    if current_version < 24 {
+        run_migration_v24(pool).await?;
+    }
+
+    if current_version < 25 {
+        run_migration_v25(pool).await?;
+    }
+
+    if current_version < 26 {
+        run_migration_v26(pool).await?;
+    }
+
+    if current_version < 27 {
+        run_migration_v27(pool).await?;
+    }
+
+    if current_version < 28 {
+        run_migration_v28(pool).await?;
+    }
+
+    if current_version < 29 {
+        run_migration_v29(pool).await?;
+    }
+
+    if current_version < 30 {
+        run_migration_v30(pool).await?;
+    }
+
+    if current_version < 31 {
+        run_migration_v31(pool).await?;
+    }
+
+    if current_version < 32 {
+        run_migration_v32(pool).await?;
+    }
+
+    if current_version < 33 {
+        run_migration_v33(pool).await?;
+    }
+
+    if current_version < 34 {
+        run_migration_v34(pool).await?;
+    }
+
+    if current_version < 35 {
+        run_migration_v35(pool).await?;
+    }
+
+    if current_version < 36 {
+        run_migration_v36(pool).await?;
+    }
+
+    if current_version < 37 {
+        run_migration_v37(pool).await?;
+    }
+
     Ok(())
 }
 
@@ -1252,6 +1320,708 @@ async fn run_migration_v20(pool: &DbPool) -> Result<(), String> {
     Ok(())
 }
 
+async fn run_migration_v21(pool: &DbPool) -> Result<(), String> {
+    // Add heat column to results table (erä, filled in by FinishLynx import)
+    let has_heat: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('results') WHERE name = 'heat'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v21 failed checking heat column: {}", e))?;
+
+    if !has_heat {
+        sqlx::query("ALTER TABLE results ADD COLUMN heat TEXT")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v21 failed adding heat column: {}", e))?;
+    }
+
+    // Manual mappings from names in FinishLynx files to athletes
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS lif_athlete_mappings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            first_name TEXT NOT NULL,
+            last_name TEXT NOT NULL,
+            affiliation TEXT NOT NULL DEFAULT '',
+            athlete_id INTEGER NOT NULL REFERENCES athletes(id) ON DELETE CASCADE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE (first_name, last_name, affiliation)
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v21 failed creating lif_athlete_mappings: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (21, 'add_heat_and_lif_athlete_mappings')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v21: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v22(pool: &DbPool) -> Result<(), String> {
+    // Key/value table for backend settings (medal rules etc.)
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v22 failed creating app_settings: {}", e))?;
+
+    // Medals derived from results are looked up by result_id
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_medals_result ON medals(result_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v22 failed creating idx_medals_result: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (22, 'add_app_settings_and_medal_result_index')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v22: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v23(pool: &DbPool) -> Result<(), String> {
+    // Link goals to the result that achieved them.
+    // No FK: a deleted result must leave the id in place so the goal can be reopened.
+    let has_achieved_result_id: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('goals') WHERE name = 'achieved_result_id'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v23 failed checking achieved_result_id column: {}", e))?;
+
+    if !has_achieved_result_id {
+        sqlx::query("ALTER TABLE goals ADD COLUMN achieved_result_id INTEGER")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v23 failed adding achieved_result_id column: {}", e))?;
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_goals_athlete_discipline ON goals(athlete_id, discipline_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v23 failed creating idx_goals_athlete_discipline: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (23, 'add_goals_achieved_result_id')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v23: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v24(pool: &DbPool) -> Result<(), String> {
+    // Migration v24: Goal kinds (value, skill_mark, competition_count, pb_count, improvement_percent)
+    // Count goals don't need a discipline, so discipline_id becomes nullable (requires table rebuild)
+    let has_goal_type: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('goals') WHERE name = 'goal_type'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v24 failed checking goal_type column: {}", e))?;
+
+    if !has_goal_type {
+        sqlx::query("DROP TABLE IF EXISTS goals_new")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v24 failed dropping goals_new: {}", e))?;
+
+        // Same connection for all statements so PRAGMA foreign_keys applies
+        let mut conn = pool.acquire().await.map_err(|e| format!("Migration v24 failed acquiring connection: {}", e))?;
+
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Migration v24 failed disabling FK: {}", e))?;
+
+        // The rebuild runs in a transaction so a failure can't leave goals half copied.
+        // foreign_keys can't change inside a transaction, so it is toggled around it.
+        let rebuilt: Result<(), String> = async {
+            let mut tx = sqlx::Connection::begin(&mut *conn)
+                .await
+                .map_err(|e| format!("Migration v24 failed starting transaction: {}", e))?;
+
+            sqlx::query(r#"
+                CREATE TABLE goals_new (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    athlete_id INTEGER NOT NULL,
+                    discipline_id INTEGER,
+                    goal_type TEXT NOT NULL DEFAULT 'value' CHECK (goal_type IN ('value', 'skill_mark', 'competition_count', 'pb_count', 'improvement_percent')),
+                    target_value REAL NOT NULL,
+                    target_date TEXT,
+                    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'achieved', 'abandoned')),
+                    achieved_at TEXT,
+                    achieved_result_id INTEGER,
+                    skill_mark TEXT CHECK (skill_mark IS NULL OR skill_mark IN ('A', 'B', 'C')),
+                    season INTEGER,
+                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                    FOREIGN KEY (athlete_id) REFERENCES athletes(id) ON DELETE CASCADE,
+                    FOREIGN KEY (discipline_id) REFERENCES disciplines(id) ON DELETE RESTRICT,
+                    CHECK (goal_type IN ('competition_count', 'pb_count') OR discipline_id IS NOT NULL)
+                )
+            "#)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Migration v24 failed creating goals_new: {}", e))?;
+
+            sqlx::query(r#"
+                INSERT INTO goals_new (id, athlete_id, discipline_id, goal_type, target_value, target_date, status, achieved_at, achieved_result_id, created_at)
+                SELECT id, athlete_id, discipline_id, 'value', target_value, target_date, status, achieved_at, achieved_result_id, created_at
+                FROM goals
+            "#)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Migration v24 failed copying goals data: {}", e))?;
+
+            sqlx::query("DROP TABLE goals")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Migration v24 failed dropping old goals: {}", e))?;
+
+            sqlx::query("ALTER TABLE goals_new RENAME TO goals")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Migration v24 failed renaming goals_new: {}", e))?;
+
+            tx.commit().await.map_err(|e| format!("Migration v24 failed committing goals rebuild: {}", e))
+        }
+        .await;
+
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Migration v24 failed re-enabling FK: {}", e))?;
+
+        drop(conn);
+        rebuilt?;
+    }
+
+    // Indexes are dropped with the old table
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_goals_athlete ON goals(athlete_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v24 failed creating idx_goals_athlete: {}", e))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_goals_athlete_discipline ON goals(athlete_id, discipline_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v24 failed creating idx_goals_athlete_discipline: {}", e))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_goals_status ON goals(status)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v24 failed creating idx_goals_status: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (24, 'add_goal_types')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v24: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v25(pool: &DbPool) -> Result<(), String> {
+    // Archive athletes instead of deleting them (a delete cascades to their whole history)
+    let has_archived_at: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('athletes') WHERE name = 'archived_at'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v25 failed checking archived_at column: {}", e))?;
+
+    if !has_archived_at {
+        sqlx::query("ALTER TABLE athletes ADD COLUMN archived_at TEXT")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v25 failed adding archived_at column: {}", e))?;
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (25, 'add_athletes_archived_at')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v25: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v26(pool: &DbPool) -> Result<(), String> {
+    // Families group siblings; guardians are linked many-to-many with athletes
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS families (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            notes TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v26 failed creating families table: {}", e))?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS guardians (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            phone TEXT,
+            email TEXT,
+            relationship TEXT,
+            family_id INTEGER REFERENCES families(id) ON DELETE SET NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v26 failed creating guardians table: {}", e))?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS athlete_guardians (
+            athlete_id INTEGER NOT NULL REFERENCES athletes(id) ON DELETE CASCADE,
+            guardian_id INTEGER NOT NULL REFERENCES guardians(id) ON DELETE CASCADE,
+            PRIMARY KEY (athlete_id, guardian_id)
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v26 failed creating athlete_guardians table: {}", e))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_athlete_guardians_guardian ON athlete_guardians(guardian_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v26 failed creating idx_athlete_guardians_guardian: {}", e))?;
+
+    let has_family_id: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('athletes') WHERE name = 'family_id'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v26 failed checking family_id column: {}", e))?;
+
+    if !has_family_id {
+        sqlx::query("ALTER TABLE athletes ADD COLUMN family_id INTEGER REFERENCES families(id) ON DELETE SET NULL")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v26 failed adding family_id column: {}", e))?;
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_athletes_family ON athletes(family_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v26 failed creating idx_athletes_family: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (26, 'add_families_and_guardians')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v26: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v27(pool: &DbPool) -> Result<(), String> {
+    // Clubs as entities with membership periods; results are credited by membership on the result date.
+    // athletes.club_name stays as the current club's name.
+    let has_memberships: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='athlete_club_memberships'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v27 failed checking athlete_club_memberships table: {}", e))?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS clubs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            abbreviation TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v27 failed creating clubs table: {}", e))?;
+
+    if !has_memberships {
+        sqlx::query(r#"
+            CREATE TABLE athlete_club_memberships (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                athlete_id INTEGER NOT NULL REFERENCES athletes(id) ON DELETE CASCADE,
+                club_id INTEGER NOT NULL REFERENCES clubs(id) ON DELETE CASCADE,
+                start_date TEXT,
+                end_date TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+        "#)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v27 failed creating athlete_club_memberships table: {}", e))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_memberships_athlete ON athlete_club_memberships(athlete_id)")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v27 failed creating idx_memberships_athlete: {}", e))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_memberships_club ON athlete_club_memberships(club_id)")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v27 failed creating idx_memberships_club: {}", e))?;
+
+        // Existing free-text club names become clubs with an open-ended membership
+        sqlx::query(r#"
+            INSERT OR IGNORE INTO clubs (name)
+            SELECT DISTINCT TRIM(club_name) FROM athletes
+            WHERE club_name IS NOT NULL AND TRIM(club_name) != ''
+        "#)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v27 failed converting club names: {}", e))?;
+
+        sqlx::query(r#"
+            INSERT INTO athlete_club_memberships (athlete_id, club_id)
+            SELECT a.id, c.id FROM athletes a
+            JOIN clubs c ON c.name = TRIM(a.club_name)
+        "#)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v27 failed creating memberships: {}", e))?;
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (27, 'add_clubs_and_memberships')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v27: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v28(pool: &DbPool) -> Result<(), String> {
+    // Optional full birth date for exact age on competition day; birth_year is kept in sync
+    let has_birth_date: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('athletes') WHERE name = 'birth_date'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v28 failed checking birth_date column: {}", e))?;
+
+    if !has_birth_date {
+        sqlx::query("ALTER TABLE athletes ADD COLUMN birth_date TEXT")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v28 failed adding birth_date column: {}", e))?;
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (28, 'add_athlete_birth_date')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v28: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v29(pool: &DbPool) -> Result<(), String> {
+    // Capture date and orientation from EXIF; photos are dated by capture time when known
+    for (column, definition) in [("taken_at", "TEXT"), ("orientation", "INTEGER")] {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('photos') WHERE name = ?"
+        )
+        .bind(column)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Migration v29 failed checking {} column: {}", column, e))?;
+
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE photos ADD COLUMN {} {}", column, definition))
+                .execute(pool)
+                .await
+                .map_err(|e| format!("Migration v29 failed adding {} column: {}", column, e))?;
+        }
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_photos_taken_at ON photos(COALESCE(taken_at, created_at))")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v29 failed creating idx_photos_taken_at: {}", e))?;
+
+    // Photos imported before this version are read in the background after startup
+    // (see photos::exif::backfill_exif_metadata)
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (29, 'add_photo_exif_metadata')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v29: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v30(pool: &DbPool) -> Result<(), String> {
+    // Storage policy: where the untouched file is kept and when the photo was re-encoded
+    for column in ["original_path", "optimized_at"] {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('photos') WHERE name = ?"
+        )
+        .bind(column)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Migration v30 failed checking {} column: {}", column, e))?;
+
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE photos ADD COLUMN {} TEXT", column))
+                .execute(pool)
+                .await
+                .map_err(|e| format!("Migration v30 failed adding {} column: {}", column, e))?;
+        }
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (30, 'add_photo_storage_policy')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v30: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v31(pool: &DbPool) -> Result<(), String> {
+    // SHA-256 of the imported bytes and a perceptual hash for duplicate detection.
+    // Existing photos are hashed on demand by find_similar_photos.
+    for column in ["content_hash", "perceptual_hash"] {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('photos') WHERE name = ?"
+        )
+        .bind(column)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Migration v31 failed checking {} column: {}", column, e))?;
+
+        if !exists {
+            sqlx::query(&format!("ALTER TABLE photos ADD COLUMN {} TEXT", column))
+                .execute(pool)
+                .await
+                .map_err(|e| format!("Migration v31 failed adding {} column: {}", column, e))?;
+        }
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_photos_content_hash ON photos(content_hash)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v31 failed creating idx_photos_content_hash: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (31, 'add_photo_hashes')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v31: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v32(pool: &DbPool) -> Result<(), String> {
+    // Photos can be tagged with any number of athletes, competitions and results.
+    // photos.entity_type/entity_id stays as the entity the photo was imported for.
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS photo_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            photo_id INTEGER NOT NULL REFERENCES photos(id) ON DELETE CASCADE,
+            entity_type TEXT NOT NULL CHECK (entity_type IN ('athletes', 'competitions', 'results')),
+            entity_id INTEGER NOT NULL,
+            caption TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE (photo_id, entity_type, entity_id)
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v32 failed creating photo_tags table: {}", e))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_photo_tags_entity ON photo_tags(entity_type, entity_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v32 failed creating idx_photo_tags_entity: {}", e))?;
+
+    // Existing links become the first tag of each photo
+    sqlx::query(r#"
+        INSERT OR IGNORE INTO photo_tags (photo_id, entity_type, entity_id, created_at)
+        SELECT id, entity_type, entity_id, created_at FROM photos
+        WHERE entity_type IN ('athletes', 'competitions', 'results')
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v32 failed migrating photo links: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (32, 'add_photo_tags')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v32: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v33(pool: &DbPool) -> Result<(), String> {
+    // Named albums holding photos of any entity in a manual order
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS albums (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT,
+            cover_photo_id INTEGER REFERENCES photos(id) ON DELETE SET NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v33 failed creating albums table: {}", e))?;
+
+    sqlx::query(r#"
+        CREATE TABLE IF NOT EXISTS album_photos (
+            album_id INTEGER NOT NULL REFERENCES albums(id) ON DELETE CASCADE,
+            photo_id INTEGER NOT NULL REFERENCES photos(id) ON DELETE CASCADE,
+            position INTEGER NOT NULL,
+            added_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (album_id, photo_id)
+        )
+    "#)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v33 failed creating album_photos table: {}", e))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_album_photos_photo ON album_photos(photo_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v33 failed creating idx_album_photos_photo: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (33, 'add_albums')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v33: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v34(pool: &DbPool) -> Result<(), String> {
+    // Viewer-sized preview next to the grid thumbnail. Existing photos get one
+    // when regenerate_thumbnails runs.
+    let has_preview_path: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('photos') WHERE name = 'preview_path'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v34 failed checking preview_path column: {}", e))?;
+
+    if !has_preview_path {
+        sqlx::query("ALTER TABLE photos ADD COLUMN preview_path TEXT")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v34 failed adding preview_path column: {}", e))?;
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (34, 'add_photo_preview_path')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v34: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v35(pool: &DbPool) -> Result<(), String> {
+    // Photo publishing consent given by the athlete's guardians. Nobody has
+    // given consent until it is recorded.
+    let has_photo_consent: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('athletes') WHERE name = 'photo_consent'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v35 failed checking photo_consent column: {}", e))?;
+
+    if !has_photo_consent {
+        for statement in [
+            "ALTER TABLE athletes ADD COLUMN photo_consent TEXT NOT NULL DEFAULT 'none' CHECK (photo_consent IN ('none', 'internal', 'public'))",
+            "ALTER TABLE athletes ADD COLUMN photo_consent_date TEXT",
+            "ALTER TABLE athletes ADD COLUMN photo_consent_note TEXT",
+        ] {
+            sqlx::query(statement)
+                .execute(pool)
+                .await
+                .map_err(|e| format!("Migration v35 failed adding photo consent columns: {}", e))?;
+        }
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (35, 'add_athlete_photo_consent')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v35: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v36(pool: &DbPool) -> Result<(), String> {
+    // Medals created from a result's placement are kept in sync with it;
+    // medals added by hand are never touched by the derivation.
+    let has_derived: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('medals') WHERE name = 'derived'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Migration v36 failed checking derived column: {}", e))?;
+
+    if !has_derived {
+        sqlx::query("ALTER TABLE medals ADD COLUMN derived INTEGER NOT NULL DEFAULT 0")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Migration v36 failed adding derived column: {}", e))?;
+
+        // Existing medals matching their result's placement came from the derivation
+        sqlx::query(
+            r#"UPDATE medals SET derived = 1
+            WHERE result_id IS NOT NULL
+              AND type = (SELECT CASE r.placement WHEN 1 THEN 'gold' WHEN 2 THEN 'silver' WHEN 3 THEN 'bronze' END
+                          FROM results r WHERE r.id = medals.result_id)"#
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Migration v36 failed marking derived medals: {}", e))?;
+    }
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (36, 'add_medal_derived_flag')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v36: {}", e))?;
+
+    Ok(())
+}
+
+async fn run_migration_v37(pool: &DbPool) -> Result<(), String> {
+    // Medals are read from their own columns only; older medals linked to a
+    // result get the result's location and discipline stored once
+    sqlx::query(
+        r#"UPDATE medals SET
+            location = COALESCE(location, (SELECT r.location FROM results r WHERE r.id = medals.result_id)),
+            discipline_id = COALESCE(discipline_id, (SELECT r.discipline_id FROM results r WHERE r.id = medals.result_id))
+        WHERE result_id IS NOT NULL AND (location IS NULL OR discipline_id IS NULL)"#
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v37 failed filling medal details: {}", e))?;
+
+    sqlx::query(
+        r#"UPDATE medals SET discipline_name = (SELECT d.full_name FROM disciplines d WHERE d.id = medals.discipline_id)
+        WHERE discipline_name IS NULL AND discipline_id IS NOT NULL"#
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Migration v37 failed filling medal discipline names: {}", e))?;
+
+    sqlx::query("INSERT INTO _migrations (version, description) VALUES (37, 'store_medal_result_details')")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to record migration v37: {}", e))?;
+
+    Ok(())
+}
+
 pub async fn get_pool(app: &AppHandle) -> Result<DbPool, String> {
     let state = app.state::<AppDatabase>();
 
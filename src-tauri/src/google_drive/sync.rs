@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use std::fs;
 
+use crate::commands::photos::privacy::sanitize_image_metadata;
 use crate::google_drive::api::{
     download_file, get_or_create_loikka_folder, get_or_create_subfolder, get_subfolder_id,
     upload_file_to_drive,
@@ -8,7 +9,20 @@ use crate::google_drive::api::{
 use crate::google_drive::tokens::get_app_data_dir;
 use crate::google_drive::types::DriveFileList;
 
-pub async fn sync_database_to_drive(access_token: &str) -> Result<i32, String> {
+/// Photo bytes to upload. With `strip_metadata` (the photo privacy setting)
+/// the copy in Drive has no location or identifying EXIF, even when the local
+/// file still does.
+fn read_photo_for_upload(path: &std::path::Path, strip_metadata: bool) -> Result<Vec<u8>, String> {
+    let content = fs::read(path).map_err(|e| format!("Failed to read photo: {}", e))?;
+    if strip_metadata {
+        if let Some(sanitized) = sanitize_image_metadata(&content) {
+            return Ok(sanitized);
+        }
+    }
+    Ok(content)
+}
+
+pub async fn sync_database_to_drive(access_token: &str, strip_metadata: bool) -> Result<i32, String> {
     let folder_id = get_or_create_loikka_folder(access_token).await?;
     let app_data_dir = get_app_data_dir()?;
 
@@ -48,7 +62,7 @@ pub async fn sync_database_to_drive(access_token: &str) -> Result<i32, String> {
                     let path = entry.path();
                     if path.is_file() {
                         if let Some(file_name) = path.file_name() {
-                            let content = fs::read(&path).map_err(|e| format!("Failed to read photo: {}", e))?;
+                            let content = read_photo_for_upload(&path, strip_metadata)?;
                             let mime_type = get_mime_type(&path);
 
                             upload_file_to_drive(
@@ -232,6 +246,7 @@ pub fn list_local_photos() -> Result<Vec<crate::types::LocalPhoto>, String> {
 pub async fn sync_with_options(
     access_token: &str,
     options: &crate::types::SyncOptions,
+    strip_metadata: bool,
 ) -> Result<i32, String> {
     let folder_id = get_or_create_loikka_folder(access_token).await?;
     let app_data_dir = get_app_data_dir()?;
@@ -283,7 +298,7 @@ pub async fn sync_with_options(
 
                 if let Some(file_name) = path.file_name() {
                     let photos_folder_id = get_or_create_subfolder(access_token, &folder_id, &folder_name).await?;
-                    let content = fs::read(path).map_err(|e| format!("Failed to read photo: {}", e))?;
+                    let content = read_photo_for_upload(path, strip_metadata)?;
                     let mime_type = get_mime_type(path);
 
                     upload_file_to_drive(
@@ -313,7 +328,7 @@ pub async fn sync_with_options(
                     let path = entry.path();
                     if path.is_file() {
                         if let Some(file_name) = path.file_name() {
-                            let content = fs::read(&path).map_err(|e| format!("Failed to read photo: {}", e))?;
+                            let content = read_photo_for_upload(&path, strip_metadata)?;
                             let mime_type = get_mime_type(&path);
 
                             upload_file_to_drive(
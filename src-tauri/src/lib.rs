@@ -29,7 +29,12 @@ pub fn run() {
                 match init_database(&handle).await {
                     Ok(pool) => {
                         let state = handle.state::<AppDatabase>();
-                        *state.0.lock().await = Some(pool);
+                        *state.0.lock().await = Some(pool.clone());
+
+                        // Capture dates of photos imported before EXIF was read
+                        if let Err(e) = commands::photos::exif::backfill_exif_metadata(&pool).await {
+                            eprintln!("Failed to read photo EXIF metadata: {}", e);
+                        }
                     }
                     Err(e) => {
                         eprintln!("Failed to initialize database: {}", e);
@@ -45,6 +50,33 @@ pub fn run() {
             commands::create_athlete,
             commands::update_athlete,
             commands::delete_athlete,
+            commands::restore_athlete,
+            commands::purge_athlete,
+            commands::merge_athletes,
+            commands::set_athlete_photo_consent,
+            commands::get_clubs,
+            commands::create_club,
+            commands::update_club,
+            commands::delete_club,
+            commands::get_athlete_memberships,
+            commands::add_club_membership,
+            commands::update_club_membership,
+            commands::delete_club_membership,
+            commands::get_club_top_list,
+            commands::get_families,
+            commands::create_family,
+            commands::update_family,
+            commands::delete_family,
+            commands::set_athlete_family,
+            commands::get_family_athletes,
+            commands::get_family_competitions,
+            commands::get_guardians,
+            commands::get_athlete_guardians,
+            commands::create_guardian,
+            commands::update_guardian,
+            commands::delete_guardian,
+            commands::link_guardian,
+            commands::unlink_guardian,
             // Results
             commands::results::get_all_results,
             commands::results::get_results_by_athlete,
@@ -56,6 +88,12 @@ pub fn run() {
             commands::results::check_season_best,
             commands::results::get_athlete_medals,
             commands::results::create_medal,
+            commands::results::update_medal,
+            commands::results::delete_medal,
+            commands::results::get_medal_table,
+            // FinishLynx import
+            commands::lynx::preview_lynx_import,
+            commands::lynx::commit_lynx_import,
             // Competitions
             commands::get_all_competitions,
             commands::get_upcoming_competitions,
@@ -74,6 +112,8 @@ pub fn run() {
             commands::update_goal,
             commands::mark_goal_achieved,
             commands::delete_goal,
+            commands::get_goal_forecast,
+            commands::get_goal_forecasts,
             // Sync
             commands::export_data,
             commands::import_data,
@@ -98,10 +138,44 @@ pub fn run() {
             commands::delete_photos_bulk,
             commands::link_photos_to_competition,
             commands::link_photos_to_athlete,
+            commands::suggest_photo_competitions,
+            commands::rotate_photo,
+            commands::crop_photo,
+            commands::optimize_photo_library,
+            commands::find_similar_photos,
+            commands::scan_photo_storage,
+            commands::repair_photo_storage,
+            commands::regenerate_thumbnails,
+            commands::import_photo_folder,
+            commands::cancel_photo_import,
+            commands::export_photos,
+            commands::sanitize_photo_library,
+            commands::get_photo_consent_violations,
+            commands::get_photo_tags,
+            commands::tag_photo,
+            commands::untag_photo,
+            commands::update_photo_tag_caption,
+            commands::get_albums,
+            commands::get_album,
+            commands::create_album,
+            commands::update_album,
+            commands::delete_album,
+            commands::get_album_photos,
+            commands::add_photos_to_album,
+            commands::remove_photos_from_album,
+            commands::reorder_album_photos,
             commands::get_photo_url,
             commands::get_all_photos,
             commands::get_photo_years,
             commands::save_athlete_profile_photo,
+            // Reports
+            commands::get_competition_report,
+            commands::export_competition_report,
+            // Settings
+            commands::get_medal_settings,
+            commands::update_medal_settings,
+            commands::get_photo_settings,
+            commands::update_photo_settings,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -128,19 +202,73 @@ mod tests {
         UpdateCompetition::export_all().expect("Failed to export UpdateCompetition");
         CompetitionParticipant::export_all().expect("Failed to export CompetitionParticipant");
         CreateCompetitionParticipant::export_all().expect("Failed to export CreateCompetitionParticipant");
+        Club::export_all().expect("Failed to export Club");
+        CreateClub::export_all().expect("Failed to export CreateClub");
+        UpdateClub::export_all().expect("Failed to export UpdateClub");
+        ClubMembership::export_all().expect("Failed to export ClubMembership");
+        CreateClubMembership::export_all().expect("Failed to export CreateClubMembership");
+        UpdateClubMembership::export_all().expect("Failed to export UpdateClubMembership");
+        ClubTopListEntry::export_all().expect("Failed to export ClubTopListEntry");
+        ResultClub::export_all().expect("Failed to export ResultClub");
+        Family::export_all().expect("Failed to export Family");
+        CreateFamily::export_all().expect("Failed to export CreateFamily");
+        UpdateFamily::export_all().expect("Failed to export UpdateFamily");
+        Guardian::export_all().expect("Failed to export Guardian");
+        CreateGuardian::export_all().expect("Failed to export CreateGuardian");
+        UpdateGuardian::export_all().expect("Failed to export UpdateGuardian");
+        AthleteGuardian::export_all().expect("Failed to export AthleteGuardian");
+        FamilyCompetition::export_all().expect("Failed to export FamilyCompetition");
         Goal::export_all().expect("Failed to export Goal");
         CreateGoal::export_all().expect("Failed to export CreateGoal");
         UpdateGoal::export_all().expect("Failed to export UpdateGoal");
+        GoalForecast::export_all().expect("Failed to export GoalForecast");
         Medal::export_all().expect("Failed to export Medal");
+        UpdateMedal::export_all().expect("Failed to export UpdateMedal");
+        MedalTableRow::export_all().expect("Failed to export MedalTableRow");
+        MedalSettings::export_all().expect("Failed to export MedalSettings");
         AthleteStats::export_all().expect("Failed to export AthleteStats");
         AthleteWithStats::export_all().expect("Failed to export AthleteWithStats");
+        MergeAthletesSummary::export_all().expect("Failed to export MergeAthletesSummary");
         ExportData::export_all().expect("Failed to export ExportData");
         AuthStatus::export_all().expect("Failed to export AuthStatus");
         SyncResult::export_all().expect("Failed to export SyncResult");
         CloudBackup::export_all().expect("Failed to export CloudBackup");
         Photo::export_all().expect("Failed to export Photo");
+        PhotoFolderImport::export_all().expect("Failed to export PhotoFolderImport");
+        PhotoImportProgress::export_all().expect("Failed to export PhotoImportProgress");
+        PhotoFolderImportSummary::export_all().expect("Failed to export PhotoFolderImportSummary");
+        PhotoExport::export_all().expect("Failed to export PhotoExport");
+        PhotoExportSummary::export_all().expect("Failed to export PhotoExportSummary");
+        PhotoConsentViolation::export_all().expect("Failed to export PhotoConsentViolation");
+        PhotoSanitizeSummary::export_all().expect("Failed to export PhotoSanitizeSummary");
+        ThumbnailProgress::export_all().expect("Failed to export ThumbnailProgress");
+        ThumbnailRegenerationSummary::export_all().expect("Failed to export ThumbnailRegenerationSummary");
+        OrphanedPhotoFile::export_all().expect("Failed to export OrphanedPhotoFile");
+        MissingPhotoFile::export_all().expect("Failed to export MissingPhotoFile");
+        BrokenThumbnail::export_all().expect("Failed to export BrokenThumbnail");
+        PhotoStorageReport::export_all().expect("Failed to export PhotoStorageReport");
+        PhotoStorageRepairSummary::export_all().expect("Failed to export PhotoStorageRepairSummary");
+        Album::export_all().expect("Failed to export Album");
+        CreateAlbum::export_all().expect("Failed to export CreateAlbum");
+        UpdateAlbum::export_all().expect("Failed to export UpdateAlbum");
+        AlbumPhoto::export_all().expect("Failed to export AlbumPhoto");
+        PhotoTag::export_all().expect("Failed to export PhotoTag");
+        PhotoDuplicateGroup::export_all().expect("Failed to export PhotoDuplicateGroup");
+        PhotoCompetitionSuggestion::export_all().expect("Failed to export PhotoCompetitionSuggestion");
+        PhotoSettings::export_all().expect("Failed to export PhotoSettings");
+        PhotoOptimizationSummary::export_all().expect("Failed to export PhotoOptimizationSummary");
+        CropRect::export_all().expect("Failed to export CropRect");
         SyncOptions::export_all().expect("Failed to export SyncOptions");
         CloudPhoto::export_all().expect("Failed to export CloudPhoto");
         LocalPhoto::export_all().expect("Failed to export LocalPhoto");
+        CompetitionReport::export_all().expect("Failed to export CompetitionReport");
+        CompetitionReportAthlete::export_all().expect("Failed to export CompetitionReportAthlete");
+        CompetitionReportResult::export_all().expect("Failed to export CompetitionReportResult");
+        CompetitionReportDiscipline::export_all().expect("Failed to export CompetitionReportDiscipline");
+        LynxImportPreview::export_all().expect("Failed to export LynxImportPreview");
+        LynxPreviewRow::export_all().expect("Failed to export LynxPreviewRow");
+        LynxImportRow::export_all().expect("Failed to export LynxImportRow");
+        CommitLynxImport::export_all().expect("Failed to export CommitLynxImport");
+        LynxImportSummary::export_all().expect("Failed to export LynxImportSummary");
     }
 }
@@ -19,9 +19,15 @@ macro_rules! athlete_from_row {
             first_name: $row.get("first_name"),
             last_name: $row.get("last_name"),
             birth_year: $row.get("birth_year"),
+            birth_date: $row.get("birth_date"),
             gender: $row.get("gender"),
             club_name: $row.get("club_name"),
             photo_path: $row.get("photo_path"),
+            archived_at: $row.get("archived_at"),
+            family_id: $row.get("family_id"),
+            photo_consent: $row.get("photo_consent"),
+            photo_consent_date: $row.get("photo_consent_date"),
+            photo_consent_note: $row.get("photo_consent_note"),
             created_at: $row.get("created_at"),
             updated_at: $row.get("updated_at"),
         }
@@ -41,6 +47,7 @@ macro_rules! result_from_row {
             result_type: $row.get("type"),
             competition_name: $row.get("competition_name"),
             competition_level: $row.get("competition_level"),
+            custom_level_name: $row.get("custom_level_name"),
             location: $row.get("location"),
             placement: $row.get("placement"),
             notes: $row.get("notes"),
@@ -52,6 +59,9 @@ macro_rules! result_from_row {
             equipment_weight: $row.get("equipment_weight"),
             hurdle_height: $row.get("hurdle_height"),
             hurdle_spacing: $row.get("hurdle_spacing"),
+            sub_results: $row.get("sub_results"),
+            combined_event_id: $row.get("combined_event_id"),
+            heat: $row.get("heat"),
             created_at: $row.get("created_at"),
         }
     };
@@ -85,6 +95,7 @@ macro_rules! competition_from_row {
             location: $row.get("location"),
             address: $row.get("address"),
             level: $row.get("level"),
+            custom_level_name: $row.get("custom_level_name"),
             notes: $row.get("notes"),
             reminder_enabled: $row.get::<i32, _>("reminder_enabled") == 1,
             reminder_days_before: $row.get("reminder_days_before"),
@@ -101,10 +112,14 @@ macro_rules! goal_from_row {
             id: $row.get("id"),
             athlete_id: $row.get("athlete_id"),
             discipline_id: $row.get("discipline_id"),
+            goal_type: $row.get("goal_type"),
             target_value: $row.get("target_value"),
+            skill_mark: $row.get("skill_mark"),
+            season: $row.get("season"),
             target_date: $row.get("target_date"),
             status: $row.get("status"),
             achieved_at: $row.get("achieved_at"),
+            achieved_result_id: $row.get("achieved_result_id"),
             created_at: $row.get("created_at"),
         }
     };
@@ -120,11 +135,14 @@ macro_rules! photo_from_row {
             entity_id: $row.get("entity_id"),
             file_path: $row.get("file_path"),
             thumbnail_path: $row.get("thumbnail_path"),
+            preview_path: $row.get("preview_path"),
             original_name: $row.get("original_name"),
             width: $row.get("width"),
             height: $row.get("height"),
             size_bytes: $row.get("size_bytes"),
             event_name: $row.get("event_name"),
+            taken_at: $row.get("taken_at"),
+            orientation: $row.get("orientation"),
             created_at: $row.get("created_at"),
         }
     };
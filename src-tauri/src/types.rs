@@ -10,13 +10,24 @@ pub struct Athlete {
     pub first_name: String,
     pub last_name: String,
     pub birth_year: i32,
+    pub birth_date: Option<String>, // YYYY-MM-DD when known; birth_year is derived from it
     pub gender: String, // "T" = Tytöt (girls), "P" = Pojat (boys)
     pub club_name: Option<String>,
     pub photo_path: Option<String>,
+    pub archived_at: Option<String>, // Set when archived; archived athletes are hidden from lists
+    pub family_id: Option<i64>,
+    #[serde(default = "default_photo_consent")]
+    pub photo_consent: String, // "none", "internal" (club use only) or "public"
+    pub photo_consent_date: Option<String>,
+    pub photo_consent_note: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+fn default_photo_consent() -> String {
+    "none".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
 #[serde(rename_all = "camelCase")]
@@ -24,6 +35,7 @@ pub struct CreateAthlete {
     pub first_name: String,
     pub last_name: String,
     pub birth_year: i32,
+    pub birth_date: Option<String>,
     pub gender: String,
     pub club_name: Option<String>,
     pub photo_path: Option<String>,
@@ -36,6 +48,9 @@ pub struct UpdateAthlete {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub birth_year: Option<i32>,
+    /// Leaving this out keeps the stored birth date unless `birth_year` moves away
+    /// from it; an empty string clears it
+    pub birth_date: Option<String>,
     pub gender: Option<String>,
     pub club_name: Option<String>,
     pub photo_path: Option<String>,
@@ -83,6 +98,7 @@ pub struct Result {
     pub hurdle_spacing: Option<f64>,
     pub sub_results: Option<String>, // JSON string for combined events (moniottelu) - legacy, will be removed
     pub combined_event_id: Option<i64>, // ID of parent combined event result (for sub-results)
+    pub heat: Option<String>, // Heat (erä) from timing system import
     pub created_at: String,
 }
 
@@ -110,6 +126,7 @@ pub struct CreateResult {
     pub is_national_record: Option<bool>,
     pub sub_results: Option<String>, // JSON string for combined events (moniottelu) - legacy
     pub combined_event_id: Option<i64>, // ID of parent combined event result (for sub-results)
+    pub heat: Option<String>, // Heat (erä) from timing system import
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -136,6 +153,8 @@ pub struct UpdateResult {
     pub is_national_record: Option<bool>,
     pub sub_results: Option<String>, // JSON string for combined events (moniottelu) - legacy
     pub combined_event_id: Option<i64>, // ID of parent combined event result (for sub-results)
+    /// Heat (erä) from timing system import. Leaving this out keeps the stored heat; an empty string clears it
+    pub heat: Option<String>,
 }
 
 // Competition types
@@ -209,6 +228,171 @@ pub struct CreateCompetitionParticipant {
     pub disciplines_planned: Option<Vec<i64>>,
 }
 
+// Club types
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct Club {
+    pub id: i64,
+    pub name: String,
+    pub abbreviation: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateClub {
+    pub name: String,
+    pub abbreviation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateClub {
+    pub name: Option<String>,
+    pub abbreviation: Option<String>,
+}
+
+// Period an athlete represented a club; open-ended dates mean "since always" / "still"
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClubMembership {
+    pub id: i64,
+    pub athlete_id: i64,
+    pub club_id: i64,
+    pub club_name: String,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateClubMembership {
+    pub athlete_id: i64,
+    pub club_id: i64,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateClubMembership {
+    pub club_id: Option<i64>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+// Best result of an athlete credited to a club (by membership on the result date)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ClubTopListEntry {
+    pub rank: i32,
+    pub athlete_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub result_id: i64,
+    pub value: f64,
+    pub date: String,
+    pub competition_name: Option<String>,
+    pub equipment_weight: Option<f64>, // Implement the list is ranked for (throws)
+    pub hurdle_height: Option<i32>, // Implement the list is ranked for (hurdles)
+}
+
+// Club a result is credited to (exports)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ResultClub {
+    pub result_id: i64,
+    pub club_id: i64,
+}
+
+// Family and guardian types
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct Family {
+    pub id: i64,
+    pub name: String,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFamily {
+    pub name: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFamily {
+    pub name: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct Guardian {
+    pub id: i64,
+    pub name: String,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub relationship: Option<String>, // e.g. "äiti", "isä", "huoltaja"
+    pub family_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGuardian {
+    pub name: String,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub relationship: Option<String>,
+    pub family_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGuardian {
+    pub name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub relationship: Option<String>,
+    pub family_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct AthleteGuardian {
+    pub athlete_id: i64,
+    pub guardian_id: i64,
+}
+
+// A competition with the family members taking part in it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct FamilyCompetition {
+    pub competition: Competition,
+    pub athlete_ids: Vec<i64>,
+}
+
 // Goal types
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -216,11 +400,16 @@ pub struct CreateCompetitionParticipant {
 pub struct Goal {
     pub id: i64,
     pub athlete_id: i64,
-    pub discipline_id: i64,
-    pub target_value: f64,
+    pub discipline_id: Option<i64>, // Required for value, skill_mark and improvement_percent goals
+    #[serde(default = "default_goal_type")]
+    pub goal_type: String, // "value", "skill_mark", "competition_count", "pb_count", "improvement_percent"
+    pub target_value: f64, // Result value, count or percent depending on goal_type (unused for skill_mark goals)
+    pub skill_mark: Option<String>, // "A", "B" or "C" for skill_mark goals
+    pub season: Option<i32>, // Season year for count and improvement goals
     pub target_date: Option<String>,
     pub status: String,
     pub achieved_at: Option<String>,
+    pub achieved_result_id: Option<i64>, // Result that met the target (None if marked by hand)
     pub created_at: String,
 }
 
@@ -229,9 +418,12 @@ pub struct Goal {
 #[serde(rename_all = "camelCase")]
 pub struct CreateGoal {
     pub athlete_id: i64,
-    pub discipline_id: i64,
+    pub discipline_id: Option<i64>,
+    pub goal_type: Option<String>, // Defaults to "value"
     pub target_value: f64,
     pub target_date: Option<String>,
+    pub skill_mark: Option<String>,
+    pub season: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -243,6 +435,24 @@ pub struct UpdateGoal {
     pub status: Option<String>,
 }
 
+fn default_goal_type() -> String {
+    "value".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct GoalForecast {
+    pub goal_id: i64,
+    pub current_best: Option<f64>, // Best result, or current count for count goals
+    pub target: Option<f64>, // Effective target (e.g. computed from last season's SB for improvement goals)
+    pub gap: Option<f64>, // Remaining distance to target (0 when met), same unit as target
+    pub projected_date: Option<String>, // When the trend reaches the target
+    pub assessment: String, // "achieved", "on_track", "at_risk", "unrealistic", "insufficient_data"
+    pub days_remaining: Option<i64>, // Days until target_date (negative if passed)
+    pub result_count: i32, // Results used for the trend fit
+}
+
 // Medal types
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -259,9 +469,157 @@ pub struct Medal {
     pub discipline_id: Option<i64>,
     pub discipline_name: Option<String>,
     pub date: String,
+    /// Created from the linked result's placement and kept in sync with it
+    #[serde(default)]
+    pub derived: bool,
     pub created_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMedal {
+    #[serde(rename = "type")]
+    pub medal_type: Option<String>,
+    pub competition_name: Option<String>,
+    pub competition_id: Option<i64>, // Replaced as sent: leaving it out clears it
+    pub location: Option<String>, // Replaced as sent: leaving it out clears it
+    pub discipline_id: Option<i64>, // Replaced as sent: leaving it out clears it
+    pub date: Option<String>,
+}
+
+// One row of a medal table: medal counts for a season, competition level and discipline
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MedalTableRow {
+    pub season: i32,
+    pub competition_level: Option<String>,
+    pub discipline_id: Option<i64>,
+    pub discipline_name: Option<String>,
+    pub gold: i32,
+    pub silver: i32,
+    pub bronze: i32,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MedalSettings {
+    pub excluded_levels: Vec<String>, // Competition levels that never create medals automatically
+}
+
+// Competition report types
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitionReport {
+    pub competition: Competition,
+    pub athletes: Vec<CompetitionReportAthlete>,
+    pub generated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitionReportAthlete {
+    pub athlete_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub results: Vec<CompetitionReportResult>,
+    pub medals: Vec<Medal>,
+    pub planned_without_result: Vec<CompetitionReportDiscipline>,
+    pub personal_best_count: i32,
+    pub season_best_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitionReportResult {
+    pub result: Result,
+    pub discipline: CompetitionReportDiscipline,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitionReportDiscipline {
+    pub id: i64,
+    pub name: String,
+    pub full_name: String,
+    pub category: String,
+    pub unit: String,
+}
+
+// FinishLynx import types
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct LynxImportPreview {
+    pub event_number: String,
+    pub round: String,
+    pub heat: String,
+    pub event_name: String,
+    pub wind: Option<f64>,
+    pub suggested_discipline_id: Option<i64>,
+    pub rows: Vec<LynxPreviewRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct LynxPreviewRow {
+    pub place: Option<i32>,
+    pub status: String, // "valid", "dns", "dnf", "dq"
+    pub bib: Option<String>,
+    pub lane: Option<i32>,
+    pub first_name: String,
+    pub last_name: String,
+    pub affiliation: Option<String>,
+    pub value: Option<f64>,
+    pub athlete_id: Option<i64>,
+    pub match_type: String, // "mapping", "name_club", "name", "none"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct LynxImportRow {
+    pub athlete_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub affiliation: Option<String>,
+    pub place: Option<i32>,
+    pub status: String,
+    pub value: Option<f64>,
+    pub remember_mapping: Option<bool>, // Save a manual mapping for future imports
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CommitLynxImport {
+    pub discipline_id: i64,
+    pub date: String,
+    pub competition_name: Option<String>,
+    pub competition_level: Option<String>,
+    pub custom_level_name: Option<String>,
+    pub location: Option<String>,
+    pub heat: Option<String>,
+    pub wind: Option<f64>,
+    pub rows: Vec<LynxImportRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct LynxImportSummary {
+    pub created: Vec<Result>,
+    pub skipped_duplicates: i32,
+}
+
 // Athlete stats
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -285,6 +643,25 @@ pub struct AthleteWithStats {
     pub stats: AthleteStats,
 }
 
+// What merge_athletes moved from the duplicate to the kept athlete
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MergeAthletesSummary {
+    pub kept_athlete_id: i64,
+    pub merged_athlete_id: i64,
+    pub results_moved: i64,
+    pub goals_moved: i64,
+    pub medals_moved: i64,
+    pub participants_moved: i64,
+    pub participants_combined: i64, // Same competition for both: planned disciplines were combined
+    pub notes_moved: i64,
+    pub photos_moved: i64,
+    pub guardians_moved: i64,
+    pub memberships_moved: i64, // Periods overlapping the kept athlete's memberships are dropped
+    pub disciplines_recalculated: i64,
+}
+
 // Export data structure
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -297,6 +674,22 @@ pub struct ExportData {
     pub competitions: Vec<Competition>,
     pub goals: Vec<Goal>,
     pub medals: Vec<Medal>,
+    #[serde(default)]
+    pub families: Vec<Family>,
+    #[serde(default)]
+    pub guardians: Vec<Guardian>,
+    #[serde(default)]
+    pub athlete_guardians: Vec<AthleteGuardian>,
+    #[serde(default)]
+    pub clubs: Vec<Club>,
+    #[serde(default)]
+    pub club_memberships: Vec<ClubMembership>,
+    #[serde(default)]
+    pub result_clubs: Vec<ResultClub>, // Derived from memberships; not imported
+    #[serde(default)]
+    pub albums: Vec<Album>,
+    #[serde(default)]
+    pub album_photos: Vec<AlbumPhoto>, // Photo files are synced separately
 }
 
 // Google Drive sync types
@@ -338,15 +731,296 @@ pub struct Photo {
     pub entity_type: String,
     pub entity_id: i64,
     pub file_path: String,
-    pub thumbnail_path: Option<String>,
+    pub thumbnail_path: Option<String>, // Grid size, in the thumbnail cache
+    pub preview_path: Option<String>, // Viewer size, in the thumbnail cache
     pub original_name: String,
     pub width: Option<i32>,
     pub height: Option<i32>,
     pub size_bytes: i64,
     pub event_name: Option<String>,
+    pub taken_at: Option<String>, // Capture time from EXIF
+    pub orientation: Option<i32>, // EXIF orientation (1-8)
     pub created_at: String,
 }
 
+// Athlete, competition or result shown in a photo
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoTag {
+    pub id: i64,
+    pub photo_id: i64,
+    pub entity_type: String, // "athletes", "competitions" or "results"
+    pub entity_id: i64,
+    pub caption: Option<String>,
+    pub entity_name: Option<String>, // Athlete name (also for results) or competition name
+    pub created_at: String,
+}
+
+// Competition held on the day a photo was taken
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoCompetitionSuggestion {
+    pub photo_id: i64,
+    pub competition: Competition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoSettings {
+    pub normalize_orientation: bool, // Rewrite imported photos upright instead of only fixing thumbnails
+    pub optimize_storage: bool, // Re-encode photos at import using the limits below
+    pub max_long_edge: u32, // Pixels; larger photos are scaled down
+    pub jpeg_quality: u8, // 1-100
+    pub keep_original: bool, // Keep the untouched file in photos/originals
+    #[serde(default)]
+    pub strip_metadata: bool, // Remove GPS and identifying EXIF from stored photos and Drive uploads
+}
+
+// Outcome of re-encoding the photo library
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoOptimizationSummary {
+    pub processed: i64,
+    pub skipped: i64, // Already small enough or not re-encodable (GIF)
+    pub failed: i64,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+    pub bytes_saved: i64,
+}
+
+// Photos that look like the same picture, for review
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoDuplicateGroup {
+    pub exact: bool, // All photos have identical file contents
+    pub photos: Vec<Photo>,
+}
+
+// Photo album types
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct Album {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_photo_id: Option<i64>, // Chosen cover; the first photo is shown when not set
+    #[serde(default)]
+    pub cover_thumbnail_path: Option<String>,
+    #[serde(default)]
+    pub photo_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAlbum {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAlbum {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub cover_photo_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumPhoto {
+    pub album_id: i64,
+    pub photo_id: i64,
+    pub position: i64,
+}
+
+// Import of a folder of photos. Photos go to the given entity; with
+// `auto_link` they are also tagged with the competitions held on the day they
+// were taken, and without an entity they are stored for those competitions.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoFolderImport {
+    pub folder_path: String,
+    #[serde(default)]
+    pub recursive: bool,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    #[serde(default)]
+    pub auto_link: bool,
+    pub event_name: Option<String>,
+    pub on_duplicate: Option<String>, // "reject" (default), "link" or "allow"
+}
+
+// Emitted as "photo-import-progress" after each file
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoImportProgress {
+    pub file_path: String,
+    pub status: String, // "imported", "linked", "duplicate", "unmatched" or "failed"
+    pub photo_id: Option<i64>,
+    pub error: Option<String>,
+    pub done: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoFolderImportSummary {
+    pub total: i64,
+    pub imported: i64,
+    pub linked: i64,
+    pub duplicates: i64,
+    pub unmatched: i64, // No competition on the capture date
+    pub failed: i64,
+    pub cancelled: bool,
+    pub photo_ids: Vec<i64>,
+}
+
+// Zip export of photos. `scope` is "athlete", "competition", "album" or
+// "year", and `id` is the entity's id or the year.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoExport {
+    pub scope: String,
+    pub id: i64,
+    pub destination_path: String,
+    #[serde(default)]
+    pub strip_gps: bool, // Remove location and identifying metadata (always on when PhotoSettings.strip_metadata is)
+    pub audience: Option<String>, // "public" (default) or "internal"
+    pub on_consent_violation: Option<String>, // "exclude" (default) or "flag"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoExportSummary {
+    pub path: String,
+    pub photo_count: i64,
+    pub size_bytes: i64,
+    pub gps_stripped: i64, // Photos whose metadata was stripped
+    pub missing_files: i64,
+    pub excluded_for_consent: i64,
+    pub flagged_for_consent: i64, // Exported but listed as lacking consent in the sidecars
+}
+
+// Athlete shown in a photo without photo consent for an audience
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoConsentViolation {
+    pub photo_id: i64,
+    pub file_path: String,
+    pub thumbnail_path: Option<String>,
+    pub athlete_id: i64,
+    pub athlete_name: String,
+    pub photo_consent: String,
+}
+
+// Outcome of sanitize_photo_library
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoSanitizeSummary {
+    pub processed: i64, // Distinct photo files
+    pub sanitized: i64, // Had metadata removed
+    pub failed: i64,
+}
+
+// Progress of regenerate_thumbnails, emitted as "photo-thumbnails-progress"
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailProgress {
+    pub done: i64,
+    pub total: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailRegenerationSummary {
+    pub total: i64, // Distinct photo files
+    pub generated: i64,
+    pub failed: i64,
+}
+
+// Photo storage check: files on disk compared with the database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedPhotoFile {
+    pub path: String,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct MissingPhotoFile {
+    pub photo_id: Option<i64>, // Gallery photo
+    pub athlete_id: Option<i64>, // Profile photo
+    pub file_path: String,
+    pub relink_path: Option<String>, // Existing file that can take its place
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenThumbnail {
+    pub photo_id: i64,
+    pub thumbnail_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoStorageReport {
+    pub orphaned_files: Vec<OrphanedPhotoFile>,
+    pub orphaned_bytes: i64,
+    pub missing_files: Vec<MissingPhotoFile>,
+    pub broken_thumbnails: Vec<BrokenThumbnail>,
+    pub empty_directories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoStorageRepairSummary {
+    pub files_deleted: i64,
+    pub bytes_freed: i64,
+    pub files_relinked: i64,
+    pub thumbnails_regenerated: i64,
+    pub rows_removed: i64, // Photos and profile photo links whose file is gone
+    pub directories_removed: i64,
+    pub failed: i64,
+}
+
+// Crop rectangle in source image pixels
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/generated/")]
+#[serde(rename_all = "camelCase")]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
 // Sync options types
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/generated/")]
@@ -107,6 +107,12 @@ export function AthleteForm({ athlete, onSave, onCancel, disabled = false }: Ath
       firstName: firstName.trim(),
       lastName: lastName.trim(),
       birthYear: birthYear as number,
+      // Keep a known birth date only while it matches the birth year; "" clears it
+      birthDate: athlete?.birthDate
+        ? athlete.birthDate.startsWith(`${birthYear}-`)
+          ? athlete.birthDate
+          : ""
+        : undefined,
       gender: "T", // Always female athletes
       clubName: clubName.trim() || undefined,
       // For new athletes, pass the pending source path; for existing, pass the saved path
@@ -337,7 +337,7 @@ export function CompetitionForm({
 
                   {/* Discipline selector - only shown when athlete is selected */}
                   {isSelected && (() => {
-                    const athleteAgeDisciplines = getDisciplinesForAthlete(athlete.birthYear, date);
+                    const athleteAgeDisciplines = getDisciplinesForAthlete(athlete.birthYear, date, athlete.birthDate);
                     const athleteAgeDisciplineIds = new Set(athleteAgeDisciplines.map(d => d.id));
                     const showAll = showAllDisciplines.has(athlete.id);
                     const allDisciplines = getDisciplinesByCategory();
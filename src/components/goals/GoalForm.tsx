@@ -50,6 +50,11 @@ export function GoalForm({ goal, athleteId, onSave, onCancel }: GoalFormProps) {
     return athleteData?.athlete.birthYear;
   }, [selectedAthleteId, athletes]);
 
+  const selectedAthleteBirthDate = useMemo(() => {
+    if (!selectedAthleteId) return undefined;
+    return athletes.find((a) => a.athlete.id === selectedAthleteId)?.athlete.birthDate;
+  }, [selectedAthleteId, athletes]);
+
   // Athlete options for FilterSelect
   const athleteOptions: FilterOption[] = useMemo(() => [
     { value: "", label: "Valitse urheilija" },
@@ -155,6 +160,7 @@ export function GoalForm({ goal, athleteId, onSave, onCancel }: GoalFormProps) {
           value={disciplineId}
           onChange={setDisciplineId}
           birthYear={selectedAthleteBirthYear}
+          birthDate={selectedAthleteBirthDate}
           required
           className={errors.disciplineId ? "border-error" : ""}
         />
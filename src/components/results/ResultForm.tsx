@@ -104,6 +104,11 @@ export function ResultForm({ athleteId, onSave, onCancel }: ResultFormProps) {
     return athleteData?.athlete.birthYear;
   }, [selectedAthleteId, athletes]);
 
+  const selectedAthleteBirthDate = useMemo(() => {
+    if (!selectedAthleteId) return undefined;
+    return athletes.find((a) => a.athlete.id === selectedAthleteId)?.athlete.birthDate;
+  }, [selectedAthleteId, athletes]);
+
   // Use shared discipline fields hook
   const {
     selectedDiscipline,
@@ -441,6 +446,7 @@ export function ResultForm({ athleteId, onSave, onCancel }: ResultFormProps) {
             value={disciplineId}
             onChange={setDisciplineId}
             birthYear={selectedAthleteBirthYear}
+            birthDate={selectedAthleteBirthDate}
             date={date}
             required
             className={errors.disciplineId ? "border-error" : ""}
@@ -17,6 +17,7 @@ interface DisciplineSelectProps {
   value: number | "";
   onChange: (value: number | "") => void;
   birthYear?: number;
+  birthDate?: string;
   date?: string;
   disabled?: boolean;
   className?: string;
@@ -27,6 +28,7 @@ export function DisciplineSelect({
   value,
   onChange,
   birthYear,
+  birthDate,
   date,
   disabled = false,
   className = "",
@@ -44,24 +46,24 @@ export function DisciplineSelect({
   // Get the age category label
   const ageCategoryLabel = useMemo(() => {
     if (!birthYear) return null;
-    const category = getAgeCategoryFromBirthYear(birthYear, date);
+    const category = getAgeCategoryFromBirthYear(birthYear, date, birthDate);
     return ageCategoryLabels[category];
-  }, [birthYear, date]);
+  }, [birthYear, birthDate, date]);
 
   // Get disciplines grouped by category
   const groupedDisciplines = useMemo(() => {
     if (!birthYear || showAll) {
       return getDisciplinesByCategory();
     }
-    return getDisciplinesByCategoryForAge(birthYear, date);
-  }, [birthYear, date, showAll]);
+    return getDisciplinesByCategoryForAge(birthYear, date, birthDate);
+  }, [birthYear, birthDate, date, showAll]);
 
   // Get available discipline IDs for highlighting
   const availableIds = useMemo(() => {
     if (!birthYear) return new Set<number>();
-    const ageDisciplines = getDisciplinesForAthlete(birthYear, date);
+    const ageDisciplines = getDisciplinesForAthlete(birthYear, date, birthDate);
     return new Set(ageDisciplines.map((d) => d.id));
-  }, [birthYear, date]);
+  }, [birthYear, birthDate, date]);
 
   // Build options sorted by category order
   const options = useMemo(() => {
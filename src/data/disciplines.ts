@@ -725,13 +725,23 @@ export function isVerticalJump(disciplineId: number): boolean {
 }
 
 // Get age category from birth year and optional date
-// If date is provided, calculates the age at that date
+// If date is provided, calculates the age at that date.
+// With a full birth date the exact age on that date is used.
 export function getAgeCategoryFromBirthYear(
   birthYear: number,
-  date?: string
+  date?: string,
+  birthDate?: string
 ): AgeCategory {
-  const targetYear = date ? new Date(date).getFullYear() : new Date().getFullYear();
-  const ageThisYear = targetYear - birthYear;
+  const target = date ? new Date(date) : new Date();
+  let ageThisYear = target.getFullYear() - birthYear;
+
+  if (birthDate) {
+    const born = new Date(birthDate);
+    const hadBirthday =
+      target.getMonth() > born.getMonth() ||
+      (target.getMonth() === born.getMonth() && target.getDate() >= born.getDate());
+    ageThisYear = target.getFullYear() - born.getFullYear() - (hadBirthday ? 0 : 1);
+  }
 
   // Children too young for official categories
   if (ageThisYear <= 3) return "T3";
@@ -762,18 +772,20 @@ export function getDisciplinesForAgeCategory(category: AgeCategory): Discipline[
 // Get disciplines for an athlete at a specific date
 export function getDisciplinesForAthlete(
   birthYear: number,
-  date?: string
+  date?: string,
+  birthDate?: string
 ): Discipline[] {
-  const category = getAgeCategoryFromBirthYear(birthYear, date);
+  const category = getAgeCategoryFromBirthYear(birthYear, date, birthDate);
   return getDisciplinesForAgeCategory(category);
 }
 
 // Get disciplines grouped by category for a specific age category
 export function getDisciplinesByCategoryForAge(
   birthYear: number,
-  date?: string
+  date?: string,
+  birthDate?: string
 ): Map<DisciplineCategory, Discipline[]> {
-  const ageDisciplines = getDisciplinesForAthlete(birthYear, date);
+  const ageDisciplines = getDisciplinesForAthlete(birthYear, date, birthDate);
   const grouped = new Map<DisciplineCategory, Discipline[]>();
 
   for (const discipline of ageDisciplines) {
@@ -789,9 +801,10 @@ export function getDisciplinesByCategoryForAge(
 export function isDisciplineAvailableForAge(
   disciplineId: number,
   birthYear: number,
-  date?: string
+  date?: string,
+  birthDate?: string
 ): boolean {
-  const category = getAgeCategoryFromBirthYear(birthYear, date);
+  const category = getAgeCategoryFromBirthYear(birthYear, date, birthDate);
   const ids = disciplineIdsByAgeCategory[category] || [];
   return ids.includes(disciplineId);
 }
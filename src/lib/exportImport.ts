@@ -2,8 +2,12 @@ import { save, open } from "@tauri-apps/plugin-dialog";
 import { writeTextFile, readTextFile } from "@tauri-apps/plugin-fs";
 import { invoke } from "@tauri-apps/api/core";
 
-export async function exportData(): Promise<boolean> {
-  const json = await invoke<string>("export_data");
+/**
+ * Export all data to a JSON file. With `excludeContacts` the guardians'
+ * phone numbers and email addresses are left out.
+ */
+export async function exportData(excludeContacts = false): Promise<boolean> {
+  const json = await invoke<string>("export_data", { excludeContacts });
 
   const filePath = await save({
     defaultPath: `loikka-backup-${new Date().toISOString().split("T")[0]}.json`,
@@ -1,18 +1,19 @@
 import { useState } from "react";
-import { Download, Upload, HelpCircle } from "lucide-react";
+import { Download, Upload, HelpCircle, Check } from "lucide-react";
 import { SettingsSection, GoogleDriveSettings, HelpPanel } from "../components/settings";
 import { toast } from "../components/ui";
 import { exportData, importData } from "../lib";
 
 export function Settings() {
   const [isExporting, setIsExporting] = useState(false);
+  const [excludeContacts, setExcludeContacts] = useState(false);
   const [isImporting, setIsImporting] = useState(false);
   const [isHelpOpen, setIsHelpOpen] = useState(false);
 
   const handleExport = async () => {
     setIsExporting(true);
     try {
-      const saved = await exportData();
+      const saved = await exportData(excludeContacts);
       if (saved) {
         toast.success("Tiedot viety onnistuneesti");
       }
@@ -62,6 +63,25 @@ export function Settings() {
               </div>
             </button>
 
+            <label className="flex items-center gap-2 px-1 text-caption text-muted-foreground cursor-pointer">
+              <input
+                type="checkbox"
+                checked={excludeContacts}
+                onChange={(e) => setExcludeContacts(e.target.checked)}
+                className="sr-only"
+              />
+              <div
+                className={`w-4 h-4 rounded flex items-center justify-center transition-colors ${
+                  excludeContacts
+                    ? "bg-primary text-primary-foreground"
+                    : "border border-border"
+                }`}
+              >
+                {excludeContacts && <Check size={10} />}
+              </div>
+              Jätä huoltajien puhelinnumerot ja sähköpostit pois
+            </label>
+
             <button
               onClick={handleImport}
               disabled={isImporting}
@@ -69,23 +69,11 @@ export const useAthleteStore = create<AthleteStore>((set, get) => ({
       // If there's a photo source path, save it and update the athlete
       if (athleteData.photoPath) {
         try {
-          // Use the new save_athlete_profile_photo command which returns just the path string
-          const savedPhotoPath = await invoke<string>("save_athlete_profile_photo", {
+          // Saves the avatar and updates the athlete's photo path
+          await invoke<string>("save_athlete_profile_photo", {
             sourcePath: athleteData.photoPath,
             athleteId: newAthlete.id,
           });
-
-          // Update athlete with the saved photo path
-          await invoke<Athlete>("update_athlete", {
-            id: newAthlete.id,
-            athlete: {
-              firstName: athleteData.firstName,
-              lastName: athleteData.lastName,
-              birthYear: athleteData.birthYear,
-              clubName: athleteData.clubName || null,
-              photoPath: savedPhotoPath,
-            },
-          });
         } catch (photoError) {
           console.error("Failed to save athlete photo:", photoError);
           // Continue even if photo save fails
@@ -112,6 +100,7 @@ export const useAthleteStore = create<AthleteStore>((set, get) => ({
           firstName: data.firstName,
           lastName: data.lastName,
           birthYear: data.birthYear,
+          birthDate: data.birthDate,
           clubName: data.clubName,
           photoPath: data.photoPath,
         },
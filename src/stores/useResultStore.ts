@@ -289,6 +289,7 @@ export const useResultStore = create<ResultStore>((set, get) => ({
           isNationalRecord: resultData.isNationalRecord,
           subResults: resultData.subResults || null,
           combinedEventId: resultData.combinedEventId ?? null,
+          heat: resultData.heat,
         },
       });
 
@@ -59,15 +59,22 @@ export type PhotoEntityType = "athletes" | "results" | "competitions";
 // Gender
 export type Gender = "T" | "P"; // T = Tytöt (girls), P = Pojat (boys)
 
+// Photo publishing consent: internal = club use only
+export type PhotoConsent = "none" | "internal" | "public";
+
 // Athlete
 export interface Athlete {
   id: number;
   firstName: string;
   lastName: string;
   birthYear: number;
+  birthDate?: string; // YYYY-MM-DD when known
   gender: Gender;
   clubName?: string;
   photoPath?: string;
+  photoConsent?: PhotoConsent;
+  photoConsentDate?: string; // YYYY-MM-DD
+  photoConsentNote?: string;
   createdAt: string;
   updatedAt: string;
 }
@@ -115,6 +122,7 @@ export interface Result {
   hurdleSpacing?: number; // Hurdle spacing in m (for hurdles)
   subResults?: string; // JSON string of SubResult[] for combined events (moniottelu) - legacy
   combinedEventId?: number; // ID of parent combined event result (for sub-results)
+  heat?: string; // Heat (erä) from timing system import; "" clears it on update
   createdAt: string;
 }
 
@@ -166,6 +174,7 @@ export interface Medal {
   disciplineId?: number;
   disciplineName?: string;
   date: string;
+  derived?: boolean; // Created from the result's placement
   createdAt: string;
 }
 
@@ -176,18 +185,45 @@ export interface Photo {
   entityId: number;
   filePath: string;
   thumbnailPath: string | null;
+  previewPath: string | null;
   originalName: string;
   width: number | null;
   height: number | null;
   sizeBytes: number;
   eventName?: string;
+  takenAt: string | null; // Capture time from EXIF
+  orientation: number | null; // EXIF orientation (1-8)
+  createdAt: string;
+}
+
+// Athlete, competition or result shown in a photo
+export interface PhotoTag {
+  id: number;
+  photoId: number;
+  entityType: PhotoEntityType;
+  entityId: number;
+  caption: string | null;
+  entityName: string | null;
   createdAt: string;
 }
 
+// Named photo album in manual order
+export interface Album {
+  id: number;
+  name: string;
+  description: string | null;
+  coverPhotoId: number | null; // Chosen cover; the first photo is shown when not set
+  coverThumbnailPath: string | null;
+  photoCount: number;
+  createdAt: string;
+  updatedAt: string;
+}
+
 // Photo with related entity details (from get_all_photos)
 export interface PhotoWithDetails extends Photo {
   athleteName: string | null;
   competitionName: string | null;
+  tags: PhotoTag[];
 }
 
 // Sync Status
@@ -206,7 +242,15 @@ export type UpdateResult = Partial<NewResult>;
 export type NewCompetition = Omit<Competition, "id" | "createdAt">;
 export type NewGoal = Omit<Goal, "id" | "createdAt" | "achievedAt">;
 export type NewMedal = Omit<Medal, "id" | "createdAt">;
-export type NewPhoto = Omit<Photo, "id" | "createdAt" | "thumbnailPath" | "width" | "height">;
+/**
+ * Medal edit. `type`, `competitionName` and `date` keep their stored value when
+ * left out, but `competitionId`, `location` and `disciplineId` are replaced as
+ * sent, so leaving one out clears it. Editing detaches a derived medal from its
+ * result.
+ */
+export type UpdateMedal = Partial<Pick<Medal, "type" | "competitionName" | "date">> &
+  Pick<Medal, "competitionId" | "location" | "disciplineId">;
+export type NewPhoto = Omit<Photo, "id" | "createdAt" | "thumbnailPath" | "previewPath" | "width" | "height">;
 
 // Competition with participants populated
 export interface CompetitionWithParticipants extends Competition {